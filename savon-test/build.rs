@@ -5,4 +5,8 @@ fn main() {
     //let s = savon::gen::gen_write("../assets/example.wsdl", env!("OUT_DIR")).unwrap();
     //let s = savon::gen::gen_write("../assets/example.wsdl", &out_dir).unwrap();
     let s = savon::gen::gen_write("./countrinfoservice.wsdl", &out_dir).unwrap();
+
+    let wsdl = savon::wsdl::parse(include_bytes!("../assets/substitutions.wsdl")).unwrap();
+    let code = savon::gen::gen(&wsdl).unwrap();
+    std::fs::write(format!("{}/substitutions.rs", out_dir), code).unwrap();
 }
@@ -5,6 +5,12 @@ mod soap {
     include!(concat!(env!("OUT_DIR"), "/example.rs"));
 }
 
+// substitution groups, only compiled
+#[allow(dead_code)]
+mod substitutions {
+    include!(concat!(env!("OUT_DIR"), "/substitutions.rs"));
+}
+
 #[tokio::main]
 async fn main() -> Result<(), savon::Error> {
     pretty_env_logger::init();
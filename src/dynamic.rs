@@ -0,0 +1,1043 @@
+//! Building SOAP messages from the parsed WSDL, without generated code.
+//!
+//! The functions borrow the `Wsdl` for the duration of the call only. A
+//! client shared between async tasks should keep the document in an
+//! `Arc<Wsdl>`, which is `Send` and `Sync`, rather than borrow it, so that
+//! it needs no lifetime parameter.
+
+use crate::gen::Decimal;
+use crate::ns::{SOAP_ENC_NS, XSD_NS, XSI_NS};
+use crate::rpser::TARGET_PREFIX;
+use crate::wsdl::{BodyEncoding, SimpleType, Type, Wsdl, WsdlError};
+use std::collections::HashMap;
+use xmltree::{Element, XMLNode};
+
+/// Builds the body of an operation's request from `(path, value)` pairs.
+///
+/// Paths are field names separated by dots, as in `customer.address.city`.
+/// Repeated fields take an index, as in `items[0].sku`, and their indices
+/// must be used in order. Paths that do not match the schema of the input
+/// message are rejected with `WsdlError::InvalidPath`.
+///
+/// Nillable fields without a value are written as `xsi:nil` elements.
+pub fn body_from_values(
+    wsdl: &Wsdl,
+    operation: &str,
+    values: &[(&str, &str)],
+) -> Result<Element, WsdlError> {
+    let message = wsdl
+        .operation(operation)
+        .ok_or_else(|| WsdlError::UnknownOperation(operation.to_string()))?
+        .input
+        .as_ref()
+        .and_then(|input| wsdl.messages.get(input))
+        .ok_or(WsdlError::ElementNotFound("message"))?;
+
+    let mut body = Element::new(&message.part_element);
+    for (path, value) in values {
+        insert_value(wsdl, &mut body, &message.part_element, path, value)?;
+    }
+    if insert_nils(wsdl, &mut body, &message.part_element) {
+        body.attributes
+            .insert("xmlns:xsi".to_string(), XSI_NS.to_string());
+    }
+
+    Ok(body)
+}
+
+/// Adds a nil element for each absent nillable field, returning whether any was added.
+fn insert_nils(wsdl: &Wsdl, element: &mut Element, type_name: &str) -> bool {
+    let c = match wsdl.resolve_alias(type_name) {
+        Some(Type::Complex(c)) => c,
+        _ => return false,
+    };
+
+    let mut fields = c.fields.iter().collect::<Vec<_>>();
+    fields.sort_by(|a, b| a.0.cmp(b.0));
+    let mut inserted = false;
+    for (name, (attributes, field_type)) in fields {
+        let present = element
+            .children
+            .iter_mut()
+            .filter_map(|c| c.as_mut_element())
+            .filter(|e| &e.name == name)
+            .collect::<Vec<_>>();
+
+        if present.is_empty() && attributes.nillable && !attributes.is_repeated() {
+            let mut nil = Element::new(name);
+            nil.attributes
+                .insert("xsi:nil".to_string(), "true".to_string());
+            element.children.push(XMLNode::Element(nil));
+            inserted = true;
+        } else if let SimpleType::Complex(t) = field_type {
+            for child in present {
+                inserted |= insert_nils(wsdl, child, t);
+            }
+        }
+    }
+
+    inserted
+}
+
+fn insert_value(
+    wsdl: &Wsdl,
+    body: &mut Element,
+    body_type: &str,
+    path: &str,
+    value: &str,
+) -> Result<(), WsdlError> {
+    let invalid = || WsdlError::InvalidPath(path.to_string());
+
+    let mut element = body;
+    let mut type_name = body_type;
+    let mut segments = path.split('.').peekable();
+    while let Some(segment) = segments.next() {
+        let (name, index) = parse_segment(segment).ok_or_else(invalid)?;
+        let (attributes, field_type) = match wsdl.resolve_alias(type_name) {
+            Some(Type::Complex(c)) => c.fields.get(name).ok_or_else(invalid)?,
+            _ => return Err(invalid()),
+        };
+        if index.is_some() && !attributes.is_repeated() {
+            return Err(invalid());
+        }
+
+        let position = child_position(element, name, index.unwrap_or(0)).ok_or_else(invalid)?;
+        element = element.children[position].as_mut_element().unwrap();
+
+        let complex = match field_type {
+            SimpleType::Complex(t) => match wsdl.resolve_alias(t) {
+                Some(Type::Complex(_)) => Some(t),
+                _ => None,
+            },
+            _ => None,
+        };
+        match (segments.peek(), complex) {
+            (Some(_), Some(t)) => type_name = t,
+            (None, None) => element.children = vec![XMLNode::Text(value.to_string())],
+            _ => return Err(invalid()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the enumeration fields of a response element of type `type_name`.
+///
+/// Returns the path and value of each enumeration field, in document order,
+/// with paths written as for `body_from_values`. A value that is not a member
+/// of its enumeration is rejected with `WsdlError::NotInEnumeration`.
+pub fn enumeration_values(
+    wsdl: &Wsdl,
+    type_name: &str,
+    element: &Element,
+) -> Result<Vec<(String, String)>, WsdlError> {
+    let mut values = Vec::new();
+    collect_enumeration_values(wsdl, type_name, element, "", &mut values)?;
+    Ok(values)
+}
+
+fn collect_enumeration_values(
+    wsdl: &Wsdl,
+    type_name: &str,
+    element: &Element,
+    path: &str,
+    values: &mut Vec<(String, String)>,
+) -> Result<(), WsdlError> {
+    let c = match wsdl.resolve_alias(type_name) {
+        Some(Type::Complex(c)) => c,
+        _ => return Ok(()),
+    };
+
+    let mut seen = HashMap::new();
+    for child in element.children.iter().filter_map(|c| c.as_element()) {
+        let (attributes, field_type) = match c.fields.get(&child.name) {
+            Some(field) => field,
+            None => continue,
+        };
+        let index = seen.entry(child.name.as_str()).or_insert(0);
+        let child_path = field_path(path, &child.name, attributes.is_repeated(), *index);
+        *index += 1;
+
+        let field_type = match field_type {
+            SimpleType::Complex(t) => t,
+            _ => continue,
+        };
+        match wsdl.types.get(field_type) {
+            Some(Type::Enumeration(members)) => {
+                let value = child.get_text().unwrap_or_default().trim().to_string();
+                if !members.contains(&value) {
+                    return Err(WsdlError::NotInEnumeration {
+                        path: child_path,
+                        value,
+                    });
+                }
+                values.push((child_path, value));
+            }
+            _ => collect_enumeration_values(wsdl, field_type, child, &child_path, values)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Path of a field below `path`, in the syntax of `body_from_values`.
+fn field_path(path: &str, name: &str, repeated: bool, index: usize) -> String {
+    match (path.is_empty(), repeated) {
+        (true, false) => name.to_string(),
+        (true, true) => format!("{}[{}]", name, index),
+        (false, false) => format!("{}.{}", path, name),
+        (false, true) => format!("{}.{}[{}]", path, name, index),
+    }
+}
+
+/// Value of a document breaking a restriction of its schema.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// decimal with more digits than allowed by `xs:totalDigits` or `xs:fractionDigits`
+    DigitConstraintViolated {
+        field: String,
+        total_digits: Option<u32>,
+        fraction_digits: Option<u32>,
+        actual_value: String,
+    },
+    /// value of a decimal field that is not a decimal number
+    NotADecimal { field: String, actual_value: String },
+    /// `xsi:nil` value of a field with a `fixed` value, which can't be nil
+    NilFixedField { field: String, fixed: String },
+}
+
+/// Checks the values of `element`, of type `type_name`, against the
+/// restrictions of the schema.
+///
+/// Returns every violation, with fields named by their path as in
+/// `body_from_values`. Elements that are not fields of their type are not
+/// checked.
+pub fn validate_element(wsdl: &Wsdl, type_name: &str, element: &Element) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    collect_validation_errors(wsdl, type_name, element, "", &mut errors);
+    errors
+}
+
+fn collect_validation_errors(
+    wsdl: &Wsdl,
+    type_name: &str,
+    element: &Element,
+    path: &str,
+    errors: &mut Vec<ValidationError>,
+) {
+    let c = match wsdl.resolve_alias(type_name) {
+        Some(Type::Complex(c)) => c,
+        _ => return,
+    };
+
+    let mut seen = HashMap::new();
+    for child in element.children.iter().filter_map(|c| c.as_element()) {
+        let (attributes, field_type) = match c.fields.get(&child.name) {
+            Some(field) => field,
+            None => continue,
+        };
+        let index = seen.entry(child.name.as_str()).or_insert(0);
+        let child_path = field_path(path, &child.name, attributes.is_repeated(), *index);
+        *index += 1;
+
+        // parsed documents keep the local name of the attribute
+        let nil = ["nil", "xsi:nil"]
+            .iter()
+            .any(|name| matches!(child.attributes.get(*name), Some(v) if v == "true"));
+        if let (true, Some(fixed)) = (nil, &attributes.fixed) {
+            errors.push(ValidationError::NilFixedField {
+                field: child_path,
+                fixed: fixed.clone(),
+            });
+            continue;
+        }
+
+        let field_type = match field_type {
+            SimpleType::Complex(t) => t,
+            _ => continue,
+        };
+        match wsdl.types.get(field_type) {
+            Some(Type::Decimal {
+                total_digits,
+                fraction_digits,
+            }) => {
+                let value = child.get_text().unwrap_or_default().trim().to_string();
+                match value.parse::<Decimal>() {
+                    Ok(decimal) => {
+                        if decimal
+                            .check_digits(*total_digits, *fraction_digits)
+                            .is_err()
+                        {
+                            errors.push(ValidationError::DigitConstraintViolated {
+                                field: child_path,
+                                total_digits: *total_digits,
+                                fraction_digits: *fraction_digits,
+                                actual_value: value,
+                            });
+                        }
+                    }
+                    Err(_) => errors.push(ValidationError::NotADecimal {
+                        field: child_path,
+                        actual_value: value,
+                    }),
+                }
+            }
+            _ => collect_validation_errors(wsdl, field_type, child, &child_path, errors),
+        }
+    }
+}
+
+/// Parts of a request, placed in the SOAP envelope.
+#[derive(Debug, Clone, Default)]
+pub struct EnvelopeParts {
+    pub body: Vec<Element>,
+    pub headers: Vec<Element>,
+}
+
+/// Where the parts of an operation's request go, besides the SOAP body.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestLayout {
+    /// parts referenced by a `soap:header` and not listed by `soap:body`
+    pub header_parts: Vec<String>,
+    /// parts bound to neither the body nor a header, such as attachments
+    pub omitted_parts: Vec<String>,
+    /// order of the parts in the body, as listed by the `parameterOrder` of
+    /// the operation, or else as declared by the message
+    pub body_order: Vec<String>,
+}
+
+/// Reads where the parts of the request of `operation` go, as declared by
+/// `binding`.
+///
+/// When `soap:body` lists its parts, only those go in the body; parts
+/// referenced by a `soap:header` go in the headers. Generated clients are
+/// built from the same layout as `envelope_parts`.
+pub fn request_layout(
+    wsdl: &Wsdl,
+    binding: &str,
+    operation: &str,
+) -> Result<RequestLayout, WsdlError> {
+    let declaration = wsdl
+        .operation(operation)
+        .ok_or_else(|| WsdlError::UnknownOperation(operation.to_string()))?;
+    let message = declaration
+        .input
+        .as_deref()
+        .ok_or(WsdlError::ElementNotFound("input"))?;
+    let message_binding = wsdl
+        .bindings
+        .get(binding)
+        .ok_or(WsdlError::ElementNotFound("binding"))?
+        .operations
+        .iter()
+        .find(|(name, _)| wsdl.operation_key(name) == wsdl.operation_key(operation))
+        .and_then(|(_, o)| o.input.clone())
+        .unwrap_or_default();
+
+    let parts = wsdl.messages.get(message).map_or(&[][..], |m| &m.parts[..]);
+    let mut layout = RequestLayout {
+        body_order: match &declaration.parameter_order {
+            Some(order) => order.clone(),
+            None => parts.iter().map(|p| p.name.clone()).collect(),
+        },
+        ..Default::default()
+    };
+    for part in parts {
+        if message_binding.in_body(message, &part.name) {
+            continue;
+        } else if message_binding.in_header(message, &part.name) {
+            layout.header_parts.push(part.name.clone());
+        } else {
+            layout.omitted_parts.push(part.name.clone());
+        }
+    }
+
+    Ok(layout)
+}
+
+/// Places the parts of an operation's request in the SOAP body or headers,
+/// as declared by `binding`.
+///
+/// `parts` are `(part name, element)` pairs, placed as read by
+/// `request_layout`. Parts bound to neither the body nor a header are left
+/// out, while parts the message does not declare go in the body.
+///
+/// Body parts are sorted as listed by the `body_order` of the layout,
+/// followed by the unknown parts.
+pub fn envelope_parts(
+    wsdl: &Wsdl,
+    binding: &str,
+    operation: &str,
+    mut parts: Vec<(String, Element)>,
+) -> Result<EnvelopeParts, WsdlError> {
+    let layout = request_layout(wsdl, binding, operation)?;
+    let order = &layout.body_order;
+    parts.sort_by_key(|(name, _)| order.iter().position(|p| p == name).unwrap_or(order.len()));
+
+    let mut envelope = EnvelopeParts::default();
+    for (name, element) in parts {
+        if layout.header_parts.contains(&name) {
+            envelope.headers.push(element);
+        } else if !layout.omitted_parts.contains(&name) {
+            envelope.body.push(element);
+        }
+    }
+
+    Ok(envelope)
+}
+
+/// Writes `element`, of type `type_name`, as declared by the `encoding` of
+/// its message.
+///
+/// Literal parts are left as they are. With the SOAP 1.1 encoding rules,
+/// every element is annotated with its `xsi:type`, and complex values
+/// appearing more than once are written once, as `multiRef` elements of the
+/// body referenced by `href` from each accessor. Returns the elements of the
+/// body, `element` first. Other encoding styles are rejected with
+/// `WsdlError::UnsupportedFeature`.
+///
+/// Generated clients do not encode their requests, so `gen` rejects the
+/// WSDLs binding messages with `use="encoded"`.
+pub fn encode_element(
+    wsdl: &Wsdl,
+    encoding: &BodyEncoding,
+    type_name: &str,
+    element: &Element,
+) -> Result<Vec<Element>, WsdlError> {
+    match encoding {
+        BodyEncoding::Literal => return Ok(vec![element.clone()]),
+        BodyEncoding::Encoded(style) if style == SOAP_ENC_NS => {}
+        BodyEncoding::Encoded(style) => {
+            return Err(WsdlError::UnsupportedFeature(format!(
+                "encoding style {}",
+                style
+            )))
+        }
+    }
+
+    let mut root = element.clone();
+    annotate(wsdl, &mut root, &SimpleType::Complex(type_name.to_string()));
+
+    let mut values: Vec<(Element, usize)> = Vec::new();
+    count_values(&root, &mut values);
+    let shared = values
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(value, _)| value)
+        .collect::<Vec<_>>();
+
+    let mut body = vec![root];
+    for (i, value) in shared.iter().enumerate() {
+        let mut multi_ref = value.clone();
+        multi_ref
+            .attributes
+            .insert("id".to_string(), format!("id{}", i));
+        body.push(multi_ref);
+    }
+    for element in body.iter_mut() {
+        share_values(element, &shared);
+        for (name, value) in [
+            ("soap:encodingStyle", SOAP_ENC_NS),
+            ("xmlns:xsi", XSI_NS),
+            ("xmlns:xsd", XSD_NS),
+        ] {
+            element
+                .attributes
+                .insert(name.to_string(), value.to_string());
+        }
+    }
+
+    Ok(body)
+}
+
+/// Adds the `xsi:type` of `element` and of its fields.
+fn annotate(wsdl: &Wsdl, element: &mut Element, simple_type: &SimpleType) {
+    if element.attributes.contains_key("xsi:nil") {
+        return;
+    }
+    let mut type_name = match simple_type {
+        SimpleType::Complex(t) => t.as_str(),
+        primitive => {
+            let primitive = primitive.primitive_name().unwrap_or_default();
+            element
+                .attributes
+                .insert("xsi:type".to_string(), format!("xsd:{}", primitive));
+            return;
+        }
+    };
+    // elements declared with a type are written with that type
+    while let Some(Type::Alias(target)) = wsdl.types.get(type_name) {
+        type_name = target;
+    }
+
+    let xsi_type = match wsdl.types.get(type_name) {
+        Some(Type::Simple(s)) => return annotate(wsdl, element, s),
+        _ => format!("{}:{}", TARGET_PREFIX, type_name),
+    };
+    element.attributes.insert("xsi:type".to_string(), xsi_type);
+
+    if let Some(Type::Complex(c)) = wsdl.types.get(type_name) {
+        for child in element
+            .children
+            .iter_mut()
+            .filter_map(|c| c.as_mut_element())
+        {
+            if let Some((_, field_type)) = c.fields.get(&child.name) {
+                annotate(wsdl, child, field_type);
+            }
+        }
+    }
+}
+
+/// Complex value of `element`, independent of the name of its accessor.
+fn value_of(element: &Element) -> Option<Element> {
+    if !element.children.iter().any(|c| c.as_element().is_some()) {
+        return None;
+    }
+    let mut value = element.clone();
+    value.name = "multiRef".to_string();
+    value.prefix = None;
+    value.namespace = None;
+    Some(value)
+}
+
+/// Counts the complex values below `element`.
+fn count_values(element: &Element, values: &mut Vec<(Element, usize)>) {
+    for child in element.children.iter().filter_map(|c| c.as_element()) {
+        if let Some(value) = value_of(child) {
+            match values.iter_mut().find(|(v, _)| *v == value) {
+                Some((_, count)) => *count += 1,
+                None => values.push((value, 1)),
+            }
+        }
+        count_values(child, values);
+    }
+}
+
+/// Replaces the accessors of `shared` values below `element` with references.
+fn share_values(element: &mut Element, shared: &[Element]) {
+    for child in element
+        .children
+        .iter_mut()
+        .filter_map(|c| c.as_mut_element())
+    {
+        let position = value_of(child).and_then(|value| shared.iter().position(|s| *s == value));
+        match position {
+            Some(i) => {
+                child.children.clear();
+                child.attributes.clear();
+                child
+                    .attributes
+                    .insert("href".to_string(), format!("#id{}", i));
+            }
+            None => share_values(child, shared),
+        }
+    }
+}
+
+/// Splits `items[2]` into its name and index.
+fn parse_segment(segment: &str) -> Option<(&str, Option<usize>)> {
+    match segment.find('[') {
+        None if !segment.is_empty() => Some((segment, None)),
+        None => None,
+        Some(start) if segment.ends_with(']') => {
+            let index = segment[start + 1..segment.len() - 1].parse().ok()?;
+            Some((&segment[..start], Some(index)))
+        }
+        Some(_) => None,
+    }
+}
+
+/// Position of the `index`-th child named `name`, appending it if it is the next one.
+fn child_position(element: &mut Element, name: &str, index: usize) -> Option<usize> {
+    let positions = element
+        .children
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.as_element().map(|e| e.name == name).unwrap_or(false))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    match positions.get(index) {
+        Some(position) => Some(*position),
+        None if index == positions.len() => {
+            element.children.push(XMLNode::Element(Element::new(name)));
+            Some(element.children.len() - 1)
+        }
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpser::xml::BuildElement;
+    use crate::wsdl::parse;
+
+    const ORDER_WSDL: &str = r#"<?xml version="1.0"?>
+<definitions name="Orders"
+             targetNamespace="http://example.com/orders.wsdl"
+             xmlns:tns="http://example.com/orders.wsdl"
+             xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+             xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <types>
+    <xsd:schema targetNamespace="http://example.com/orders.wsdl">
+      <xsd:element name="PlaceOrder">
+        <xsd:complexType>
+          <xsd:sequence>
+            <xsd:element name="customer" type="tns:Customer"/>
+            <xsd:element name="items" type="tns:Item" minOccurs="1" maxOccurs="unbounded"/>
+          </xsd:sequence>
+        </xsd:complexType>
+      </xsd:element>
+      <xsd:complexType name="Customer">
+        <xsd:sequence>
+          <xsd:element name="name" type="xsd:string"/>
+          <xsd:element name="address" type="tns:Address"/>
+        </xsd:sequence>
+      </xsd:complexType>
+      <xsd:complexType name="Address">
+        <xsd:sequence>
+          <xsd:element name="city" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>
+      <xsd:complexType name="Item">
+        <xsd:sequence>
+          <xsd:element name="sku" type="xsd:string"/>
+          <xsd:element name="quantity" type="xsd:int"/>
+        </xsd:sequence>
+      </xsd:complexType>
+    </xsd:schema>
+  </types>
+  <message name="PlaceOrderInput">
+    <part name="body" element="tns:PlaceOrder"/>
+  </message>
+  <portType name="OrdersPortType">
+    <operation name="PlaceOrder">
+      <input message="tns:PlaceOrderInput"/>
+    </operation>
+  </portType>
+  <service name="OrdersService"/>
+</definitions>"#;
+
+    #[test]
+    fn nested_body() {
+        let wsdl = parse(ORDER_WSDL.as_bytes()).unwrap();
+        let body = body_from_values(
+            &wsdl,
+            "PlaceOrder",
+            &[
+                ("customer.name", "Ada"),
+                ("customer.address.city", "London"),
+                ("items[0].sku", "A-1"),
+                ("items[1].sku", "B-2"),
+                ("items[1].quantity", "3"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            body.to_string(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><PlaceOrder>\
+             <customer><name>Ada</name><address><city>London</city></address></customer>\
+             <items><sku>A-1</sku></items>\
+             <items><sku>B-2</sku><quantity>3</quantity></items>\
+             </PlaceOrder>"
+        );
+    }
+
+    #[test]
+    fn invalid_paths() {
+        let wsdl = parse(ORDER_WSDL.as_bytes()).unwrap();
+        for path in &[
+            "customer.phone",
+            "customer[0].name",
+            "customer.address",
+            "items[2].sku",
+            "customer.name.first",
+        ] {
+            match body_from_values(&wsdl, "PlaceOrder", &[(path, "x")]) {
+                Err(WsdlError::InvalidPath(p)) => assert_eq!(&p, path),
+                other => panic!("expected {} to be invalid, got {:?}", path, other),
+            }
+        }
+    }
+
+    #[test]
+    fn body_parts() {
+        let wsdl = parse(
+            r#"<?xml version="1.0"?>
+<definitions name="Uploads"
+             targetNamespace="http://example.com/uploads.wsdl"
+             xmlns:tns="http://example.com/uploads.wsdl"
+             xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+             xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <types>
+    <xsd:schema xmlns:xsd="http://www.w3.org/2001/XMLSchema"/>
+  </types>
+  <message name="UploadInput">
+    <part name="body_part" element="tns:Upload"/>
+    <part name="auth" element="tns:Auth"/>
+    <part name="file" type="tns:Binary"/>
+  </message>
+  <portType name="UploadsPortType">
+    <operation name="Upload">
+      <input message="tns:UploadInput"/>
+    </operation>
+  </portType>
+  <binding name="UploadsBinding" type="tns:UploadsPortType">
+    <soap:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="Upload">
+      <input>
+        <soap:body parts="body_part" use="literal"/>
+        <soap:header message="tns:UploadInput" part="auth" use="literal"/>
+      </input>
+    </operation>
+  </binding>
+  <service name="UploadsService"/>
+</definitions>"#
+                .as_bytes(),
+        )
+        .unwrap();
+
+        let names = wsdl.messages["UploadInput"]
+            .parts
+            .iter()
+            .map(|p| (p.name.as_str(), p.element.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                ("body_part", "Upload"),
+                ("auth", "Auth"),
+                ("file", "Binary")
+            ]
+        );
+
+        let parts = ["body_part", "auth", "file"]
+            .iter()
+            .map(|name| (name.to_string(), Element::new(name)))
+            .collect();
+        let envelope = envelope_parts(&wsdl, "UploadsBinding", "Upload", parts).unwrap();
+        let names =
+            |elements: &[Element]| elements.iter().map(|e| e.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&envelope.body), vec!["body_part"]);
+        assert_eq!(names(&envelope.headers), vec!["auth"]);
+    }
+
+    #[test]
+    fn parameter_order() {
+        let source = r#"<?xml version="1.0"?>
+<definitions name="Transfers"
+             targetNamespace="http://example.com/transfers.wsdl"
+             xmlns:tns="http://example.com/transfers.wsdl"
+             xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+             xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+             xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <types>
+    <xsd:schema/>
+  </types>
+  <message name="TransferInput">
+    <part name="amount" type="xsd:float"/>
+    <part name="from" type="xsd:string"/>
+    <part name="to" type="xsd:string"/>
+  </message>
+  <portType name="TransfersPortType">
+    <operation name="Transfer" parameterOrder="from to amount">
+      <input message="tns:TransferInput"/>
+    </operation>
+  </portType>
+  <binding name="TransfersBinding" type="tns:TransfersPortType">
+    <soap:binding style="rpc" transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="Transfer">
+      <input>
+        <soap:body use="literal"/>
+      </input>
+    </operation>
+  </binding>
+  <service name="TransfersService"/>
+</definitions>"#;
+        let mut wsdl = parse(source.as_bytes()).unwrap();
+        assert_eq!(
+            wsdl.operations["Transfer"].parameter_order,
+            Some(vec![
+                "from".to_string(),
+                "to".to_string(),
+                "amount".to_string()
+            ])
+        );
+
+        let parts = || {
+            ["to", "amount", "from"]
+                .iter()
+                .map(|name| (name.to_string(), Element::new(name)))
+                .collect()
+        };
+        let names = |envelope: EnvelopeParts| {
+            envelope
+                .body
+                .iter()
+                .map(|e| e.name.clone())
+                .collect::<Vec<_>>()
+        };
+        let envelope = envelope_parts(&wsdl, "TransfersBinding", "Transfer", parts()).unwrap();
+        assert_eq!(names(envelope), vec!["from", "to", "amount"]);
+
+        // without parameterOrder, parts follow the message
+        wsdl.operations.get_mut("Transfer").unwrap().parameter_order = None;
+        let envelope = envelope_parts(&wsdl, "TransfersBinding", "Transfer", parts()).unwrap();
+        assert_eq!(names(envelope), vec!["amount", "from", "to"]);
+    }
+
+    #[test]
+    fn soap_encoding() {
+        let wsdl = parse(
+            ORDER_WSDL
+                .replace(
+                    r#"<service name="OrdersService"/>"#,
+                    r#"<binding name="OrdersBinding" type="tns:OrdersPortType"
+           xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/">
+    <soap:binding style="rpc" transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="PlaceOrder">
+      <input>
+        <soap:body use="encoded" encodingStyle="http://schemas.xmlsoap.org/soap/encoding/"/>
+      </input>
+    </operation>
+  </binding>
+  <service name="OrdersService"/>"#,
+                )
+                .as_bytes(),
+        )
+        .unwrap();
+        let encoding = &wsdl.bindings["OrdersBinding"].operations["PlaceOrder"]
+            .input
+            .as_ref()
+            .unwrap()
+            .encoding;
+        assert_eq!(*encoding, BodyEncoding::Encoded(SOAP_ENC_NS.to_string()));
+
+        let order = body_from_values(
+            &wsdl,
+            "PlaceOrder",
+            &[
+                ("customer.name", "Ada"),
+                ("customer.address.city", "London"),
+                ("items[0].sku", "A-1"),
+                ("items[1].sku", "A-1"),
+            ],
+        )
+        .unwrap();
+        let body = encode_element(&wsdl, encoding, "PlaceOrder", &order).unwrap();
+        assert_eq!(body.len(), 2);
+
+        let xsi_type = |e: &Element| e.attributes["xsi:type"].clone();
+        let root = &body[0];
+        assert_eq!(xsi_type(root), "ns:PlaceOrder");
+        assert_eq!(root.attributes["soap:encodingStyle"], SOAP_ENC_NS);
+        let customer = root.get_child("customer").unwrap();
+        assert_eq!(xsi_type(customer), "ns:Customer");
+        assert_eq!(xsi_type(customer.get_child("name").unwrap()), "xsd:string");
+        let address = customer.get_child("address").unwrap();
+        assert_eq!(xsi_type(address.get_child("city").unwrap()), "xsd:string");
+
+        // both items hold the same value, written once
+        let items = root
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .filter(|e| e.name == "items")
+            .collect::<Vec<_>>();
+        assert_eq!(items.len(), 2);
+        for item in items {
+            assert_eq!(item.attributes["href"], "#id0");
+            assert!(item.children.is_empty());
+        }
+        let shared = &body[1];
+        assert_eq!(shared.name, "multiRef");
+        assert_eq!(shared.attributes["id"], "id0");
+        assert_eq!(xsi_type(shared), "ns:Item");
+        assert_eq!(shared.get_child("sku").unwrap().get_text().unwrap(), "A-1");
+
+        let literal = encode_element(&wsdl, &BodyEncoding::Literal, "PlaceOrder", &order);
+        assert_eq!(literal.unwrap(), vec![order.clone()]);
+        let soap12 = BodyEncoding::Encoded("http://www.w3.org/2003/05/soap-encoding".to_string());
+        assert!(matches!(
+            encode_element(&wsdl, &soap12, "PlaceOrder", &order),
+            Err(WsdlError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn nil_fields() {
+        let wsdl = parse(
+            ORDER_WSDL
+                .replace(
+                    r#"<xsd:element name="city" type="xsd:string"/>"#,
+                    r#"<xsd:element name="city" type="xsd:string"/>
+          <xsd:element name="zip" type="xsd:string" nillable="true"/>"#,
+                )
+                .as_bytes(),
+        )
+        .unwrap();
+
+        let body = body_from_values(
+            &wsdl,
+            "PlaceOrder",
+            &[("customer.address.city", "London"), ("items[0].sku", "A-1")],
+        )
+        .unwrap();
+
+        assert_eq!(body.attributes["xmlns:xsi"], XSI_NS);
+        let address = body
+            .get_child("customer")
+            .and_then(|c| c.get_child("address"))
+            .unwrap();
+        assert_eq!(
+            address.get_child("zip").unwrap().attributes["xsi:nil"],
+            "true"
+        );
+        assert!(address.get_child("city").unwrap().attributes.is_empty());
+        assert!(body.to_string().contains("xsi:nil=\"true\""));
+    }
+
+    #[test]
+    fn response_enumerations() {
+        let wsdl = parse(
+            ORDER_WSDL
+                .replace(
+                    r#"<xsd:element name="quantity" type="xsd:int"/>"#,
+                    r#"<xsd:element name="quantity" type="xsd:int"/>
+          <xsd:element name="status" type="tns:Status"/>"#,
+                )
+                .replace(
+                    r#"<xsd:complexType name="Item">"#,
+                    r#"<xsd:simpleType name="Status">
+        <xsd:restriction base="xsd:string">
+          <xsd:enumeration value="shipped"/>
+          <xsd:enumeration value="on hold"/>
+        </xsd:restriction>
+      </xsd:simpleType>
+      <xsd:complexType name="Item">"#,
+                )
+                .as_bytes(),
+        )
+        .unwrap();
+        let response = |statuses: &[&str]| {
+            let items = statuses
+                .iter()
+                .map(|s| format!("<items><sku>A-1</sku><status>{}</status></items>", s))
+                .collect::<String>();
+            Element::parse(format!("<PlaceOrder>{}</PlaceOrder>", items).as_bytes()).unwrap()
+        };
+
+        assert_eq!(
+            enumeration_values(&wsdl, "PlaceOrder", &response(&["shipped", "on hold"])).unwrap(),
+            vec![
+                ("items[0].status".to_string(), "shipped".to_string()),
+                ("items[1].status".to_string(), "on hold".to_string()),
+            ]
+        );
+        match enumeration_values(&wsdl, "PlaceOrder", &response(&["shipped", "lost"])) {
+            Err(WsdlError::NotInEnumeration { path, value }) => {
+                assert_eq!(path, "items[1].status");
+                assert_eq!(value, "lost");
+            }
+            other => panic!("expected an invalid member, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nil_fixed_fields() {
+        let wsdl = parse(
+            ORDER_WSDL
+                .replace(
+                    r#"<xsd:element name="city" type="xsd:string"/>"#,
+                    r#"<xsd:element name="city" type="xsd:string"/>
+          <xsd:element name="country" type="xsd:string" nillable="true" fixed="FR"/>"#,
+                )
+                .as_bytes(),
+        )
+        .unwrap();
+        match &wsdl.types["Address"] {
+            Type::Complex(c) => {
+                let (attributes, _) = &c.fields["country"];
+                assert!(attributes.nillable);
+                assert_eq!(attributes.fixed.as_deref(), Some("FR"));
+            }
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+
+        let order = |country: &str| {
+            Element::parse(
+                format!(
+                    r#"<PlaceOrder xmlns:xsi="{}"><customer><address>{}</address></customer></PlaceOrder>"#,
+                    XSI_NS, country
+                )
+                .as_bytes(),
+            )
+            .unwrap()
+        };
+        assert!(validate_element(&wsdl, "PlaceOrder", &order("<country>FR</country>")).is_empty());
+        assert_eq!(
+            validate_element(&wsdl, "PlaceOrder", &order(r#"<country xsi:nil="true"/>"#)),
+            vec![ValidationError::NilFixedField {
+                field: "customer.address.country".to_string(),
+                fixed: "FR".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn digit_constraints() {
+        let wsdl = parse(
+            ORDER_WSDL
+                .replace(
+                    r#"<xsd:element name="quantity" type="xsd:int"/>"#,
+                    r#"<xsd:element name="quantity" type="xsd:int"/>
+          <xsd:element name="price" type="tns:Amount"/>"#,
+                )
+                .replace(
+                    r#"<xsd:complexType name="Item">"#,
+                    r#"<xsd:simpleType name="Amount">
+        <xsd:restriction base="xsd:decimal">
+          <xsd:totalDigits value="5"/>
+          <xsd:fractionDigits value="2"/>
+        </xsd:restriction>
+      </xsd:simpleType>
+      <xsd:complexType name="Item">"#,
+                )
+                .as_bytes(),
+        )
+        .unwrap();
+        let order = |prices: &[&str]| {
+            let items = prices
+                .iter()
+                .map(|p| format!("<items><sku>A-1</sku><price>{}</price></items>", p))
+                .collect::<String>();
+            Element::parse(format!("<PlaceOrder>{}</PlaceOrder>", items).as_bytes()).unwrap()
+        };
+
+        assert!(validate_element(&wsdl, "PlaceOrder", &order(&["123.45", "-0.50"])).is_empty());
+        assert_eq!(
+            validate_element(&wsdl, "PlaceOrder", &order(&["1.005", "123456", "12,5"])),
+            vec![
+                ValidationError::DigitConstraintViolated {
+                    field: "items[0].price".to_string(),
+                    total_digits: Some(5),
+                    fraction_digits: Some(2),
+                    actual_value: "1.005".to_string(),
+                },
+                ValidationError::DigitConstraintViolated {
+                    field: "items[1].price".to_string(),
+                    total_digits: Some(5),
+                    fraction_digits: Some(2),
+                    actual_value: "123456".to_string(),
+                },
+                ValidationError::NotADecimal {
+                    field: "items[2].price".to_string(),
+                    actual_value: "12,5".to_string(),
+                },
+            ]
+        );
+    }
+}
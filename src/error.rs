@@ -3,7 +3,16 @@ pub enum Error {
     Wsdl(crate::wsdl::WsdlError),
     Reqwest(reqwest::Error),
     Rpser(crate::rpser::xml::Error),
+    Rpc(crate::rpser::RpcError),
     Num(std::num::ParseFloatError),
+    EnumParse(crate::gen::EnumParseError),
+    Interceptor(crate::http::InterceptorError),
+    DateTime(chrono::ParseError),
+    Hex(crate::gen::HexError),
+    Decimal(crate::gen::DecimalError),
+    Name(crate::gen::NameError),
+    Assertion(crate::gen::AssertionError),
+    Soap(crate::soap::SoapError),
 }
 
 impl From<crate::wsdl::WsdlError> for Error {
@@ -18,6 +27,12 @@ impl From<crate::rpser::xml::Error> for Error {
     }
 }
 
+impl From<crate::rpser::RpcError> for Error {
+    fn from(e: crate::rpser::RpcError) -> Self {
+        Error::Rpc(e)
+    }
+}
+
 impl From<reqwest::Error> for Error {
     fn from(e: reqwest::Error) -> Self {
         Error::Reqwest(e)
@@ -29,3 +44,51 @@ impl From<std::num::ParseFloatError> for Error {
         Error::Num(e)
     }
 }
+
+impl From<crate::gen::EnumParseError> for Error {
+    fn from(e: crate::gen::EnumParseError) -> Self {
+        Error::EnumParse(e)
+    }
+}
+
+impl From<crate::http::InterceptorError> for Error {
+    fn from(e: crate::http::InterceptorError) -> Self {
+        Error::Interceptor(e)
+    }
+}
+
+impl From<chrono::ParseError> for Error {
+    fn from(e: chrono::ParseError) -> Self {
+        Error::DateTime(e)
+    }
+}
+
+impl From<crate::gen::HexError> for Error {
+    fn from(e: crate::gen::HexError) -> Self {
+        Error::Hex(e)
+    }
+}
+
+impl From<crate::gen::DecimalError> for Error {
+    fn from(e: crate::gen::DecimalError) -> Self {
+        Error::Decimal(e)
+    }
+}
+
+impl From<crate::gen::NameError> for Error {
+    fn from(e: crate::gen::NameError) -> Self {
+        Error::Name(e)
+    }
+}
+
+impl From<crate::gen::AssertionError> for Error {
+    fn from(e: crate::gen::AssertionError) -> Self {
+        Error::Assertion(e)
+    }
+}
+
+impl From<crate::soap::SoapError> for Error {
+    fn from(e: crate::soap::SoapError) -> Self {
+        Error::Soap(e)
+    }
+}
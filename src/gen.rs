@@ -1,10 +1,48 @@
-use crate::wsdl::{parse, SimpleType, Type, Wsdl};
+use crate::dynamic::request_layout;
+use crate::rpser::TARGET_PREFIX;
+use crate::wsdl::{
+    parse, BodyEncoding, ComplexType, ElementForm, SimpleType, Type, Wsdl, WsdlError,
+};
 use case::CaseExt;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone};
 use proc_macro2::{Ident, Literal, Span, TokenStream};
-use std::{fs::File, io::Write};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+pub mod rename;
 
 pub trait ToElements {
     fn to_elements(&self) -> Vec<xmltree::Element>;
+
+    /// Fields written as XML attributes, as `(name, value)`.
+    fn to_attributes(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Text of the element, for values of simple types written as text.
+    fn to_text(&self) -> Option<String> {
+        None
+    }
+
+    /// Fills `element` with the child elements, the attributes and the text
+    /// of the value.
+    fn fill_element(&self, mut element: xmltree::Element) -> xmltree::Element {
+        element.attributes.extend(self.to_attributes());
+        element
+            .children
+            .extend(self.to_text().map(xmltree::XMLNode::Text));
+        element.children.extend(
+            self.to_elements()
+                .into_iter()
+                .map(xmltree::XMLNode::Element),
+        );
+        element
+    }
 }
 
 pub trait FromElement {
@@ -13,6 +51,20 @@ pub trait FromElement {
         Self: Sized;
 }
 
+/// Future returned by the methods of the generated service and callback
+/// traits.
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/// Error of an operation declaring faults, generated as an enum with one
+/// variant per fault.
+pub trait FromFault: From<crate::Error> {
+    /// Reads the fault from the element in the `detail` of a SOAP fault,
+    /// returning `None` if it is not one of the declared faults.
+    fn from_detail(element: &xmltree::Element) -> Result<Option<Self>, crate::Error>
+    where
+        Self: Sized;
+}
+
 impl<T: ToElements> ToElements for Option<T> {
     fn to_elements(&self) -> Vec<xmltree::Element> {
         match self {
@@ -20,6 +72,17 @@ impl<T: ToElements> ToElements for Option<T> {
             None => vec![],
         }
     }
+
+    fn to_attributes(&self) -> Vec<(String, String)> {
+        match self {
+            Some(e) => e.to_attributes(),
+            None => vec![],
+        }
+    }
+
+    fn to_text(&self) -> Option<String> {
+        self.as_ref().and_then(|e| e.to_text())
+    }
 }
 
 /*impl<T: ToElements> for Vec<T> {
@@ -32,9 +95,443 @@ impl<T: ToElements> ToElements for Option<T> {
     }
 }*/
 
+/// Error returned when a string is not a value of a generated enumeration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumParseError {
+    pub type_name: &'static str,
+    pub value: String,
+}
+
+/// Error returned by the `validate` method of a generated type when one of
+/// the `xs:assert` assertions of its schema does not hold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssertionError {
+    pub type_name: &'static str,
+    /// `test` of the assertion
+    pub test: &'static str,
+}
+
+/// Calls of a generated mock service, and the responses queued for them.
+#[derive(Debug, Default)]
+pub struct MockCalls {
+    calls: Mutex<Vec<String>>,
+    responses: Mutex<HashMap<String, VecDeque<Vec<u8>>>>,
+}
+
+impl MockCalls {
+    /// Queues `response`, the XML of the output element, for the next call
+    /// of `operation`.
+    pub fn expect_call(&self, operation: &str, response: Vec<u8>) {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(operation.to_string())
+            .or_default()
+            .push_back(response);
+    }
+
+    /// Panics if `operation` was never called.
+    pub fn assert_called(&self, operation: &str) {
+        let calls = self.calls();
+        assert!(
+            calls.iter().any(|c| c == operation),
+            "{} was not called, calls: {:?}",
+            operation,
+            calls
+        );
+    }
+
+    /// Names of the called operations, in call order.
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Records a call of `operation`, discarding its queued response if any.
+    pub fn record(&self, operation: &str) -> Option<Vec<u8>> {
+        self.calls.lock().unwrap().push(operation.to_string());
+        self.responses
+            .lock()
+            .unwrap()
+            .get_mut(operation)
+            .and_then(|r| r.pop_front())
+    }
+
+    /// Records a call of `operation` and reads its queued response.
+    ///
+    /// Panics if no response is queued or if it is not XML, as the test
+    /// setting up the mock is wrong.
+    pub fn respond<T: FromElement>(&self, operation: &str) -> Result<T, crate::Error> {
+        let response = self
+            .record(operation)
+            .unwrap_or_else(|| panic!("no response is queued for {}", operation));
+        let element = xmltree::Element::parse(response.as_slice())
+            .unwrap_or_else(|e| panic!("the response queued for {} is not XML: {}", operation, e));
+        T::from_element(&element)
+    }
+}
+
+/// Parses an `xs:dateTime`, keeping its UTC offset.
+///
+/// Accepts `Z`, `+HH:MM` and `-HH:MM` offsets. Values without an offset are
+/// taken as UTC.
+pub fn parse_date_time(s: &str) -> Result<DateTime<FixedOffset>, chrono::ParseError> {
+    DateTime::parse_from_rfc3339(s).or_else(|e| {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+            .map(|d| FixedOffset::east_opt(0).unwrap().from_utc_datetime(&d))
+            .map_err(|_| e)
+    })
+}
+
+/// Binary data of an `xs:hexBinary`, as in `"4A6F"`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HexBinary(pub Vec<u8>);
+
+/// Error returned when a string is not a valid `xs:hexBinary`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HexError {
+    OddLength,
+    InvalidDigit(char),
+}
+
+impl HexBinary {
+    /// Decodes pairs of hex digits, in either case.
+    pub fn from_hex(s: &str) -> Result<Vec<u8>, HexError> {
+        let digits = s
+            .trim()
+            .chars()
+            .map(|c| c.to_digit(16).ok_or(HexError::InvalidDigit(c)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if digits.len() % 2 != 0 {
+            return Err(HexError::OddLength);
+        }
+
+        Ok(digits
+            .chunks(2)
+            .map(|pair| (pair[0] * 16 + pair[1]) as u8)
+            .collect())
+    }
+
+    /// Encodes bytes as uppercase hex digits, the canonical form of `xs:hexBinary`.
+    pub fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02X}", b)).collect()
+    }
+}
+
+/// An `xs:decimal`, kept as written to avoid rounding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decimal(String);
+
+/// Error returned when a string is not an `xs:decimal`, or when a value does
+/// not fit the digits of its restriction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecimalError {
+    Invalid(String),
+    TooManyDigits {
+        value: String,
+        total_digits: Option<u32>,
+        fraction_digits: Option<u32>,
+    },
+}
+
+impl Decimal {
+    /// Integer and fraction digits, without sign.
+    fn parts(&self) -> (&str, &str) {
+        let unsigned = self.0.trim_start_matches(['+', '-']);
+        match unsigned.find('.') {
+            Some(dot) => (&unsigned[..dot], &unsigned[dot + 1..]),
+            None => (unsigned, ""),
+        }
+    }
+
+    /// Number of significant digits, as counted by `xs:totalDigits`.
+    pub fn total_digits(&self) -> u32 {
+        let (integer, fraction) = self.parts();
+        let digits = format!("{}{}", integer, fraction.trim_end_matches('0'));
+        digits.trim_start_matches('0').len() as u32
+    }
+
+    /// Number of digits after the point, as counted by `xs:fractionDigits`.
+    pub fn fraction_digits(&self) -> u32 {
+        self.parts().1.trim_end_matches('0').len() as u32
+    }
+
+    /// Checks the value against the `xs:totalDigits` and `xs:fractionDigits`
+    /// facets of a restriction.
+    pub fn check_digits(
+        &self,
+        total_digits: Option<u32>,
+        fraction_digits: Option<u32>,
+    ) -> Result<(), DecimalError> {
+        let too_many = matches!(total_digits, Some(t) if self.total_digits() > t)
+            || matches!(fraction_digits, Some(f) if self.fraction_digits() > f);
+        if !too_many {
+            Ok(())
+        } else {
+            Err(DecimalError::TooManyDigits {
+                value: self.0.clone(),
+                total_digits,
+                fraction_digits,
+            })
+        }
+    }
+}
+
+impl Default for Decimal {
+    fn default() -> Self {
+        Decimal("0".to_string())
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(value: i64) -> Self {
+        Decimal(value.to_string())
+    }
+}
+
+impl std::fmt::Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for Decimal {
+    type Err = DecimalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+        let mut halves = unsigned.splitn(2, '.');
+        let integer = halves.next().unwrap_or("");
+        let fraction = halves.next().unwrap_or("");
+        let digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+
+        if digits(integer) && digits(fraction) && integer.len() + fraction.len() > 0 {
+            Ok(Decimal(s.to_string()))
+        } else {
+            Err(DecimalError::Invalid(s.to_string()))
+        }
+    }
+}
+
+/// Error returned when a string is not a valid XML name of the given kind,
+/// as in `NCName`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameError {
+    pub kind: &'static str,
+    pub value: String,
+}
+
+fn is_name_start_char(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '\u{b7}'
+}
+
+/// Whether `s` is a name without colons.
+fn is_ncname(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if is_name_start_char(c)) && chars.all(is_name_char)
+}
+
+fn check_name(kind: &'static str, s: &str, valid: bool) -> Result<String, NameError> {
+    if valid {
+        Ok(s.to_string())
+    } else {
+        Err(NameError {
+            kind,
+            value: s.to_string(),
+        })
+    }
+}
+
+/// An `xs:NCName`, a name without colons such as an element name.
+///
+/// The default value is empty, and invalid.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct NCName(String);
+
+impl NCName {
+    pub fn new(s: &str) -> Result<Self, NameError> {
+        check_name("NCName", s, is_ncname(s)).map(NCName)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// An `xs:Name`, a name that may contain colons.
+///
+/// The default value is empty, and invalid.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Name(String);
+
+impl Name {
+    pub fn new(s: &str) -> Result<Self, NameError> {
+        let mut chars = s.chars();
+        let valid = matches!(chars.next(), Some(c) if is_name_start_char(c) || c == ':')
+            && chars.all(|c| is_name_char(c) || c == ':');
+        check_name("Name", s, valid).map(Name)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// An `xs:QName`, a name with an optional namespace prefix, as in `tns:Order`.
+///
+/// The default value is empty, and invalid.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct QName(String);
+
+impl QName {
+    pub fn new(s: &str) -> Result<Self, NameError> {
+        let valid = match s.find(':') {
+            Some(i) => is_ncname(&s[..i]) && is_ncname(&s[i + 1..]),
+            None => is_ncname(s),
+        };
+        check_name("QName", s, valid).map(QName)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn prefix(&self) -> Option<&str> {
+        self.0.find(':').map(|i| &self.0[..i])
+    }
+
+    pub fn local_name(&self) -> &str {
+        self.0.find(':').map_or(&self.0, |i| &self.0[i + 1..])
+    }
+}
+
+impl std::fmt::Display for NCName {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::fmt::Display for Name {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::fmt::Display for QName {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for NCName {
+    type Err = NameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NCName::new(s)
+    }
+}
+
+impl std::str::FromStr for Name {
+    type Err = NameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Name::new(s)
+    }
+}
+
+impl std::str::FromStr for QName {
+    type Err = NameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        QName::new(s)
+    }
+}
+
+// the names are strings, and are passed as such to functions taking `&str`
+impl AsRef<str> for NCName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::ops::Deref for NCName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Name {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::ops::Deref for Name {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for QName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::ops::Deref for QName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Error returned by a generated conversion when a field of the source
+/// type has no counterpart in the target type but holds a value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionError {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub field: &'static str,
+}
+
+#[derive(Default)]
+pub struct CodegenOptions {
+    /// Generates `TryFrom` conversions between complex types sharing at
+    /// least this fraction, between 0 and 1, of their fields. A field is
+    /// shared if both types have it with the same type and occurence, and
+    /// the other fields must be optional. `None` disables conversions.
+    pub conversion_threshold: Option<f64>,
+    /// Names the generated type of each schema type, instead of converting
+    /// the schema name to camel case. See `rename` for common conventions.
+    #[allow(clippy::type_complexity)]
+    pub type_rename: Option<Box<dyn Fn(&str) -> String>>,
+}
+
+impl std::fmt::Debug for CodegenOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("CodegenOptions")
+            .field("conversion_threshold", &self.conversion_threshold)
+            .field("type_rename", &self.type_rename.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
 #[derive(Debug)]
 pub enum GenError {
     Io(std::io::Error),
+    Wsdl(WsdlError),
+    /// types written by `incremental_generate` to the same file, whose names
+    /// only differ by their case or underscores
+    FileCollision(String, String),
 }
 
 impl From<std::io::Error> for GenError {
@@ -43,6 +540,72 @@ impl From<std::io::Error> for GenError {
     }
 }
 
+impl From<WsdlError> for GenError {
+    fn from(e: WsdlError) -> Self {
+        GenError::Wsdl(e)
+    }
+}
+
+/// Rejects the features of `wsdl` the generated client cannot send.
+///
+/// Fields of abstract types without members could not have a value, so they
+/// fail with `WsdlError::UnsupportedFeature`.
+///
+/// Messages bound with `use="encoded"` need the SOAP encoding rules, which
+/// only `dynamic::encode_element` applies, so generation fails with
+/// `WsdlError::UnsupportedFeature` rather than sending them as literal.
+fn check_supported(wsdl: &Wsdl) -> Result<(), GenError> {
+    // fields of abstract types default to their first member
+    for (name, t) in sorted(&wsdl.types) {
+        let head = match t {
+            Type::Complex(c) if !c.abstract_ => c.fields.values().find_map(|(_, f)| match f {
+                SimpleType::Complex(head) if is_abstract(wsdl, head) => Some(head),
+                _ => None,
+            }),
+            _ => None,
+        };
+        let has_members = |head: &str| {
+            wsdl.types.values().any(
+                |t| matches!(t, Type::Complex(c) if c.substitution_group.as_deref() == Some(head)),
+            )
+        };
+        if let Some(head) = head.filter(|head| !has_members(head)) {
+            return Err(WsdlError::UnsupportedFeature(format!(
+                "field of {} of the abstract type {}, without substitution group members",
+                name, head
+            ))
+            .into());
+        }
+    }
+
+    // the client has a method per operation name, that overloads would share
+    if let Some((_, overloads)) = sorted(&wsdl.overloads).first() {
+        return Err(WsdlError::UnsupportedFeature(format!(
+            "overloaded operation {}",
+            overloads[0].name
+        ))
+        .into());
+    }
+
+    for (name, binding) in sorted(&wsdl.bindings) {
+        for (operation, binding_operation) in sorted(&binding.operations) {
+            let encoded = [&binding_operation.input, &binding_operation.output]
+                .iter()
+                .filter_map(|m| m.as_ref())
+                .any(|m| matches!(m.encoding, BodyEncoding::Encoded(_)));
+            if encoded {
+                return Err(WsdlError::UnsupportedFeature(format!(
+                    "encoded messages of operation {} in binding {}",
+                    operation, name
+                ))
+                .into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub fn gen_write(path: &str, out: &str) -> Result<(), ()> {
     let out_path = format!("{}/example.rs", out);
     let v = std::fs::read(path).unwrap();
@@ -55,299 +618,1422 @@ pub fn gen_write(path: &str, out: &str) -> Result<(), ()> {
     Ok(())
 }
 
-pub fn gen(wsdl: &Wsdl) -> Result<String, GenError> {
-    let target_namespace = Literal::string(&wsdl.target_namespace);
+/// Name of the generated type of the schema type `name`.
+fn rust_type_name(name: &str, options: &CodegenOptions) -> String {
+    match &options.type_rename {
+        Some(rename) => rename(name),
+        None => name.to_camel(),
+    }
+}
 
-    let operations = wsdl.operations.iter().map(|(name, operation)| {
-        let op_name = Ident::new(&name.to_snake(), Span::call_site());
-        let input_name = Ident::new(&operation.input.as_ref().unwrap().to_snake(), Span::call_site());
-        let input_type = Ident::new(&operation.input.as_ref().unwrap().to_camel(), Span::call_site());
+fn type_ident(name: &str, options: &CodegenOptions) -> Ident {
+    Ident::new(&rust_type_name(name, options), Span::call_site())
+}
 
-        let op_str = Literal::string(&name);
+fn simple_type_path(simple_type: &SimpleType, options: &CodegenOptions) -> TokenStream {
+    match simple_type {
+        SimpleType::Complex(s) => {
+            let ident = type_ident(s, options);
+            quote! { #ident }
+        }
+        builtin => builtin_type_path(builtin)
+            .and_then(|path| path.parse().ok())
+            .expect("built-in types have a valid path"),
+    }
+}
 
-        match (operation.output.as_ref(), operation.faults.as_ref()) {
-            (None, None) => {
-                quote! {
-                    pub async fn #op_name(&self, #input_name: #input_type) -> Result<(), savon::Error> {
-                        savon::http::one_way(&self.client, &self.base_url, #target_namespace, #op_str, &#input_name).await
-                    }
-                }
-            },
-            (None, Some(_)) => quote!{},
-            (Some(out), None) => {
-                let out_name = Ident::new(&out, Span::call_site());
+/// Path of the Rust type of a built-in simple type in generated code, or
+/// `None` for a reference to a schema type.
+pub(crate) fn builtin_type_path(simple_type: &SimpleType) -> Option<&'static str> {
+    Some(match simple_type {
+        SimpleType::Boolean => "bool",
+        SimpleType::String | SimpleType::Notation => "String",
+        SimpleType::Float => "f64",
+        SimpleType::Int => "i64",
+        SimpleType::HexBinary => "Vec<u8>",
+        SimpleType::Decimal => "savon::gen::Decimal",
+        SimpleType::QName => "savon::gen::QName",
+        SimpleType::NCName => "savon::gen::NCName",
+        SimpleType::Name => "savon::gen::Name",
+        SimpleType::DateTime => {
+            "savon::internal::chrono::DateTime<savon::internal::chrono::FixedOffset>"
+        }
+        SimpleType::Complex(_) => return None,
+    })
+}
+
+/// Generates the `validate` method checking the `xs:assert` assertions of a
+/// complex type.
+///
+/// Only comparisons of two fields, as in `@start <= @end`, are checked. The
+/// other assertions are listed as TODO in the documentation of the method.
+fn gen_assertions(name: &str, c: &ComplexType, options: &CodegenOptions) -> TokenStream {
+    if c.assertions.is_empty() {
+        return quote! {};
+    }
+    let type_name = type_ident(name, options);
+    let type_str = Literal::string(&rust_type_name(name, options));
+
+    let mut todos = Vec::new();
+    let mut checks = Vec::new();
+    for test in &c.assertions {
+        let (left, operator, right) = match comparison(c, test) {
+            Some(comparison) => comparison,
+            None => {
+                let todo = format!(" TODO: `{}` is not checked.", test);
+                todos.push(quote! { #[doc = #todo] });
+                continue;
+            }
+        };
+        let test_str = Literal::string(test);
+        let error = quote! {
+            return Err(savon::gen::AssertionError { type_name: #type_str, test: #test_str });
+        };
+        let value = |field: &str| {
+            let fname = Ident::new(&field.to_snake(), Span::call_site());
+            match c.fields[field].0.nillable {
+                true => quote! { self.#fname.as_ref() },
+                false => quote! { Some(&self.#fname) },
+            }
+        };
 
+        // absent values are not compared
+        checks.push(
+            if !c.fields[left].0.nillable && !c.fields[right].0.nillable {
+                let left = Ident::new(&left.to_snake(), Span::call_site());
+                let right = Ident::new(&right.to_snake(), Span::call_site());
                 quote! {
-                    pub async fn #op_name(&self, #input_name: #input_type) -> Result<Result<#out_name, ()>, savon::Error> {
-                        savon::http::request_response(&self.client, &self.base_url, #target_namespace, #op_str, &#input_name).await
+                    if !(self.#left #operator self.#right) {
+                        #error
                     }
                 }
-            },
-            (Some(out), Some(_)) => {
-                let out_name = Ident::new(&out, Span::call_site());
-                let err_name = Ident::new(&format!("{}Error", name.to_camel()), Span::call_site());
-
+            } else {
+                let (left, right) = (value(left), value(right));
                 quote! {
-                    pub async fn #op_name(&self, #input_name: #input_type) -> Result<Result<#out_name, #err_name>, savon::Error> {
-                        unimplemented!()
-                        /*let req = hyper::http::request::Builder::new()
-                            .method("POST")
-                            .header("Content-Type", "text/xml-SOAP")
-                            .header("MessageType", "Call")
-                            .body(#input_name.as_xml())?;
-
-                        let response: hyper::http::Response<String> = self.client.request(req).await?;
-                        let body = response.body().await?;
-                        if let Ok(out) = #out_name::from_xml(body) {
-                            Ok(Ok(out))
-                        } else {
-                            Ok(#err_name::from_xml(body)?)
+                    if let (Some(left), Some(right)) = (#left, #right) {
+                        if !(left #operator right) {
+                            #error
                         }
-                        */
                     }
                 }
             },
+        );
+    }
+    let todos = match todos.is_empty() {
+        true => quote! {},
+        false => quote! { #[doc = ""] #(#todos)* },
+    };
+
+    quote! {
+        impl #type_name {
+            /// Checks the `xs:assert` assertions of the schema.
+            #todos
+            pub fn validate(&self) -> Result<(), savon::gen::AssertionError> {
+                #(#checks)*
+                Ok(())
+            }
         }
-    }).collect::<Vec<_>>();
+    }
+}
 
-    let types = wsdl
-        .types
+/// Reads an assertion comparing two fields of `c`, as in `@start <= @end`,
+/// returning the fields and the Rust operator.
+///
+/// The fields must hold a single value of the same ordered primitive type.
+fn comparison<'a>(c: &ComplexType, test: &'a str) -> Option<(&'a str, TokenStream, &'a str)> {
+    // two character operators first, so that `<=` is not read as `<`
+    let (index, operator) = ["<=", ">=", "!=", "<", ">", "="]
         .iter()
-        .map(|(name, t)| {
-            if let Type::Complex(c) = t {
-                let type_name = Ident::new(&name.to_camel(), Span::call_site());
+        .find_map(|op| test.find(op).map(|index| (index, *op)))?;
+    let field = |operand: &'a str| {
+        let operand = operand.trim();
+        let name = operand.strip_prefix('@').unwrap_or(operand);
+        let (attributes, field_type) = c.fields.get(name)?;
+        let ordered = matches!(
+            field_type,
+            SimpleType::Boolean
+                | SimpleType::String
+                | SimpleType::Notation
+                | SimpleType::Float
+                | SimpleType::Int
+                | SimpleType::DateTime
+                | SimpleType::HexBinary
+        );
+        match ordered && !attributes.is_repeated() {
+            true => Some((name, field_type)),
+            false => None,
+        }
+    };
+    let (left, left_type) = field(&test[..index])?;
+    let (right, right_type) = field(&test[index + operator.len()..])?;
+    if left_type != right_type {
+        return None;
+    }
 
-                let fields = c
-                    .fields
-                    .iter()
-                    .map(|(field_name, (attributes, field_type))| {
-                        let fname = Ident::new(&field_name.to_snake(), Span::call_site());
-                        let ft = match field_type {
-                            SimpleType::Boolean => Ident::new("bool", Span::call_site()),
-                            SimpleType::String => Ident::new("String", Span::call_site()),
-                            SimpleType::Float => Ident::new("f64", Span::call_site()),
-                            SimpleType::Int => Ident::new("i64", Span::call_site()),
-                            SimpleType::DateTime => Ident::new("chrono::DateTime", Span::call_site()),
-                            SimpleType::Complex(s) => Ident::new(&s.to_camel(), Span::call_site()),
-                        };
+    let operator = match operator {
+        "<=" => quote! { <= },
+        ">=" => quote! { >= },
+        "!=" => quote! { != },
+        "<" => quote! { < },
+        ">" => quote! { > },
+        _ => quote! { == },
+    };
+    Some((left, operator, right))
+}
 
-                        let ft = match (attributes.min_occurs.as_ref(), attributes.max_occurs.as_ref()) {
-                          (Some(_), Some(_)) => quote! { Vec<#ft> },
-                          _ => quote! { #ft }
-                        };
-                        let ft = if attributes.nillable {
-                            quote! { Option<#ft> }
-                        } else {
-                            ft
-                        };
+/// Whether the generated type is written as text, with `Display` and `FromStr`.
+fn is_text_type(wsdl: &Wsdl, type_name: &str) -> bool {
+    matches!(
+        wsdl.types.get(type_name),
+        Some(Type::Enumeration(_)) | Some(Type::Decimal { .. })
+    )
+}
 
-                        quote! {
-                            pub #fname: #ft,
-                        }
-                    })
-                    .collect::<Vec<_>>();
+/// Type a field is written and read as: aliases and restrictions of simple
+/// types are followed down to the primitive or the text type they stand for,
+/// the other types are kept as declared.
+fn field_text_type(wsdl: &Wsdl, field_type: &SimpleType) -> SimpleType {
+    let mut current = field_type.clone();
+    for _ in 0..=wsdl.types.len() {
+        let next = match &current {
+            SimpleType::Complex(s) => match wsdl.types.get(s) {
+                Some(Type::Simple(simple)) => simple.clone(),
+                Some(Type::Alias(target)) => SimpleType::Complex(target.clone()),
+                _ if is_text_type(wsdl, s) => return current,
+                _ => break,
+            },
+            _ => return current,
+        };
+        current = next;
+    }
+    field_type.clone()
+}
 
-                let fields_serialize_impl = c
-                    .fields
-                    .iter()
-                    .map(|(field_name, (attributes, field_type))| {
-                        let fname = Ident::new(&field_name.to_snake(), Span::call_site());
-                        //FIXME: handle more complex types
-                        /*let ft = match field_type {
-                            SimpleType::Boolean => Ident::new("bool", Span::call_site()),
-                            SimpleType::String => Ident::new("String", Span::call_site()),
-                            SimpleType::Float => Ident::new("f64", Span::call_site()),
-                            SimpleType::Int => Ident::new("i64", Span::call_site()),
-                            SimpleType::DateTime => Ident::new("String", Span::call_site()),
-                            SimpleType::Complex(s) => Ident::new(&s, Span::call_site()),
-                        };*/
-                        let ftype = Literal::string(field_name);
-                        let prefix = quote! { xmltree::Element::node(#ftype) };
-
-                        match (attributes.min_occurs.as_ref(), attributes.max_occurs.as_ref()) {
-                          (Some(_), Some(_)) => if attributes.nillable {
-                              quote! {
-                                  self.#fname.as_ref().map(|v| v.iter().map(|i| {
-                                      #prefix.with_children(i.to_elements())
-                                  }).collect()).unwrap_or_else(Vec::new)
-                              }
-                          } else {
-                              quote! {
-                                  self.#fname.iter().map(|i| {
-                                      #prefix.with_children(i.to_elements())
-                                  }).collect()
-                              }
-                          },
-                          _ => {
-                              match field_type {
-                                  SimpleType::Complex(_s) => quote!{ vec![#prefix.with_children(self.#fname.to_elements())]},
-                                  _ => quote!{ vec![#prefix.with_text(self.#fname.to_string())] },
-                              }
-
-                          }
-                        }
-                    })
-                    .collect::<Vec<_>>();
+/// Type of the message part `element` when it is written as text, `None`
+/// for parts written as child elements.
+fn text_part(wsdl: &Wsdl, element: &str) -> Option<SimpleType> {
+    match field_text_type(wsdl, &SimpleType::Complex(element.to_string())) {
+        SimpleType::Complex(s) if !is_text_type(wsdl, &s) => None,
+        text_type => Some(text_type),
+    }
+}
 
-                let serialize_impl = if fields_serialize_impl.is_empty() {
-                    quote! {
-                        impl savon::gen::ToElements for #type_name {
-                            fn to_elements(&self) -> Vec<xmltree::Element> {
-                                vec![]
-                            }
-                        }
-                    }
+/// Text of `value`, of a type written as text.
+fn write_text(text_type: &SimpleType, value: TokenStream) -> TokenStream {
+    match text_type {
+        SimpleType::DateTime => quote! { #value.to_rfc3339() },
+        SimpleType::HexBinary => quote! { savon::gen::HexBinary::to_hex(&#value) },
+        _ => quote! { #value.to_string() },
+    }
+}
 
-                }else {
-                    quote! {
-                        impl savon::gen::ToElements for #type_name {
-                            fn to_elements(&self) -> Vec<xmltree::Element> {
-                                vec![#(#fields_serialize_impl),*].drain(..).flatten().collect()
-                            }
-                        }
-                    }
+/// Reads a value of a type written as text from the text of `element`.
+fn read_text(
+    text_type: &SimpleType,
+    element: TokenStream,
+    options: &CodegenOptions,
+) -> TokenStream {
+    let text = quote! {
+        #element.get_text()
+            .ok_or(savon::rpser::xml::Error::Empty)
+            .map_err(savon::Error::from)
+    };
+    match text_type {
+        SimpleType::Boolean => quote! { #element.as_boolean().map_err(savon::Error::from) },
+        SimpleType::Int => quote! { #element.as_long().map_err(savon::Error::from) },
+        SimpleType::String | SimpleType::Notation => quote! { #text.map(|s| s.to_string()) },
+        SimpleType::DateTime => {
+            quote! { #text.and_then(|s| savon::gen::parse_date_time(&s).map_err(savon::Error::from)) }
+        }
+        SimpleType::HexBinary => {
+            quote! { #text.and_then(|s| savon::gen::HexBinary::from_hex(&s).map_err(savon::Error::from)) }
+        }
+        SimpleType::Complex(s) => {
+            let path = type_ident(s, options);
+            quote! { #text.and_then(|s| s.parse::<#path>().map_err(savon::Error::from)) }
+        }
+        _ => {
+            let path = simple_type_path(text_type, options);
+            quote! { #text.and_then(|s| s.parse::<#path>().map_err(savon::Error::from)) }
+        }
+    }
+}
+
+/// Whether the generated type is a trait, implemented by the members of its
+/// substitution group.
+fn is_abstract(wsdl: &Wsdl, type_name: &str) -> bool {
+    matches!(wsdl.types.get(type_name), Some(Type::Complex(c)) if c.abstract_)
+}
+
+/// Path of the type of a complex field, boxing the abstract types.
+fn complex_type_path(wsdl: &Wsdl, type_name: &str, options: &CodegenOptions) -> TokenStream {
+    let ident = type_ident(type_name, options);
+    match is_abstract(wsdl, type_name) {
+        true => quote! { Box<dyn #ident> },
+        false => quote! { #ident },
+    }
+}
+
+/// Prefix bound to the namespace of the `xsi:type` of the values of abstract types.
+const XSI_TYPE_PREFIX: &str = "st";
+
+/// Generates the trait of an abstract type, implemented by the members of its
+/// substitution group. Values are boxed, written with the `xsi:type` of their
+/// member, and read as the member named by their `xsi:type`, or else by their
+/// element.
+fn gen_abstract_type(wsdl: &Wsdl, name: &str, options: &CodegenOptions) -> TokenStream {
+    let type_name = type_ident(name, options);
+    let type_str = Literal::string(&rust_type_name(name, options));
+    let mut members = wsdl
+        .types
+        .iter()
+        .filter_map(|(member, t)| match t {
+            Type::Complex(c) if c.substitution_group.as_deref() == Some(name) => Some(member),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    members.sort();
+    let member_types = members
+        .iter()
+        .map(|member| type_ident(member, options))
+        .collect::<Vec<_>>();
+    // members are read by the name of their type or of their elements
+    let member_names = members
+        .iter()
+        .map(|member| {
+            let mut names = vec![member.as_str()];
+            names.extend(wsdl.types.iter().filter_map(|(element, t)| match t {
+                Type::Alias(target) if target == *member => Some(element.as_str()),
+                _ => None,
+            }));
+            names.sort();
+            let names = names.into_iter().map(Literal::string);
+            quote! { #(#names)|* }
+        })
+        .collect::<Vec<_>>();
+    let xmlns = Literal::string(&format!("xmlns:{}", XSI_TYPE_PREFIX));
+    let prefix = Literal::string(XSI_TYPE_PREFIX);
+
+    // heads without members are rejected by `check_supported` when used by a
+    // field, as there is no value to default to
+    let default_impl = member_types.first().map(|first| {
+        quote! {
+            impl Default for Box<dyn #type_name> {
+                fn default() -> Self {
+                    Box::new(#first::default())
+                }
+            }
+        }
+    });
+
+    quote! {
+        pub trait #type_name: savon::gen::ToElements + std::fmt::Debug + Send + Sync {
+            /// Name of the type of the value, written as its `xsi:type`.
+            fn type_name(&self) -> &'static str;
+
+            /// Namespace of the schema declaring the type of the value.
+            fn type_namespace(&self) -> &'static str;
+
+            fn clone_box(&self) -> Box<dyn #type_name>;
+        }
+
+        impl Clone for Box<dyn #type_name> {
+            fn clone(&self) -> Self {
+                #type_name::clone_box(&**self)
+            }
+        }
+
+        #default_impl
+
+        impl savon::gen::ToElements for Box<dyn #type_name> {
+            fn to_elements(&self) -> Vec<xmltree::Element> {
+                savon::gen::ToElements::to_elements(&**self)
+            }
+
+            fn to_attributes(&self) -> Vec<(String, String)> {
+                let mut attributes = savon::gen::ToElements::to_attributes(&**self);
+                attributes.push((#xmlns.to_string(), #type_name::type_namespace(&**self).to_string()));
+                attributes.push((
+                    "xsi:type".to_string(),
+                    format!("{}:{}", #prefix, #type_name::type_name(&**self)),
+                ));
+                attributes
+            }
+        }
+
+        impl savon::gen::FromElement for Box<dyn #type_name> {
+            fn from_element(element: &xmltree::Element) -> Result<Self, savon::Error> {
+                let name = element
+                    .attributes
+                    .get("type")
+                    .map(|t| t.rsplit(':').next().unwrap_or(t))
+                    .unwrap_or(&element.name);
+                match name {
+                    #(#member_names => Ok(Box::new(
+                        <#member_types as savon::gen::FromElement>::from_element(element)?,
+                    )),)*
+                    _ => Err(savon::gen::EnumParseError {
+                        type_name: #type_str,
+                        value: name.to_string(),
+                    }
+                    .into()),
+                }
+            }
+        }
+    }
+}
+
+/// Builds enum variants from enumeration values, as in `"on hold"` -> `OnHold`.
+fn enum_variants(values: &[String]) -> Vec<Ident> {
+    let mut seen = HashSet::new();
+
+    values
+        .iter()
+        .map(|value| {
+            let mut variant = value
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(|word| word.to_camel())
+                .collect::<String>();
+            if variant.is_empty() {
+                variant.push_str("Empty");
+            }
+            if variant.starts_with(|c: char| c.is_ascii_digit()) {
+                variant.insert(0, 'V');
+            }
+            let base = variant.clone();
+            let mut index = 1;
+            while !seen.insert(variant.clone()) {
+                index += 1;
+                variant = format!("{}{}", base, index);
+            }
+
+            Ident::new(&variant, Span::call_site())
+        })
+        .collect()
+}
+
+/// Generates conversions from `from` to `to` if they have similar fields.
+fn conversion(
+    from_name: &str,
+    from: &ComplexType,
+    to_name: &str,
+    to: &ComplexType,
+    threshold: f64,
+    options: &CodegenOptions,
+) -> Option<TokenStream> {
+    let shared = |name: &String| match (from.fields.get(name), to.fields.get(name)) {
+        (Some((a, t)), Some((b, u))) => {
+            t == u && a.nillable == b.nillable && a.is_repeated() == b.is_repeated()
+        }
+        _ => false,
+    };
+    let union = from
+        .fields
+        .keys()
+        .chain(to.fields.keys().filter(|k| !from.fields.contains_key(*k)))
+        .collect::<Vec<_>>();
+    let shared_count = union.iter().filter(|k| shared(k)).count();
+    if shared_count == 0 || (shared_count as f64) < threshold * union.len() as f64 {
+        return None;
+    }
+    // fields missing on one side are dropped or left empty, so they must be optional
+    let optional = |name: &String| {
+        [from, to]
+            .iter()
+            .filter_map(|c| c.fields.get(name))
+            .all(|(a, _)| a.nillable)
+    };
+    if !union.iter().filter(|k| !shared(k)).all(|k| optional(k)) {
+        return None;
+    }
+
+    let from_type = type_ident(from_name, options);
+    let to_type = type_ident(to_name, options);
+    let from_str = Literal::string(&rust_type_name(from_name, options));
+    let to_str = Literal::string(&rust_type_name(to_name, options));
+
+    let mut from_fields = from.fields.keys().collect::<Vec<_>>();
+    from_fields.sort();
+    let checks = from_fields.iter().filter(|k| !shared(k)).map(|k| {
+        let fname = Ident::new(&k.to_snake(), Span::call_site());
+        let field_str = Literal::string(&k.to_snake());
+        quote! {
+            if value.#fname.is_some() {
+                return Err(savon::gen::ConversionError {
+                    from: #from_str,
+                    to: #to_str,
+                    field: #field_str,
+                });
+            }
+        }
+    });
+
+    let mut to_fields = to.fields.keys().collect::<Vec<_>>();
+    to_fields.sort();
+    let fields = to_fields.iter().map(|k| {
+        let fname = Ident::new(&k.to_snake(), Span::call_site());
+        if shared(k) {
+            quote! { #fname: value.#fname, }
+        } else {
+            quote! { #fname: None, }
+        }
+    });
+
+    Some(quote! {
+        impl std::convert::TryFrom<#from_type> for #to_type {
+            type Error = savon::gen::ConversionError;
+
+            fn try_from(value: #from_type) -> Result<Self, Self::Error> {
+                #(#checks)*
+                Ok(#to_type {
+                    #(#fields)*
+                })
+            }
+        }
+    })
+}
+
+pub fn gen(wsdl: &Wsdl) -> Result<String, GenError> {
+    gen_with_options(wsdl, &CodegenOptions::default())
+}
+
+pub fn gen_with_options(wsdl: &Wsdl, options: &CodegenOptions) -> Result<String, GenError> {
+    check_supported(wsdl)?;
+    let types = wsdl
+        .types
+        .iter()
+        .map(|(name, t)| gen_type(wsdl, name, t, options))
+        .collect::<Vec<_>>();
+
+    let mut stream: TokenStream = quote! {
+        use savon::internal::xmltree;
+        use savon::rpser::xml::*;
+
+        #(#types)*
+    };
+    stream.extend(gen_service(wsdl, options));
+    stream.extend(gen_conversions(wsdl, options));
+
+    Ok(stream.to_string())
+}
+
+/// Generates the conversions between similar complex types, when
+/// `CodegenOptions::conversion_threshold` is set.
+fn gen_conversions(wsdl: &Wsdl, options: &CodegenOptions) -> Vec<TokenStream> {
+    let mut complex_types = wsdl
+        .types
+        .iter()
+        .filter_map(|(name, t)| match t {
+            // abstract types are traits, without fields to convert
+            Type::Complex(c) if !c.abstract_ => Some((name, c)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    complex_types.sort_by(|a, b| a.0.cmp(b.0));
+
+    options
+        .conversion_threshold
+        .map(|threshold| {
+            complex_types
+                .iter()
+                .flat_map(|(from_name, from)| {
+                    complex_types
+                        .iter()
+                        .filter(move |(to_name, _)| to_name != from_name)
+                        .filter_map(move |(to_name, to)| {
+                            conversion(from_name, from, to_name, to, threshold, options)
+                        })
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+}
+
+/// Generates the code of `wsdl` in `output_dir`, rewriting only what changed
+/// since `previous_wsdl`.
+///
+/// Each type goes to `types/<type>.rs`, and the client, messages, faults and
+/// conversions to `service.rs`. `mod.rs` includes all of them. The types
+/// written are the ones `Wsdl::diff` reports as added or changed, along with
+/// the types whose fields refer to them, or all types without a previous
+/// WSDL. Files of removed types are deleted. A file is only written if its
+/// content changes, so untouched files keep their timestamps. Returns the
+/// paths written.
+///
+/// Fails with `GenError::FileCollision`, before writing anything, if two
+/// types would be written to the same file.
+pub fn incremental_generate(
+    wsdl: &Wsdl,
+    previous_wsdl: Option<&Wsdl>,
+    output_dir: &Path,
+    options: &CodegenOptions,
+) -> Result<Vec<PathBuf>, GenError> {
+    check_supported(wsdl)?;
+    let mut names = wsdl.types.keys().collect::<Vec<_>>();
+    names.sort();
+    let mut files = HashMap::new();
+    for name in &names {
+        if let Some(other) = files.insert(name.to_snake(), *name) {
+            return Err(GenError::FileCollision(other.clone(), name.to_string()));
+        }
+    }
+
+    let types_dir = output_dir.join("types");
+    std::fs::create_dir_all(&types_dir)?;
+    let type_path = |name: &str| types_dir.join(format!("{}.rs", name.to_snake()));
+
+    let regenerate = previous_wsdl.map(|previous| {
+        let diff = wsdl.diff(previous);
+        for name in &diff.removed_types {
+            // the type may be gone from the output directory already
+            let _ = std::fs::remove_file(type_path(name));
+        }
+
+        let modified = diff
+            .added_types
+            .iter()
+            .chain(&diff.changed_types)
+            .chain(&diff.removed_types)
+            .collect::<HashSet<_>>();
+        wsdl.types
+            .iter()
+            .filter(|(name, t)| {
+                modified.contains(name)
+                    || match t {
+                        Type::Complex(c) => c.fields.values().any(
+                            |(_, f)| matches!(f, SimpleType::Complex(s) if modified.contains(s)),
+                        ),
+                        Type::Alias(target) => modified.contains(target),
+                        _ => false,
+                    }
+            })
+            .map(|(name, _)| name.clone())
+            .collect::<HashSet<_>>()
+    });
+
+    let mut written = Vec::new();
+    for name in &names {
+        let path = type_path(name);
+        let selected = regenerate
+            .as_ref()
+            .map(|r| r.contains(*name))
+            .unwrap_or(true);
+        if selected || !path.exists() {
+            let code = gen_type(wsdl, name, &wsdl.types[*name], options).to_string();
+            write_if_changed(&path, &code, &mut written)?;
+        }
+    }
+
+    let mut service = gen_service(wsdl, options);
+    service.extend(gen_conversions(wsdl, options));
+    write_if_changed(
+        &output_dir.join("service.rs"),
+        &service.to_string(),
+        &mut written,
+    )?;
+
+    let mut module = String::from("use savon::internal::xmltree;\nuse savon::rpser::xml::*;\n\n");
+    for name in &names {
+        module.push_str(&format!("include!(\"types/{}.rs\");\n", name.to_snake()));
+    }
+    module.push_str("include!(\"service.rs\");\n");
+    write_if_changed(&output_dir.join("mod.rs"), &module, &mut written)?;
+
+    Ok(written)
+}
+
+fn write_if_changed(
+    path: &Path,
+    content: &str,
+    written: &mut Vec<PathBuf>,
+) -> Result<(), GenError> {
+    if std::fs::read(path).ok().as_deref() != Some(content.as_bytes()) {
+        std::fs::write(path, content)?;
+        written.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+/// Generates the definition of a type and its (de)serialization.
+fn gen_type(wsdl: &Wsdl, name: &str, t: &Type, options: &CodegenOptions) -> TokenStream {
+    if let Type::Complex(c) = t {
+        if c.abstract_ {
+            return gen_abstract_type(wsdl, name, options);
+        }
+        let type_name = type_ident(name, options);
+
+        let fields = c
+            .fields
+            .iter()
+            .map(|(field_name, (attributes, field_type))| {
+                let fname = Ident::new(&field_name.to_snake(), Span::call_site());
+                let ft = match field_type {
+                    SimpleType::Complex(s) => complex_type_path(wsdl, s, options),
+                    _ => simple_type_path(field_type, options),
                 };
 
-                let fields_deserialize_impl = c
-                    .fields
-                    .iter()
-                    .map(|(field_name, (attributes, field_type))| {
-                        let fname = Ident::new(&field_name.to_snake(), Span::call_site());
+                let ft = if attributes.is_repeated() {
+                    quote! { Vec<#ft> }
+                } else {
+                    ft
+                };
+                let ft = if attributes.nillable {
+                    quote! { Option<#ft> }
+                } else {
+                    ft
+                };
+
+                let doc = attributes
+                    .documentation
+                    .as_ref()
+                    .map(|d| quote! { #[doc = #d] });
+
+                quote! {
+                    #doc
+                    pub #fname: #ft,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let fields_serialize_impl = c
+            .fields
+            .iter()
+            .filter(|(field_name, _)| !c.is_attribute(field_name))
+            .map(|(field_name, (attributes, field_type))| {
+                let fname = Ident::new(&field_name.to_snake(), Span::call_site());
+                //FIXME: handle more complex types
+                /*let ft = match field_type {
+                    SimpleType::Boolean => Ident::new("bool", Span::call_site()),
+                    SimpleType::String => Ident::new("String", Span::call_site()),
+                    SimpleType::Float => Ident::new("f64", Span::call_site()),
+                    SimpleType::Int => Ident::new("i64", Span::call_site()),
+                    SimpleType::DateTime => Ident::new("String", Span::call_site()),
+                    SimpleType::Complex(s) => Ident::new(&s, Span::call_site()),
+                };*/
+                // qualified fields are in the namespace of the schema declaring
+                // their type, bound to the prefix on the element itself
+                let prefix = match c.field_form(field_name) {
+                    ElementForm::Qualified => {
+                        let ftype = Literal::string(&format!("{}:{}", TARGET_PREFIX, field_name));
+                        let xmlns = Literal::string(&format!("xmlns:{}", TARGET_PREFIX));
+                        let namespace = Literal::string(wsdl.type_namespace(name));
+                        quote! { xmltree::Element::node(#ftype).with_attr(#xmlns, #namespace) }
+                    }
+                    ElementForm::Unqualified => {
                         let ftype = Literal::string(field_name);
+                        quote! { xmltree::Element::node(#ftype) }
+                    }
+                };
 
-                        let prefix = quote!{ #fname: element.get_at_path(&[#ftype]) };
+                match attributes.is_repeated() {
+                    true => {
+                        if attributes.nillable {
+                            quote! {
+                                self.#fname.as_ref().map(|v| v.iter().map(|i| {
+                                    i.fill_element(#prefix)
+                                }).collect()).unwrap_or_else(Vec::new)
+                            }
+                        } else {
+                            quote! {
+                                self.#fname.iter().map(|i| {
+                                    i.fill_element(#prefix)
+                                }).collect()
+                            }
+                        }
+                    }
+                    false => {
+                        let value = quote! { self.#fname };
+                        let value = if attributes.nillable {
+                            quote! { v }
+                        } else {
+                            value
+                        };
+                        let element = match &field_text_type(wsdl, field_type) {
+                            SimpleType::Complex(s) if !is_text_type(wsdl, s) => {
+                                quote! { #value.fill_element(#prefix) }
+                            }
+                            SimpleType::DateTime => {
+                                quote! { #prefix.with_text(#value.to_rfc3339()) }
+                            }
+                            SimpleType::HexBinary => {
+                                quote! { #prefix.with_text(savon::gen::HexBinary::to_hex(&#value)) }
+                            }
+                            _ => quote! { #prefix.with_text(#value.to_string()) },
+                        };
+
+                        // absent nillable values are sent as nil elements
+                        if attributes.nillable {
+                            quote! {
+                                vec![match &self.#fname {
+                                    Some(v) => #element,
+                                    None => #prefix.with_attr("xsi:nil", "true"),
+                                }]
+                            }
+                        } else {
+                            quote! { vec![#element] }
+                        }
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let attributes_serialize_impl = c
+            .fields
+            .iter()
+            .filter(|(field_name, _)| c.is_attribute(field_name))
+            .map(|(field_name, (attributes, field_type))| {
+                let fname = Ident::new(&field_name.to_snake(), Span::call_site());
+                let attribute_name = Literal::string(field_name);
+                let value = if attributes.nillable {
+                    quote! { v }
+                } else {
+                    quote! { self.#fname }
+                };
+                let text = write_text(&field_text_type(wsdl, field_type), value);
+
+                if attributes.nillable {
+                    quote! { self.#fname.as_ref().map(|v| (#attribute_name.to_string(), #text)) }
+                } else {
+                    quote! { Some((#attribute_name.to_string(), #text)) }
+                }
+            })
+            .collect::<Vec<_>>();
+        let to_attributes = if attributes_serialize_impl.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                fn to_attributes(&self) -> Vec<(String, String)> {
+                    vec![#(#attributes_serialize_impl),*].into_iter().flatten().collect()
+                }
+            }
+        };
+
+        let serialize_impl = if fields_serialize_impl.is_empty() {
+            quote! {
+                impl savon::gen::ToElements for #type_name {
+                    fn to_elements(&self) -> Vec<xmltree::Element> {
+                        vec![]
+                    }
+
+                    #to_attributes
+                }
+            }
+        } else {
+            quote! {
+                impl savon::gen::ToElements for #type_name {
+                    fn to_elements(&self) -> Vec<xmltree::Element> {
+                        vec![#(#fields_serialize_impl),*].drain(..).flatten().collect()
+                    }
+
+                    #to_attributes
+                }
+            }
+        };
+
+        let fields_deserialize_impl = c
+            .fields
+            .iter()
+            .map(|(field_name, (attributes, field_type))| {
+                let fname = Ident::new(&field_name.to_snake(), Span::call_site());
+                let ftype = Literal::string(field_name);
+
+                // attribute values are read as the text of an element of the same name
+                let prefix = if c.is_attribute(field_name) {
+                    quote!{ #fname: element.attributes.get(#ftype)
+                        .map(|v| xmltree::Element::node(#ftype).with_text(v.as_str()))
+                        .ok_or_else(|| savon::rpser::xml::Error::NotFoundAtPath { path: vec![#ftype.to_string()] }) }
+                } else {
+                    quote!{ #fname: element.get_at_path(&[#ftype]) }
+                };
+
+                match &field_text_type(wsdl, field_type) {
+                    SimpleType::Boolean => {
+                        let ft = quote!{ #prefix.and_then(|e| e.as_boolean()) };
+                        if attributes.nillable {
+                            quote!{ #ft.ok(),}
+                        } else {
+                            quote!{ #ft?,}
+                        }
+                    },
+                    SimpleType::String | SimpleType::Notation => {
+                        let ft = quote!{ #prefix.and_then(|e| e.get_text().map(|s| s.to_string())
+                                             .ok_or(savon::rpser::xml::Error::Empty)
+                                             ) };
+                        if attributes.nillable {
+                            quote!{ #ft.ok(),}
+                        } else {
+                            quote!{ #ft?,}
+                        }
+                    },
+                    SimpleType::Float => {
+                        let ft = quote!{ #prefix.map_err(savon::Error::from).and_then(|e| e.get_text()
+                                             .ok_or(savon::rpser::xml::Error::Empty)
+                                             .map_err(savon::Error::from)
+                                             .and_then(|s| s.parse().map_err(savon::Error::from))) };
+                        if attributes.nillable {
+                            quote!{ #ft.ok(),}
+                        } else {
+                            quote!{ #ft?,}
+                        }
+                    },
+                    SimpleType::Int => {
+                        let ft = quote!{ #prefix.and_then(|e| e.as_long()) };
+                        if attributes.nillable {
+                            quote!{ #ft.ok(),}
+                        } else {
+                            quote!{ #ft?,}
+                        }
+                    },
+                    SimpleType::DateTime => {
+                        let ft = quote!{ #prefix.map_err(savon::Error::from).and_then(|e| e.get_text()
+                                             .ok_or(savon::rpser::xml::Error::Empty)
+                                             .map_err(savon::Error::from)
+                                             .and_then(|s| savon::gen::parse_date_time(&s).map_err(savon::Error::from))) };
+                        if attributes.nillable {
+                            quote!{ #ft.ok(),}
+                        } else {
+                            quote!{ #ft?,}
+                        }
+                    },
+                    SimpleType::HexBinary => {
+                        let ft = quote!{ #prefix.map_err(savon::Error::from).and_then(|e| e.get_text()
+                                             .ok_or(savon::rpser::xml::Error::Empty)
+                                             .map_err(savon::Error::from)
+                                             .and_then(|s| savon::gen::HexBinary::from_hex(&s).map_err(savon::Error::from))) };
+                        if attributes.nillable {
+                            quote!{ #ft.ok(),}
+                        } else {
+                            quote!{ #ft?,}
+                        }
+                    },
+                    SimpleType::Decimal | SimpleType::QName | SimpleType::NCName | SimpleType::Name => {
+                        let path = simple_type_path(field_type, options);
+                        let ft = quote!{ #prefix.map_err(savon::Error::from).and_then(|e| e.get_text()
+                                             .ok_or(savon::rpser::xml::Error::Empty)
+                                             .map_err(savon::Error::from)
+                                             .and_then(|s| s.parse::<#path>().map_err(savon::Error::from))) };
+                        if attributes.nillable {
+                            quote!{ #ft.ok(),}
+                        } else {
+                            quote!{ #ft?,}
+                        }
+                    },
+                    SimpleType::Complex(s) if is_text_type(wsdl, s) => {
+                        let enum_type = type_ident(s, options);
+                        let ft = quote!{ #prefix.map_err(savon::Error::from).and_then(|e| e.get_text()
+                                             .ok_or(savon::rpser::xml::Error::Empty)
+                                             .map_err(savon::Error::from)
+                                             .and_then(|s| s.parse::<#enum_type>().map_err(savon::Error::from))) };
+                        if attributes.nillable {
+                            quote!{ #ft.ok(),}
+                        } else {
+                            quote!{ #ft?,}
+                        }
+                    },
+                    SimpleType::Complex(s) => {
+                        let complex_type = complex_type_path(wsdl, s, options);
+                        let complex_type = quote! { <#complex_type as savon::gen::FromElement> };
+
+                        match attributes.is_repeated() {
+                            true => {
+                                let ft = quote! {
+                                    {
+                                        let mut v = vec![];
+                                        for elem in element.children.iter()
+                                            .filter_map(|c| c.as_element()) {
+                                                v.push(#complex_type::from_element(&elem)?);
+                                            }
+                                        v
+                                    },
+                                };
 
-                        match field_type {
-                            SimpleType::Boolean => {
-                                let ft = quote!{ #prefix.and_then(|e| e.as_boolean()) };
-                                if attributes.nillable {
-                                    quote!{ #ft.ok(),}
-                                } else {
-                                    quote!{ #ft?,}
-                                }
-                            },
-                            SimpleType::String => {
-                                let ft = quote!{ #prefix.and_then(|e| e.get_text().map(|s| s.to_string())
-                                                     .ok_or(savon::rpser::xml::Error::Empty)
-                                                     ) };
-                                if attributes.nillable {
-                                    quote!{ #ft.ok(),}
-                                } else {
-                                    quote!{ #ft?,}
-                                }
-                            },
-                            SimpleType::Float => {
-                                let ft = quote!{ #prefix.map_err(savon::Error::from).and_then(|e| e.get_text()
-                                                     .ok_or(savon::rpser::xml::Error::Empty)
-                                                     .map_err(savon::Error::from)
-                                                     .and_then(|s| s.parse().map_err(savon::Error::from))) };
-                                if attributes.nillable {
-                                    quote!{ #ft.ok(),}
-                                } else {
-                                    quote!{ #ft?,}
-                                }
-                            },
-                            SimpleType::Int => {
-                                let ft = quote!{ #prefix.and_then(|e| e.as_long()) };
                                 if attributes.nillable {
-                                    quote!{ #ft.ok(),}
+                                    quote!{ #fname: Some(#ft) }
                                 } else {
-                                    quote!{ #ft?,}
+                                    quote!{ #fname: #ft }
                                 }
                             },
-                            SimpleType::DateTime => {
-                                let ft = quote!{
-                                    #prefix.and_then(|e| e.get_text()
-                                                     .ok_or(savon::rpser::xml::Error::Empty)
-                                                     ).map_err(savon::Error::from)
-                                    .and_then(|s|
-                                              s.parse::<savon::internal::chrono::DateTime<savon::internal::chrono::offset::Utc>>().map_err(savon::Error::from))
-                                };
+                            false => {
+                                let ft = quote!{ #prefix.map_err(savon::Error::from).and_then(|e| #complex_type::from_element(&e).map_err(savon::Error::from)) };
                                 if attributes.nillable {
                                     quote!{ #ft.ok(),}
                                 } else {
                                     quote!{ #ft?,}
                                 }
-                            },
-                            SimpleType::Complex(s) => {
-                                let complex_type = Ident::new(&s.to_camel(), Span::call_site());
-
-                                match (attributes.min_occurs.as_ref(), attributes.max_occurs.as_ref()) {
-                                    (Some(_), Some(_)) => {
-                                        let ft = quote! {
-                                            {
-                                                let mut v = vec![];
-                                                for elem in element.children.iter()
-                                                    .filter_map(|c| c.as_element()) {
-                                                        v.push(#complex_type::from_element(&elem)?);
-                                                    }
-                                                v
-                                            },
-                                        };
-
-                                        if attributes.nillable {
-                                            quote!{ #fname: Some(#ft) }
-                                        } else {
-                                            quote!{ #fname: #ft }
-                                        }
-                                    },
-                                    _ => {
-                                        let ft = quote!{ #prefix.map_err(savon::Error::from).and_then(|e| #complex_type::from_element(&e).map_err(savon::Error::from)) };
-                                        if attributes.nillable {
-                                            quote!{ #ft.ok(),}
-                                        } else {
-                                            quote!{ #ft?,}
-                                        }
-                                    }
-                                }
-                            },
+                            }
                         }
-                    })
-                    .collect::<Vec<_>>();
+                    },
+                }
+            })
+            .collect::<Vec<_>>();
 
-                    let deserialize_impl = if fields_deserialize_impl.is_empty() {
-                        quote! {
-                            impl savon::gen::FromElement for #type_name {
-                                fn from_element(_element: &xmltree::Element) -> Result<Self, savon::Error> {
-                                    Ok(#type_name {
-                                    })
-                                }
-                            }
+        let deserialize_impl = if fields_deserialize_impl.is_empty() {
+            quote! {
+                impl savon::gen::FromElement for #type_name {
+                    fn from_element(_element: &xmltree::Element) -> Result<Self, savon::Error> {
+                        Ok(#type_name {
+                        })
+                    }
+                }
+            }
+        } else {
+            quote! {
+                impl savon::gen::FromElement for #type_name {
+                    fn from_element(element: &xmltree::Element) -> Result<Self, savon::Error> {
+                        Ok(#type_name {
+                            #(#fields_deserialize_impl)*
+                        })
+                    }
+                }
+            }
+        };
+
+        let validate_impl = gen_assertions(name, c, options);
+
+        let substitution_impl = c
+            .substitution_group
+            .as_ref()
+            .filter(|head| is_abstract(wsdl, head))
+            .map(|head| {
+                let head = type_ident(head, options);
+                let namespace = Literal::string(wsdl.type_namespace(name));
+                let name = Literal::string(name);
+                quote! {
+                    impl #head for #type_name {
+                        fn type_name(&self) -> &'static str {
+                            #name
                         }
-                    } else {
-                        quote! {
-                            impl savon::gen::FromElement for #type_name {
-                                fn from_element(element: &xmltree::Element) -> Result<Self, savon::Error> {
-                                    Ok(#type_name {
-                                        #(#fields_deserialize_impl)*
-                                    })
-                                }
-                            }
+
+                        fn type_namespace(&self) -> &'static str {
+                            #namespace
+                        }
+
+                        fn clone_box(&self) -> Box<dyn #head> {
+                            Box::new(self.clone())
                         }
-                    };
+                    }
+                }
+            });
 
+        quote! {
+            #[derive(Clone, Debug, Default)]
+            pub struct #type_name {
+                #(#fields)*
+            }
+
+            #serialize_impl
+
+            #deserialize_impl
+
+            #validate_impl
+
+            #substitution_impl
+        }
+    } else if let Type::Alias(target) = t {
+        // aliases reuse the definition of the type they point to
+        let type_name = type_ident(name, options);
+        let target_name = type_ident(target, options);
+
+        quote! {
+            pub type #type_name = #target_name;
+        }
+    } else if let Type::Enumeration(values) = t {
+        let type_name = type_ident(name, options);
+        let type_str = Literal::string(&rust_type_name(name, options));
+        let variants = enum_variants(values);
+        let first_variant = &variants[0];
+        // Display and FromStr use the values from the WSDL, as sent on the wire
+        let values = values
+            .iter()
+            .map(|v| Literal::string(v))
+            .collect::<Vec<_>>();
+
+        quote! {
+            #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+            pub enum #type_name {
+                #(#variants,)*
+            }
+
+            impl Default for #type_name {
+                fn default() -> Self {
+                    #type_name::#first_variant
+                }
+            }
+
+            impl std::fmt::Display for #type_name {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    match self {
+                        #(#type_name::#variants => f.write_str(#values),)*
+                    }
+                }
+            }
+
+            impl std::str::FromStr for #type_name {
+                type Err = savon::gen::EnumParseError;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    match s {
+                        #(#values => Ok(#type_name::#variants),)*
+                        _ => Err(savon::gen::EnumParseError {
+                            type_name: #type_str,
+                            value: s.to_string(),
+                        }),
+                    }
+                }
+            }
+        }
+    } else if let Type::Decimal {
+        total_digits,
+        fraction_digits,
+    } = t
+    {
+        let type_name = type_ident(name, options);
+        let constant = |name: &str, digits: &Option<u32>| {
+            let ident = Ident::new(name, Span::call_site());
+            match digits {
+                Some(d) => (
+                    quote! { pub const #ident: u32 = #d; },
+                    quote! { Some(Self::#ident) },
+                ),
+                None => (quote! {}, quote! { None }),
+            }
+        };
+        let (total_const, total) = constant("TOTAL_DIGITS", total_digits);
+        let (fraction_const, fraction) = constant("FRACTION_DIGITS", fraction_digits);
+
+        quote! {
+            #[derive(Clone, Debug, Default, PartialEq)]
+            pub struct #type_name(savon::gen::Decimal);
+
+            impl #type_name {
+                #total_const
+                #fraction_const
+
+                /// Wraps `value`, checking that it fits the digits of the restriction.
+                pub fn new(value: savon::gen::Decimal) -> Result<Self, savon::gen::DecimalError> {
+                    value.check_digits(#total, #fraction)?;
+                    Ok(#type_name(value))
+                }
+
+                pub fn value(&self) -> &savon::gen::Decimal {
+                    &self.0
+                }
+            }
+
+            impl std::fmt::Display for #type_name {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+
+            impl std::str::FromStr for #type_name {
+                type Err = savon::gen::DecimalError;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    #type_name::new(s.parse()?)
+                }
+            }
+        }
+    } else if let Type::Simple(simple_type) = t {
+        let type_name = type_ident(name, options);
+        let simple_type = simple_type_path(simple_type, options);
+
+        quote! {
+            pub type #type_name = #simple_type;
+        }
+    } else {
+        unreachable!();
+    }
+}
+
+/// Generates the client, its operations, the messages and the fault enums.
+fn gen_service(wsdl: &Wsdl, options: &CodegenOptions) -> TokenStream {
+    let target_namespace = Literal::string(&wsdl.target_namespace);
+    let service_name = Ident::new(&wsdl.name, Span::call_site());
+
+    // operations are named as declared, their keys may be normalized.
+    // Notifications, without input, are called by the service instead
+    let operations = sorted(&wsdl.operations).into_iter().filter(|(_, operation)| operation.input.is_some()).map(|(_, operation)| {
+        let name = &operation.name;
+        let op_name = Ident::new(&name.to_snake(), Span::call_site());
+        let input_name = Ident::new(&operation.input.as_ref().unwrap().to_snake(), Span::call_site());
+        let input_type = Ident::new(&operation.input.as_ref().unwrap().to_camel(), Span::call_site());
+
+        let op_str = Literal::string(&name);
+
+        match (operation.output.as_ref(), operation.faults.as_ref()) {
+            (None, None) => {
                 quote! {
-                    #[derive(Clone, Debug, Default)]
-                    pub struct #type_name {
-                        #(#fields)*
+                    pub async fn #op_name(&self, #input_name: #input_type) -> Result<(), savon::Error> {
+                        savon::http::one_way(&self.client, &self.base_url, &Self::operation(#op_str), &#input_name, &self.interceptors).await
                     }
+                }
+            },
+            (None, Some(_)) => {
+                let err_name = Ident::new(&format!("{}Error", name.to_camel()), Span::call_site());
 
-                    #serialize_impl
+                quote! {
+                    pub async fn #op_name(&self, #input_name: #input_type) -> Result<(), #err_name> {
+                        savon::http::request_fault(&self.client, &self.base_url, &Self::operation(#op_str), &#input_name, &self.interceptors).await
+                    }
+                }
+            },
+            (Some(out), None) => {
+                let out_name = Ident::new(&out, Span::call_site());
 
-                    #deserialize_impl
+                quote! {
+                    pub async fn #op_name(&self, #input_name: #input_type) -> Result<Result<#out_name, ()>, savon::Error> {
+                        savon::http::request_response(&self.client, &self.base_url, &Self::operation(#op_str), &#input_name, &self.interceptors).await
+                    }
+                }
+            },
+            (Some(out), Some(_)) => {
+                let out_name = Ident::new(&out, Span::call_site());
+                let err_name = Ident::new(&format!("{}Error", name.to_camel()), Span::call_site());
+
+                quote! {
+                    pub async fn #op_name(&self, #input_name: #input_type) -> Result<#out_name, #err_name> {
+                        savon::http::request_fault_response(&self.client, &self.base_url, &Self::operation(#op_str), &#input_name, &self.interceptors).await
+                    }
+                }
+            },
+        }
+    }).collect::<Vec<_>>();
+
+    // methods of the service trait, implemented by the client and by the mock
+    let service_methods = sorted(&wsdl.operations)
+        .into_iter()
+        .filter_map(|(_, operation)| {
+            let name = &operation.name;
+            let op_name = Ident::new(&name.to_snake(), Span::call_site());
+            let input_type = Ident::new(&operation.input.as_ref()?.to_camel(), Span::call_site());
+            let op_str = Literal::string(name);
+
+            let (output, mock) = match (operation.output.as_ref(), operation.faults.as_ref()) {
+                (None, None) => (
+                    quote! { Result<(), savon::Error> },
+                    quote! {
+                        self.calls.record(#op_str);
+                        Ok(())
+                    },
+                ),
+                (None, Some(_)) => {
+                    let err_name =
+                        Ident::new(&format!("{}Error", name.to_camel()), Span::call_site());
+                    (
+                        quote! { Result<(), #err_name> },
+                        quote! {
+                            self.calls.record(#op_str);
+                            Ok(())
+                        },
+                    )
+                }
+                (Some(out), None) => {
+                    let out_name = Ident::new(out, Span::call_site());
+                    (
+                        quote! { Result<Result<#out_name, ()>, savon::Error> },
+                        quote! { self.calls.respond(#op_str).map(Ok) },
+                    )
+                }
+                (Some(out), Some(_)) => {
+                    let out_name = Ident::new(out, Span::call_site());
+                    let err_name =
+                        Ident::new(&format!("{}Error", name.to_camel()), Span::call_site());
+                    (
+                        quote! { Result<#out_name, #err_name> },
+                        quote! { self.calls.respond(#op_str).map_err(#err_name::from) },
+                    )
+                }
+            };
+
+            Some((op_name, input_type, output, mock))
+        })
+        .collect::<Vec<_>>();
+    let service_trait = Ident::new(&format!("{}Service", wsdl.name), Span::call_site());
+    let mock_service = Ident::new(&format!("{}MockService", wsdl.name), Span::call_site());
+    let trait_methods = service_methods
+        .iter()
+        .map(|(op_name, input_type, output, _)| {
+            quote! {
+                fn #op_name(&self, input: #input_type) -> savon::gen::BoxFuture<'_, #output>;
+            }
+        });
+    let client_methods = service_methods
+        .iter()
+        .map(|(op_name, input_type, output, _)| {
+            quote! {
+                fn #op_name(&self, input: #input_type) -> savon::gen::BoxFuture<'_, #output> {
+                    Box::pin(async move { #service_name::#op_name(self, input).await })
                 }
-            } else {
-                panic!();
             }
+        });
+    let mock_methods = service_methods
+        .iter()
+        .map(|(op_name, input_type, output, mock)| {
+            quote! {
+                fn #op_name(&self, _: #input_type) -> savon::gen::BoxFuture<'_, #output> {
+                    let output = { #mock };
+                    Box::pin(async move { output })
+                }
+            }
+        });
+
+    // notifications sent by the service to the client, read from their output element
+    let notifications = sorted(&wsdl.operations)
+        .into_iter()
+        .filter(|(_, operation)| operation.input.is_none())
+        .filter_map(|(_, operation)| {
+            let output = operation.output.as_ref()?;
+            let op_name = Ident::new(&operation.name.to_snake(), Span::call_site());
+            let out_name = Ident::new(output, Span::call_site());
+            let element = Literal::string(&wsdl.messages.get(output)?.part_element);
+            Some((op_name, out_name, element))
         })
         .collect::<Vec<_>>();
+    let callback_handler = if notifications.is_empty() {
+        quote! {}
+    } else {
+        let handler = Ident::new(&format!("{}CallbackHandler", wsdl.name), Span::call_site());
+        let handler_str = Literal::string(&handler.to_string());
+        let methods = notifications.iter().map(|(op_name, out_name, _)| {
+            quote! {
+                fn #op_name(&self, notification: #out_name) -> savon::gen::BoxFuture<'_, Result<(), savon::Error>>;
+            }
+        });
+        let arms = notifications.iter().map(|(op_name, out_name, element)| {
+            quote! {
+                #element => #handler::#op_name(self, <#out_name as savon::gen::FromElement>::from_element(body)?).await,
+            }
+        });
 
-    let messages = wsdl
-        .messages
-        .iter()
+        quote! {
+            /// Notifications sent by the service to the client.
+            ///
+            /// To receive them, serve an endpoint passing the root element of
+            /// the body of each request it receives to `dispatch`, then
+            /// register the URL of this endpoint with the service as it
+            /// documents, usually with a subscription operation or a
+            /// WS-Addressing `ReplyTo` header.
+            pub trait #handler: Sync {
+                #(#methods)*
+
+                /// Calls the method of the notification whose output element is `body`.
+                fn dispatch<'a>(&'a self, body: &'a xmltree::Element) -> savon::gen::BoxFuture<'a, Result<(), savon::Error>> {
+                    Box::pin(async move {
+                        match body.name.as_str() {
+                            #(#arms)*
+                            name => Err(savon::gen::EnumParseError {
+                                type_name: #handler_str,
+                                value: name.to_string(),
+                            }
+                            .into()),
+                        }
+                    })
+                }
+            }
+        }
+    };
+
+    let messages = sorted(&wsdl.messages)
+        .into_iter()
         .map(|(message_name, message)| {
             let mname = Ident::new(&message_name, Span::call_site());
-            let iname = Ident::new(&message.part_element, Span::call_site());
+            let iname = type_ident(&message.part_element, options);
+
+            // messages of several parts have a field per part, written as an
+            // element named after the part so that its binding can place it
+            if message.parts.len() > 1 {
+                let fields = message
+                    .parts
+                    .iter()
+                    .map(|part| Ident::new(&part.name.to_snake(), Span::call_site()))
+                    .collect::<Vec<_>>();
+                let types = message
+                    .parts
+                    .iter()
+                    .map(|part| type_ident(&part.element, options))
+                    .collect::<Vec<_>>();
+                let names = message
+                    .parts
+                    .iter()
+                    .map(|part| Literal::string(&part.name))
+                    .collect::<Vec<_>>();
+                // parts of simple types are the text of their element
+                let (writes, reads): (Vec<_>, Vec<_>) = message
+                    .parts
+                    .iter()
+                    .zip(&fields)
+                    .zip(&names)
+                    .map(|((part, field), name)| {
+                        let node = quote! { xmltree::Element::node(#name) };
+                        match text_part(wsdl, &part.element) {
+                            Some(text_type) => {
+                                let text = write_text(&text_type, quote! { self.#field });
+                                let read = read_text(&text_type, quote! { e }, options);
+                                (quote! { #node.with_text(#text) }, read)
+                            }
+                            None => {
+                                let part_type = type_ident(&part.element, options);
+                                (
+                                    quote! { self.#field.fill_element(#node) },
+                                    quote! { #part_type::from_element(&e) },
+                                )
+                            }
+                        }
+                    })
+                    .unzip();
+
+                return quote! {
+                    #[derive(Clone, Debug, Default)]
+                    pub struct #mname {
+                        #(pub #fields: #types,)*
+                    }
+
+                    impl savon::gen::ToElements for #mname {
+                        fn to_elements(&self) -> Vec<xmltree::Element> {
+                            vec![#(#writes),*]
+                        }
+                    }
+
+                    impl savon::gen::FromElement for #mname {
+                        fn from_element(element: &xmltree::Element) -> Result<Self, savon::Error> {
+                            Ok(#mname {
+                                #(#fields: element.get_at_path(&[#names])
+                                    .map_err(savon::Error::from)
+                                    .and_then(|e| #reads)?,)*
+                            })
+                        }
+                    }
+                };
+            }
+
+            if let Some(text_type) = text_part(wsdl, &message.part_element) {
+                let text = write_text(&text_type, quote! { self.0 });
+                let read = read_text(&text_type, quote! { element }, options);
+
+                // the value is the text of the body element
+                return quote! {
+                    #[derive(Clone, Debug, Default)]
+                    pub struct #mname(pub #iname);
+
+                    impl savon::gen::ToElements for #mname {
+                        fn to_elements(&self) -> Vec<xmltree::Element> {
+                            vec![]
+                        }
+
+                        fn to_text(&self) -> Option<String> {
+                            Some(#text)
+                        }
+                    }
+
+                    impl savon::gen::FromElement for #mname {
+                        fn from_element(element: &xmltree::Element) -> Result<Self, savon::Error> {
+                            #read.map(#mname)
+                        }
+                    }
+                };
+            }
 
             quote! {
                 #[derive(Clone, Debug, Default)]
@@ -368,17 +2054,66 @@ pub fn gen(wsdl: &Wsdl) -> Result<String, GenError> {
         })
         .collect::<Vec<_>>();
 
-    let service_name = Ident::new(&wsdl.name, Span::call_site());
+    // SOAPAction headers of the operations, as declared by their binding
+    let soap_actions = sorted(&wsdl.operations)
+        .into_iter()
+        .map(|(_, operation)| {
+            let op_str = Literal::string(&operation.name);
+            let soap_action = Literal::string(&operation.soap_action_header(true));
+            quote! { #op_str => #soap_action, }
+        })
+        .collect::<Vec<_>>();
 
-    let toks = quote! {
-        use savon::internal::xmltree;
-        use savon::rpser::xml::*;
+    // operations as sent by the client, with the parts their binding places
+    // out of the body. Parts of single part messages are the input itself
+    let operation_calls = sorted(&wsdl.operations)
+        .into_iter()
+        .map(|(key, operation)| {
+            let op_str = Literal::string(&operation.name);
+            let soap_action = Literal::string(&operation.soap_action_header(true));
+            let message = operation
+                .input
+                .as_ref()
+                .and_then(|input| wsdl.messages.get(input));
+            let parts = message.map_or(0, |message| message.parts.len());
+            // the body element is in the namespace of the input element
+            let namespace = Literal::string(message.map_or(&wsdl.target_namespace, |message| {
+                wsdl.type_namespace(&message.part_element)
+            }));
+            let layout = wsdl
+                .binding_for_operation(key)
+                .filter(|_| parts > 1)
+                .and_then(|binding| request_layout(wsdl, &binding.name, key).ok())
+                .unwrap_or_default();
+            let header_parts = layout.header_parts.iter().map(|p| Literal::string(p));
+            let omitted_parts = layout.omitted_parts.iter().map(|p| Literal::string(p));
+            let body_order = layout.body_order.iter().map(|p| Literal::string(p));
 
-        #(#types)*
+            quote! {
+                #op_str => savon::http::OperationCall {
+                    name: #op_str,
+                    namespace: #namespace,
+                    soap_action: #soap_action,
+                    header_parts: &[#(#header_parts),*],
+                    omitted_parts: &[#(#omitted_parts),*],
+                    body_order: &[#(#body_order),*],
+                },
+            }
+        })
+        .collect::<Vec<_>>();
 
+    let toks = quote! {
+        /// Client of the service.
+        ///
+        /// The operations, messages and types of the WSDL are compiled in. The
+        /// parsed `Wsdl`, when needed at run time, is shared through an `Arc`
+        /// set by `with_wsdl`, so the client has no lifetime parameter and can
+        /// be moved across async tasks.
         pub struct #service_name {
             pub base_url: String,
             pub client: savon::internal::reqwest::Client,
+            pub interceptors: Vec<Box<dyn savon::http::RequestInterceptor>>,
+            pub wsdl: Option<std::sync::Arc<savon::wsdl::Wsdl>>,
         }
         #(#messages)*
 
@@ -388,51 +2123,178 @@ pub fn gen(wsdl: &Wsdl) -> Result<String, GenError> {
                 Self::with_client(base_url, savon::internal::reqwest::Client::new())
             }
 
+            /// Creates the service with a client configured by `builder`.
+            pub fn with_builder(base_url: String, builder: savon::http::ClientBuilder) -> Result<Self, savon::Error> {
+                Ok(Self::with_client(base_url, builder.build()?))
+            }
+
+            /// Creates the service with `client`, whose connections are reused by every call.
             pub fn with_client(base_url: String, client: savon::internal::reqwest::Client) -> Self {
                 #service_name {
                     base_url,
                     client,
+                    interceptors: Vec::new(),
+                    wsdl: None,
+                }
+            }
+
+            /// Shares the parsed `Wsdl` of the service with the client.
+            pub fn with_wsdl(mut self, wsdl: std::sync::Arc<savon::wsdl::Wsdl>) -> Self {
+                self.wsdl = Some(wsdl);
+                self
+            }
+
+            pub fn with_interceptor(mut self, interceptor: impl savon::http::RequestInterceptor + 'static) -> Self {
+                self.interceptors.push(Box::new(interceptor));
+                self
+            }
+
+            /// Value of the `SOAPAction` header sent for `operation`.
+            pub fn soap_action(operation: &str) -> &'static str {
+                match operation {
+                    #(#soap_actions)*
+                    _ => "\"\"",
+                }
+            }
+
+            /// Operation named `operation`, as sent by the client.
+            pub fn operation(operation: &str) -> savon::http::OperationCall<'_> {
+                match operation {
+                    #(#operation_calls)*
+                    _ => savon::http::OperationCall {
+                        name: operation,
+                        namespace: #target_namespace,
+                        soap_action: "\"\"",
+                        ..Default::default()
+                    },
                 }
             }
 
+            /// Calls `operation` and returns the response body along with the SOAP header elements.
+            pub async fn call<Input: savon::gen::ToElements>(&self, operation: &str, input: &Input) -> Result<savon::http::CallResponse, savon::Error> {
+                savon::http::call(&self.client, &self.base_url, &Self::operation(operation), input, &self.interceptors).await
+            }
+
             #(#operations)*
         }
+
+        /// Operations of the service, implemented by the client and, for
+        /// tests, by a mock.
+        pub trait #service_trait {
+            #(#trait_methods)*
+        }
+
+        impl #service_trait for #service_name {
+            #(#client_methods)*
+        }
+
+        /// Implementation of the service for tests, recording the calls and
+        /// returning the responses queued with `expect_call`.
+        #[derive(Debug, Default)]
+        pub struct #mock_service {
+            pub calls: savon::gen::MockCalls,
+        }
+
+        #[allow(dead_code)]
+        impl #mock_service {
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            /// Queues `response`, the XML of the output element, for the next call of `operation`.
+            pub fn expect_call(&self, operation: &str, response: Vec<u8>) {
+                self.calls.expect_call(operation, response)
+            }
+
+            /// Panics if `operation` was never called.
+            pub fn assert_called(&self, operation: &str) {
+                self.calls.assert_called(operation)
+            }
+        }
+
+        impl #service_trait for #mock_service {
+            #(#mock_methods)*
+        }
+
+        #callback_handler
     };
 
-    let operation_faults = wsdl
-        .operations
-        .iter()
+    let operation_faults = sorted(&wsdl.operations)
+        .into_iter()
         .filter(|(_, op)| op.faults.is_some())
-        .map(|(name, operation)| {
-            let op_error = Ident::new(&format!("{}Error", name), Span::call_site());
+        .map(|(_, operation)| {
+            let name = &operation.name;
+            let op_error = Ident::new(&format!("{}Error", name.to_camel()), Span::call_site());
 
-            let faults = operation
-                .faults
-                .as_ref()
-                .unwrap()
+            let faults = operation.faults.as_ref().unwrap();
+            let variants = faults
                 .iter()
                 .map(|fault| {
-                    let fault_name = Ident::new(&fault, Span::call_site());
+                    let fault_name = Ident::new(&fault.name.to_camel(), Span::call_site());
+                    let fault_message = Ident::new(&fault.message, Span::call_site());
 
                     quote! {
-                          #fault_name(#fault_name),
+                          #fault_name(#fault_message),
                     }
                 })
                 .collect::<Vec<_>>();
+            // faults are recognized by the element of their message
+            let detail_arms = faults
+                .iter()
+                .filter_map(|fault| {
+                    let element = &wsdl.messages.get(&fault.message)?.part_element;
+                    let element = Literal::string(element);
+                    let fault_name = Ident::new(&fault.name.to_camel(), Span::call_site());
+                    let fault_message = Ident::new(&fault.message, Span::call_site());
+
+                    Some(quote! {
+                        #element => <#fault_message as savon::gen::FromElement>::from_element(element).map(|f| Some(#op_error::#fault_name(f))),
+                    })
+                })
+                .collect::<Vec<_>>();
 
             quote! {
-                #[derive(Clone, Debug, Default)]
+                #[derive(Debug)]
                 pub enum #op_error {
-                    #(#faults)*
+                    #(#variants)*
+                    /// the request could not be sent, or the response could not be received
+                    Http(savon::internal::reqwest::Error),
+                    /// the response could not be read, or is an undeclared fault
+                    Parse(savon::Error),
+                }
+
+                impl From<savon::Error> for #op_error {
+                    fn from(error: savon::Error) -> Self {
+                        match error {
+                            savon::Error::Reqwest(e) => #op_error::Http(e),
+                            e => #op_error::Parse(e),
+                        }
+                    }
+                }
+
+                impl savon::gen::FromFault for #op_error {
+                    fn from_detail(element: &xmltree::Element) -> Result<Option<Self>, savon::Error> {
+                        match element.name.as_str() {
+                            #(#detail_arms)*
+                            _ => Ok(None),
+                        }
+                    }
                 }
             }
         })
         .collect::<Vec<_>>();
 
-    let mut stream: TokenStream = toks;
-    stream.extend(operation_faults);
+    quote! {
+        #toks
+        #(#operation_faults)*
+    }
+}
 
-    Ok(stream.to_string())
+/// Entries of `map` sorted by name, so that the generated code is stable.
+fn sorted<V>(map: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries = map.iter().collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
 }
 
 #[cfg(test)]
@@ -452,4 +2314,875 @@ mod tests {
         println!("generated:\n{}", res);
         panic!();
     }
+
+    #[test]
+    fn enumeration() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        wsdl.types.insert(
+            "Status".to_string(),
+            Type::Enumeration(vec!["active".to_string(), "on hold".to_string()]),
+        );
+
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains("pub enum Status { Active , OnHold , }"));
+        assert!(res.contains("Status :: OnHold => f . write_str (\"on hold\")"));
+        assert!(res.contains("\"on hold\" => Ok (Status :: OnHold)"));
+    }
+
+    #[test]
+    fn assertions() {
+        let source = std::str::from_utf8(EXAMPLE_WSDL).unwrap().replace(
+            "<complexType>",
+            r#"<complexType>
+            <assert test="@start &lt;= @end"/>
+            <assert test="@low != @high"/>
+            <assert test="count(price) > 0"/>"#,
+        );
+        let source = source.replace(
+            "<element name=\"tickerSymbol\" type=\"string\"/>",
+            r#"<element name="tickerSymbol" type="string"/>
+                  <element name="start" type="int"/>
+                  <element name="end" type="int"/>
+                  <element name="low" type="float" minOccurs="0"/>
+                  <element name="high" type="float"/>"#,
+        );
+        let wsdl = parse(source.as_bytes()).unwrap();
+        match &wsdl.types["TradePriceRequest"] {
+            Type::Complex(c) => assert_eq!(
+                c.assertions,
+                vec!["@start <= @end", "@low != @high", "count(price) > 0"]
+            ),
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains(
+            r#"# [doc = " TODO: `count(price) > 0` is not checked."] pub fn validate (& self) -> Result < () , savon :: gen :: AssertionError >"#
+        ));
+        assert!(res.contains(
+            r#"if ! (self . start <= self . end) { return Err (savon :: gen :: AssertionError { type_name : "TradePriceRequest" , test : "@start <= @end" }) ; }"#
+        ));
+        assert!(res.contains(
+            r#"if let (Some (left) , Some (right)) = (self . low . as_ref () , Some (& self . high)) { if ! (left != right) {"#
+        ));
+    }
+
+    #[test]
+    fn soap_actions() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains(
+            r#""GetLastTradePrice" => savon :: http :: OperationCall { name : "GetLastTradePrice" , namespace : "http://example.com/stockquote.xsd" , soap_action : "\"http://example.com/GetLastTradePrice\"" ,"#
+        ));
+        assert!(res.contains(
+            r#"savon :: http :: request_response (& self . client , & self . base_url , & Self :: operation ("GetLastTradePrice") ,"#
+        ));
+        assert!(res.contains(
+            r#""GetLastTradePrice" => "\"http://example.com/GetLastTradePrice\"" , _ => "\"\"" ,"#
+        ));
+    }
+
+    #[test]
+    fn encoded_bindings() {
+        let source = String::from_utf8(EXAMPLE_WSDL.to_vec()).unwrap().replace(
+            r#"<input>
+        <soap:body use="literal"/>"#,
+            r#"<input>
+        <soap:body use="encoded" encodingStyle="http://schemas.xmlsoap.org/soap/encoding/"/>"#,
+        );
+        let wsdl = parse(source.as_bytes()).unwrap();
+
+        assert!(matches!(
+            gen(&wsdl),
+            Err(GenError::Wsdl(WsdlError::UnsupportedFeature(_)))
+        ));
+    }
+
+    #[test]
+    fn overloaded_operations() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let overload = OperationBuilder::new()
+            .name("GetLastTradePrice")
+            .input("GetLastTradePriceOutput")
+            .build();
+        wsdl.overloads.insert(
+            "GetLastTradePrice".to_string(),
+            vec![wsdl.operations["GetLastTradePrice"].clone(), overload],
+        );
+
+        match gen(&wsdl) {
+            Err(GenError::Wsdl(WsdlError::UnsupportedFeature(feature))) => {
+                assert_eq!(feature, "overloaded operation GetLastTradePrice")
+            }
+            other => panic!("expected an unsupported feature, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn operation_namespace() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert_ne!(wsdl.target_namespace, "http://example.com/stockquote.xsd");
+
+        // the body element is bound to the namespace of the schema declaring it
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains(
+            r#"name : "GetLastTradePrice" , namespace : "http://example.com/stockquote.xsd" ,"#
+        ));
+    }
+
+    #[test]
+    fn bound_parts() {
+        let source = String::from_utf8(EXAMPLE_WSDL.to_vec())
+            .unwrap()
+            .replace(
+                r#"<part name="body" element="xsd1:TradePriceRequest"/>"#,
+                r#"<part name="body" element="xsd1:TradePriceRequest"/>
+    <part name="session" element="xsd1:TradePrice"/>"#,
+            )
+            .replace(
+                r#"<input>
+        <soap:body use="literal"/>"#,
+                r#"<input>
+        <soap:body parts="body" use="literal"/>
+        <soap:header message="tns:GetLastTradePriceInput" part="session" use="literal"/>"#,
+            );
+        let wsdl = parse(source.as_bytes()).unwrap();
+        let res = gen(&wsdl).unwrap();
+
+        assert!(res.contains(
+            "pub struct GetLastTradePriceInput { pub body : TradePriceRequest , pub session : TradePrice , }"
+        ));
+        assert!(res.contains(
+            r#"vec ! [self . body . fill_element (xmltree :: Element :: node ("body")) , self . session . fill_element (xmltree :: Element :: node ("session"))]"#
+        ));
+        assert!(res.contains(
+            r#"header_parts : & ["session"] , omitted_parts : & [] , body_order : & ["body" , "session"] ,"#
+        ));
+        // single part messages are the input itself
+        assert!(res.contains("pub struct GetLastTradePriceOutput (pub TradePrice) ;"));
+    }
+
+    #[test]
+    fn parameter_order() {
+        let source = String::from_utf8(EXAMPLE_WSDL.to_vec())
+            .unwrap()
+            .replace(
+                r#"<part name="body" element="xsd1:TradePriceRequest"/>"#,
+                r#"<part name="body" element="xsd1:TradePriceRequest"/>
+    <part name="session" element="xsd1:TradePrice"/>"#,
+            )
+            .replace(
+                r#"<operation name="GetLastTradePrice">
+      <input"#,
+                r#"<operation name="GetLastTradePrice" parameterOrder="session body">
+      <input"#,
+            );
+        let wsdl = parse(source.as_bytes()).unwrap();
+        let res = gen(&wsdl).unwrap();
+
+        assert!(res.contains(r#"body_order : & ["session" , "body"] ,"#));
+    }
+
+    #[test]
+    fn shared_wsdl() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains("pub wsdl : Option < std :: sync :: Arc < savon :: wsdl :: Wsdl >> ,"));
+        assert!(res.contains(
+            "pub fn with_wsdl (mut self , wsdl : std :: sync :: Arc < savon :: wsdl :: Wsdl >) -> Self { self . wsdl = Some (wsdl) ; self }"
+        ));
+    }
+
+    #[test]
+    fn mock_service() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains(
+            "pub trait StockQuoteServiceService { fn get_last_trade_price (& self , input : GetLastTradePriceInput) -> savon :: gen :: BoxFuture < '_ , Result < Result < GetLastTradePriceOutput , () > , savon :: Error > > ; }"
+        ));
+        assert!(res.contains(
+            "impl StockQuoteServiceService for StockQuoteService { fn get_last_trade_price (& self , input : GetLastTradePriceInput) -> savon :: gen :: BoxFuture < '_ , Result < Result < GetLastTradePriceOutput , () > , savon :: Error > > { Box :: pin (async move { StockQuoteService :: get_last_trade_price (self , input) . await }) } }"
+        ));
+        assert!(res.contains(
+            "impl StockQuoteServiceService for StockQuoteServiceMockService { fn get_last_trade_price (& self , _ : GetLastTradePriceInput) -> savon :: gen :: BoxFuture < '_ , Result < Result < GetLastTradePriceOutput , () > , savon :: Error > > { let output = { self . calls . respond (\"GetLastTradePrice\") . map (Ok) } ; Box :: pin (async move { output }) } }"
+        ));
+        assert!(!res.contains("async_fn_in_trait"));
+
+        struct Price(String);
+        impl FromElement for Price {
+            fn from_element(element: &xmltree::Element) -> Result<Self, crate::Error> {
+                Ok(Price(element.get_text().unwrap_or_default().to_string()))
+            }
+        }
+        let calls = MockCalls::default();
+        calls.expect_call(
+            "GetLastTradePrice",
+            b"<TradePrice>1.5</TradePrice>".to_vec(),
+        );
+        calls.expect_call(
+            "GetLastTradePrice",
+            b"<TradePrice>2.5</TradePrice>".to_vec(),
+        );
+        assert_eq!(
+            calls.respond::<Price>("GetLastTradePrice").unwrap().0,
+            "1.5"
+        );
+        assert_eq!(
+            calls.respond::<Price>("GetLastTradePrice").unwrap().0,
+            "2.5"
+        );
+        assert_eq!(calls.record("Ping"), None);
+        calls.assert_called("Ping");
+        assert_eq!(
+            calls.calls(),
+            vec!["GetLastTradePrice", "GetLastTradePrice", "Ping"]
+        );
+        let missing = std::panic::catch_unwind(|| calls.assert_called("Cancel"));
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn faults_without_output() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        wsdl.add_operation(
+            OperationBuilder::new()
+                .name("CancelTrade")
+                .input("GetLastTradePriceInput")
+                .fault("trade_closed", "GetLastTradePriceOutput")
+                .build(),
+        )
+        .unwrap();
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains(
+            "pub async fn cancel_trade (& self , get_last_trade_price_input : GetLastTradePriceInput) -> Result < () , CancelTradeError > { savon :: http :: request_fault ("
+        ));
+        assert!(res.contains(
+            "fn cancel_trade (& self , input : GetLastTradePriceInput) -> savon :: gen :: BoxFuture < '_ , Result < () , CancelTradeError > > ;"
+        ));
+        assert!(res.contains(
+            "fn cancel_trade (& self , _ : GetLastTradePriceInput) -> savon :: gen :: BoxFuture < '_ , Result < () , CancelTradeError > > { let output = { self . calls . record (\"CancelTrade\") ; Ok (()) } ;"
+        ));
+        assert!(res.contains("pub enum CancelTradeError"));
+    }
+
+    #[test]
+    fn callback_handler() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert!(!gen(&wsdl).unwrap().contains("CallbackHandler"));
+
+        wsdl.add_operation(
+            OperationBuilder::new()
+                .name("PriceChanged")
+                .output("GetLastTradePriceOutput")
+                .build(),
+        )
+        .unwrap();
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains(&format!("pub trait {}CallbackHandler", wsdl.name)));
+        assert!(res.contains(
+            "fn price_changed (& self , notification : GetLastTradePriceOutput) -> savon :: gen :: BoxFuture < '_ , Result < () , savon :: Error >> ;"
+        ));
+        assert!(res.contains("\"TradePrice\" =>"));
+        // notifications are not operations of the client
+        assert!(!res.contains("pub async fn price_changed"));
+    }
+
+    #[test]
+    fn field_documentation() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let mut fields = HashMap::new();
+        fields.insert(
+            "id".to_string(),
+            (
+                TypeAttribute {
+                    documentation: Some("Identifier assigned at registration.".to_string()),
+                    ..Default::default()
+                },
+                SimpleType::Int,
+            ),
+        );
+        wsdl.types.insert(
+            "Customer".to_string(),
+            Type::Complex(ComplexType {
+                fields,
+                ..Default::default()
+            }),
+        );
+
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains("# [doc = \"Identifier assigned at registration.\"] pub id : i64"));
+    }
+
+    #[test]
+    fn qualified_fields() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let mut fields = HashMap::new();
+        fields.insert(
+            "id".to_string(),
+            (
+                TypeAttribute {
+                    form: Some(ElementForm::Qualified),
+                    ..Default::default()
+                },
+                SimpleType::Int,
+            ),
+        );
+        wsdl.types.insert(
+            "Account".to_string(),
+            Type::Complex(ComplexType {
+                fields,
+                ..Default::default()
+            }),
+        );
+
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains("xmltree :: Element :: node (\"ns:id\")"));
+    }
+
+    #[test]
+    fn qualified_field_namespace() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let mut fields = HashMap::new();
+        fields.insert(
+            "id".to_string(),
+            (TypeAttribute::default(), SimpleType::Int),
+        );
+        wsdl.types.insert(
+            "Account".to_string(),
+            Type::Complex(ComplexType {
+                fields,
+                element_form_default: ElementForm::Qualified,
+                ..Default::default()
+            }),
+        );
+        wsdl.type_namespaces.insert(
+            "Account".to_string(),
+            "http://example.com/accounts.xsd".to_string(),
+        );
+
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains(
+            r#"xmltree :: Element :: node ("ns:id") . with_attr ("xmlns:ns" , "http://example.com/accounts.xsd")"#
+        ));
+    }
+
+    #[test]
+    fn fault_errors() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        wsdl.messages.insert(
+            "InvalidSymbolFault".to_string(),
+            Message {
+                part_name: "body".to_string(),
+                part_element: "InvalidSymbol".to_string(),
+                part_prefix: None,
+                parts: Vec::new(),
+            },
+        );
+        let operation = wsdl.operations.get_mut("GetLastTradePrice").unwrap();
+        operation.faults = Some(vec![FaultRef {
+            name: "invalid_symbol".to_string(),
+            message: "InvalidSymbolFault".to_string(),
+        }]);
+
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains(
+            "-> Result < GetLastTradePriceOutput , GetLastTradePriceError > { \
+             savon :: http :: request_fault_response"
+        ));
+        assert!(res.contains("InvalidSymbol (InvalidSymbolFault) ,"));
+        assert!(res.contains("Http (savon :: internal :: reqwest :: Error) ,"));
+        assert!(res.contains("Parse (savon :: Error) ,"));
+        assert!(res.contains(
+            "\"InvalidSymbol\" => < InvalidSymbolFault as savon :: gen :: FromElement > :: from_element (element) \
+             . map (| f | Some (GetLastTradePriceError :: InvalidSymbol (f))) ,"
+        ));
+    }
+
+    #[test]
+    fn attribute_fields() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let mut fields = HashMap::new();
+        fields.insert(
+            "amount".to_string(),
+            (TypeAttribute::default(), SimpleType::Float),
+        );
+        fields.insert(
+            "currency".to_string(),
+            (
+                TypeAttribute {
+                    kind: FieldKind::Attribute,
+                    ..Default::default()
+                },
+                SimpleType::String,
+            ),
+        );
+        wsdl.types.insert(
+            "Price".to_string(),
+            Type::Complex(ComplexType {
+                fields,
+                ..Default::default()
+            }),
+        );
+
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains(
+            "fn to_attributes (& self) -> Vec < (String , String) > { \
+             vec ! [Some ((\"currency\" . to_string () , self . currency . to_string ()))]"
+        ));
+        assert!(!res.contains("with_text (self . currency"));
+        assert!(res.contains("currency : element . attributes . get (\"currency\")"));
+    }
+
+    #[test]
+    fn restricted_simple_fields() {
+        let source = String::from_utf8(EXAMPLE_WSDL.to_vec())
+            .unwrap()
+            .replace(
+                r#"<element name="tickerSymbol" type="string"/>"#,
+                r#"<element name="tickerSymbol" type="xsd1:Symbol"/>"#,
+            )
+            .replace(
+                r#"<element name="TradePrice">"#,
+                r#"<simpleType name="Symbol">
+        <restriction base="string"/>
+      </simpleType>
+      <element name="TradePrice">"#,
+            );
+        let wsdl = parse(source.as_bytes()).unwrap();
+        let res = gen(&wsdl).unwrap();
+
+        assert!(res.contains("pub type Symbol = String ;"));
+        assert!(res.contains("pub ticker_symbol : Symbol ,"));
+        assert!(res.contains("with_text (self . ticker_symbol . to_string ())"));
+        assert!(res.contains(
+            "ticker_symbol : element . get_at_path (& [\"tickerSymbol\"]) . and_then (| e | e . get_text () . map (| s | s . to_string ())"
+        ));
+        assert!(!res.contains("self . ticker_symbol . fill_element"));
+        assert!(!res.contains("< Symbol as savon :: gen :: FromElement >"));
+    }
+
+    #[test]
+    fn simple_type_messages() {
+        let source = String::from_utf8(EXAMPLE_WSDL.to_vec())
+            .unwrap()
+            .replace(
+                r#"<part name="body" element="xsd1:TradePriceRequest"/>"#,
+                r#"<part name="body" element="xsd1:Symbol"/>"#,
+            )
+            .replace(
+                r#"<part name="body" element="xsd1:TradePrice"/>"#,
+                r#"<part name="price" element="xsd1:Price"/>
+    <part name="closed" element="xsd1:Closed"/>"#,
+            )
+            .replace(
+                r#"<element name="TradePrice">"#,
+                r#"<element name="Symbol" type="string"/>
+      <element name="Price" type="float"/>
+      <element name="Closed" type="boolean"/>
+      <element name="TradePrice">"#,
+            );
+        let wsdl = parse(source.as_bytes()).unwrap();
+        let res = gen(&wsdl).unwrap();
+
+        assert!(res.contains("pub struct GetLastTradePriceInput (pub Symbol) ;"));
+        assert!(res.contains(
+            "fn to_elements (& self) -> Vec < xmltree :: Element > { vec ! [] } \
+             fn to_text (& self) -> Option < String > { Some (self . 0 . to_string ()) }"
+        ));
+        assert!(res.contains(
+            "element . get_text () . ok_or (savon :: rpser :: xml :: Error :: Empty) . map_err (savon :: Error :: from) . map (| s | s . to_string ()) . map (GetLastTradePriceInput)"
+        ));
+        assert!(!res.contains("Symbol :: from_element"));
+
+        assert!(res.contains(
+            "vec ! [xmltree :: Element :: node (\"price\") . with_text (self . price . to_string ()) , \
+             xmltree :: Element :: node (\"closed\") . with_text (self . closed . to_string ())]"
+        ));
+        assert!(
+            res.contains("and_then (| e | e . as_boolean () . map_err (savon :: Error :: from)) ?")
+        );
+        assert!(!res.contains("| e | Price :: from_element"));
+    }
+
+    #[test]
+    fn type_rename() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let strip = rename::strip_prefix("Trade");
+        let options = CodegenOptions {
+            type_rename: Some(Box::new(move |name: &str| format!("Svc{}", strip(name)))),
+            ..Default::default()
+        };
+
+        let res = gen_with_options(&wsdl, &options).unwrap();
+        assert!(res.contains("pub struct SvcPriceRequest {"));
+        assert!(res.contains("pub struct SvcPrice {"));
+        assert!(res.contains("pub struct GetLastTradePriceInput (pub SvcPriceRequest) ;"));
+        assert!(!res.contains("pub struct TradePrice"));
+    }
+
+    #[test]
+    fn unqualified_override() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let mut fields = HashMap::new();
+        fields.insert(
+            "id".to_string(),
+            (TypeAttribute::default(), SimpleType::Int),
+        );
+        fields.insert(
+            "label".to_string(),
+            (
+                TypeAttribute {
+                    form: Some(ElementForm::Unqualified),
+                    ..Default::default()
+                },
+                SimpleType::String,
+            ),
+        );
+        wsdl.types.insert(
+            "Account".to_string(),
+            Type::Complex(ComplexType {
+                fields,
+                element_form_default: ElementForm::Qualified,
+                ..Default::default()
+            }),
+        );
+
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains("xmltree :: Element :: node (\"ns:id\")"));
+        assert!(res.contains("xmltree :: Element :: node (\"label\")"));
+    }
+
+    fn complex_type(fields: &[(&str, bool)]) -> Type {
+        let fields = fields
+            .iter()
+            .map(|(name, nillable)| {
+                let attributes = TypeAttribute {
+                    nillable: *nillable,
+                    ..Default::default()
+                };
+                (name.to_string(), (attributes, SimpleType::String))
+            })
+            .collect();
+        Type::Complex(ComplexType {
+            fields,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn nil_fields() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        wsdl.types
+            .insert("Contact".to_string(), complex_type(&[("email", true)]));
+
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains(
+            "vec ! [match & self . email { \
+             Some (v) => xmltree :: Element :: node (\"email\") . with_text (v . to_string ()) , \
+             None => xmltree :: Element :: node (\"email\") . with_attr (\"xsi:nil\" , \"true\") , }]"
+        ));
+    }
+
+    #[test]
+    fn conversions() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        wsdl.types.insert(
+            "CreateRequest".to_string(),
+            complex_type(&[("name", false), ("email", true), ("phone", true)]),
+        );
+        wsdl.types.insert(
+            "UpdateRequest".to_string(),
+            complex_type(&[
+                ("name", false),
+                ("email", true),
+                ("phone", true),
+                ("id", true),
+            ]),
+        );
+
+        let res = gen(&wsdl).unwrap();
+        assert!(!res.contains("TryFrom"));
+
+        let options = CodegenOptions {
+            conversion_threshold: Some(0.7),
+            ..Default::default()
+        };
+        let res = gen_with_options(&wsdl, &options).unwrap();
+        assert!(res.contains(
+            "impl std :: convert :: TryFrom < CreateRequest > for UpdateRequest { \
+             type Error = savon :: gen :: ConversionError ; \
+             fn try_from (value : CreateRequest) -> Result < Self , Self :: Error > { \
+             Ok (UpdateRequest { email : value . email , id : None , name : value . name , phone : value . phone , }) } }"
+        ));
+        assert!(res.contains(
+            "impl std :: convert :: TryFrom < UpdateRequest > for CreateRequest { \
+             type Error = savon :: gen :: ConversionError ; \
+             fn try_from (value : UpdateRequest) -> Result < Self , Self :: Error > { \
+             if value . id . is_some ()"
+        ));
+
+        let options = CodegenOptions {
+            conversion_threshold: Some(0.8),
+            ..Default::default()
+        };
+        let res = gen_with_options(&wsdl, &options).unwrap();
+        assert!(!res.contains("TryFrom"));
+    }
+
+    /// a unique output directory, removed when dropped, even if the test fails
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos();
+            TempDir(std::env::temp_dir().join(format!(
+                "savon-{}-{}-{}",
+                name,
+                std::process::id(),
+                nanos
+            )))
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn incremental() {
+        let temp = TempDir::new("incremental");
+        let dir = &temp.0;
+        let options = CodegenOptions::default();
+        let previous = parse(EXAMPLE_WSDL).unwrap();
+        let written = incremental_generate(&previous, None, dir, &options).unwrap();
+        assert_eq!(
+            written,
+            vec![
+                dir.join("types/trade_price.rs"),
+                dir.join("types/trade_price_request.rs"),
+                dir.join("service.rs"),
+                dir.join("mod.rs"),
+            ]
+        );
+
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        if let Some(Type::Complex(c)) = wsdl.types.get_mut("TradePrice") {
+            c.fields.insert(
+                "currency".to_string(),
+                (TypeAttribute::default(), SimpleType::String),
+            );
+        }
+        let written = incremental_generate(&wsdl, Some(&previous), dir, &options).unwrap();
+        assert_eq!(written, vec![dir.join("types/trade_price.rs")]);
+        let code = std::fs::read_to_string(dir.join("types/trade_price.rs")).unwrap();
+        assert!(code.contains("pub currency : String"));
+
+        let mut next = parse(EXAMPLE_WSDL).unwrap();
+        next.types.remove("TradePriceRequest");
+        let written = incremental_generate(&next, Some(&wsdl), dir, &options).unwrap();
+        assert_eq!(
+            written,
+            vec![dir.join("types/trade_price.rs"), dir.join("mod.rs")]
+        );
+        assert!(!dir.join("types/trade_price_request.rs").exists());
+    }
+
+    #[test]
+    fn incremental_options() {
+        let temp = TempDir::new("incremental-options");
+        let options = CodegenOptions {
+            type_rename: Some(Box::new(|name: &str| format!("Svc{}", name))),
+            ..CodegenOptions::default()
+        };
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        incremental_generate(&wsdl, None, &temp.0, &options).unwrap();
+        let code = std::fs::read_to_string(temp.0.join("types/trade_price.rs")).unwrap();
+        assert!(code.contains("pub struct SvcTradePrice"));
+    }
+
+    #[test]
+    fn incremental_file_collision() {
+        let temp = TempDir::new("incremental-collision");
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let price = wsdl.types["TradePrice"].clone();
+        wsdl.types.insert("FooBar".to_string(), price.clone());
+        wsdl.types.insert("fooBar".to_string(), price);
+        match incremental_generate(&wsdl, None, &temp.0, &CodegenOptions::default()) {
+            Err(GenError::FileCollision(a, b)) => {
+                assert_eq!((a.as_str(), b.as_str()), ("FooBar", "fooBar"))
+            }
+            other => panic!("expected a file collision, got {:?}", other.map(|_| ())),
+        }
+        assert!(!temp.0.join("types").exists());
+    }
+
+    #[test]
+    fn date_time_offsets() {
+        let hours = |h| FixedOffset::east_opt(h * 3600).unwrap();
+
+        let d = parse_date_time("2023-01-15T10:00:00+05:30").unwrap();
+        assert_eq!(
+            d.offset(),
+            &FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap()
+        );
+        assert_eq!(d.to_rfc3339(), "2023-01-15T10:00:00+05:30");
+
+        let d = parse_date_time("2023-01-15T10:00:00-08:00").unwrap();
+        assert_eq!(d.offset(), &hours(-8));
+
+        let d = parse_date_time("2023-01-15T10:00:00.250Z").unwrap();
+        assert_eq!(d.offset(), &hours(0));
+        assert_eq!(d.timestamp_millis() % 1000, 250);
+
+        let d = parse_date_time("2023-01-15T10:00:00").unwrap();
+        assert_eq!(d, parse_date_time("2023-01-15T10:00:00Z").unwrap());
+
+        assert!(parse_date_time("15/01/2023").is_err());
+    }
+
+    #[test]
+    fn hex_binary() {
+        assert_eq!(HexBinary::from_hex("4A6f").unwrap(), b"Jo".to_vec());
+        assert_eq!(HexBinary::from_hex("").unwrap(), Vec::<u8>::new());
+        assert_eq!(HexBinary::to_hex(&[0x4a, 0x6f, 0x05]), "4A6F05");
+        assert_eq!(HexBinary::from_hex("4A6"), Err(HexError::OddLength));
+        assert_eq!(HexBinary::from_hex("4G"), Err(HexError::InvalidDigit('G')));
+    }
+
+    #[test]
+    fn decimal_digits() {
+        let d = "-0012.3400".parse::<Decimal>().unwrap();
+        assert_eq!(d.total_digits(), 4);
+        assert_eq!(d.fraction_digits(), 2);
+        assert_eq!(d.to_string(), "-0012.3400");
+        assert_eq!("0.05".parse::<Decimal>().unwrap().total_digits(), 1);
+        assert!(d.check_digits(Some(4), Some(2)).is_ok());
+        assert!(d.check_digits(Some(3), None).is_err());
+        assert!(d.check_digits(None, Some(1)).is_err());
+        for invalid in &["", ".", "1.2.3", "1e5", "--1"] {
+            assert!(invalid.parse::<Decimal>().is_err(), "{}", invalid);
+        }
+
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        wsdl.types.insert(
+            "Amount".to_string(),
+            Type::Decimal {
+                total_digits: Some(10),
+                fraction_digits: Some(2),
+            },
+        );
+        let res = gen(&wsdl).unwrap();
+        assert!(res.contains("pub struct Amount (savon :: gen :: Decimal) ;"));
+        assert!(res.contains("pub const TOTAL_DIGITS : u32 = 10u32 ;"));
+        assert!(res.contains("pub const FRACTION_DIGITS : u32 = 2u32 ;"));
+        assert!(res.contains(
+            "value . check_digits (Some (Self :: TOTAL_DIGITS) , Some (Self :: FRACTION_DIGITS)) ?"
+        ));
+    }
+
+    #[test]
+    fn substitution_groups() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let complex = |fields: Vec<(&str, SimpleType)>, group: Option<&str>, abstract_| {
+            Type::Complex(ComplexType {
+                fields: fields
+                    .into_iter()
+                    .map(|(name, t)| (name.to_string(), (TypeAttribute::default(), t)))
+                    .collect(),
+                substitution_group: group.map(|g| g.to_string()),
+                abstract_,
+                ..Default::default()
+            })
+        };
+        wsdl.types
+            .insert("Address".to_string(), complex(vec![], None, true));
+        wsdl.types.insert(
+            "PoBox".to_string(),
+            complex(vec![("number", SimpleType::Int)], Some("Address"), false),
+        );
+        wsdl.types.insert(
+            "Shipment".to_string(),
+            complex(
+                vec![("destination", SimpleType::Complex("Address".to_string()))],
+                None,
+                false,
+            ),
+        );
+
+        let res = gen(&wsdl).unwrap();
+        assert!(
+            res.contains("pub trait Address : savon :: gen :: ToElements + std :: fmt :: Debug")
+        );
+        assert!(!res.contains("pub struct Address"));
+        assert!(res.contains("impl Address for PoBox"));
+        assert!(res.contains("pub destination : Box < dyn Address > ,"));
+        assert!(res.contains("\"PoBox\" => Ok (Box :: new ("));
+        assert!(res.contains("Box :: new (PoBox :: default ())"));
+    }
+
+    #[test]
+    fn substitution_group_members() {
+        let wsdl = parse(include_bytes!("../assets/substitutions.wsdl")).unwrap();
+        let res = gen(&wsdl).unwrap();
+
+        // typed members are read by the name of their type or of their element
+        assert!(res.contains("impl Address for LockerType"));
+        assert!(res.contains("\"Locker\" | \"LockerType\" => Ok (Box :: new ("));
+        // the `xsi:type` is qualified with the namespace of the member
+        assert!(res.contains(
+            "fn type_namespace (& self) -> & 'static str { \"http://example.com/shipping.xsd\" }"
+        ));
+        assert!(res.contains(
+            "attributes . push ((\"xmlns:st\" . to_string () , Address :: type_namespace (& * * self) . to_string ())) ;"
+        ));
+
+        // without members, fields of the head could not have a value
+        let mut wsdl = wsdl;
+        wsdl.types
+            .retain(|_, t| !matches!(t, Type::Complex(c) if c.substitution_group.is_some()));
+        assert!(matches!(
+            gen(&wsdl),
+            Err(GenError::Wsdl(WsdlError::UnsupportedFeature(_)))
+        ));
+    }
+
+    #[test]
+    fn xml_names() {
+        assert!(NCName::new("order_line-2").is_ok());
+        assert!(NCName::new("été").is_ok());
+        for invalid in &["", "2nd", "tns:Order", "-x", "a b"] {
+            assert_eq!(
+                NCName::new(invalid),
+                Err(NameError {
+                    kind: "NCName",
+                    value: invalid.to_string(),
+                })
+            );
+        }
+
+        assert!(Name::new("tns:Order:v2").is_ok());
+        assert!(Name::new(":x").is_ok());
+        assert!(Name::new("2x").is_err());
+
+        let q = "tns:Order".parse::<super::QName>().unwrap();
+        assert_eq!(q.prefix(), Some("tns"));
+        assert_eq!(q.local_name(), "Order");
+        assert_eq!(super::QName::new("Order").unwrap().prefix(), None);
+        for invalid in &["tns:", ":Order", "a:b:c", "tns:2"] {
+            assert!(super::QName::new(invalid).is_err(), "{}", invalid);
+        }
+
+        fn length<S: AsRef<str>>(s: S) -> usize {
+            s.as_ref().len()
+        }
+        assert_eq!(length(NCName::new("Order").unwrap()), 5);
+        assert_eq!(length(Name::new("tns:Order").unwrap()), 9);
+        assert!(q.starts_with("tns:"));
+        assert_eq!(&*q, "tns:Order");
+    }
 }
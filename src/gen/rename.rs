@@ -0,0 +1,89 @@
+//! Common naming conventions for `CodegenOptions::type_rename`.
+//!
+//! ```
+//! use savon::gen::{rename, CodegenOptions};
+//!
+//! let strip = rename::strip_prefix("SVC_");
+//! let options = CodegenOptions {
+//!     type_rename: Some(Box::new(move |name: &str| rename::pascal_case(&strip(name)))),
+//!     ..Default::default()
+//! };
+//! ```
+
+/// Joins the words of `name` in pascal case, as in `SVC_get_customer` ->
+/// `SvcGetCustomer`.
+///
+/// Words are separated by characters that are not alphanumeric, and words
+/// written in capitals are lowercased, while mixed case words keep their
+/// case after their first letter.
+pub fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let word = match word.chars().any(|c| c.is_ascii_lowercase()) {
+                true => word.to_string(),
+                false => word.to_ascii_lowercase(),
+            };
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Removes `prefix` from the names starting with it.
+pub fn strip_prefix(prefix: &str) -> impl Fn(&str) -> String {
+    let prefix = prefix.to_string();
+    move |name| {
+        name.strip_prefix(prefix.as_str())
+            .unwrap_or(name)
+            .to_string()
+    }
+}
+
+/// Converts a camel case name to snake case, as in `GetHTTPResponse` ->
+/// `get_http_response`.
+pub fn camel_to_snake(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut out = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_lowercase = matches!(chars.get(i + 1), Some(n) if n.is_ascii_lowercase());
+            if previous.is_ascii_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_ascii_uppercase() && next_lowercase)
+            {
+                out.push('_');
+            }
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conventions() {
+        assert_eq!(
+            pascal_case("SVC_GetCustomer_Request"),
+            "SvcGetCustomerRequest"
+        );
+        assert_eq!(pascal_case("ALL_CAPS"), "AllCaps");
+        assert_eq!(pascal_case("camelCase"), "CamelCase");
+        assert_eq!(pascal_case("order-line.v2"), "OrderLineV2");
+
+        let strip = strip_prefix("SVC_");
+        assert_eq!(strip("SVC_GetCustomer_Request"), "GetCustomer_Request");
+        assert_eq!(strip("Customer"), "Customer");
+
+        assert_eq!(camel_to_snake("GetCustomerRequest"), "get_customer_request");
+        assert_eq!(camel_to_snake("GetHTTPResponse"), "get_http_response");
+        assert_eq!(camel_to_snake("address2Line"), "address2_line");
+    }
+}
@@ -1,69 +1,730 @@
-use crate::gen::{FromElement, ToElements};
-use crate::rpser::{Method, Response};
+use crate::gen::{FromElement, FromFault, ToElements};
+use crate::rpser::{Method, Response, RpcError};
 use reqwest::Client;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::time::Duration;
+
+/// Records a debug event of the call lifecycle when the `tracing` feature
+/// is enabled.
+macro_rules! lifecycle {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+/// Runs a call of a generated service in a `soap.call` span carrying the
+/// operation name.
+#[cfg(feature = "tracing")]
+async fn instrumented<F: Future>(method: &str, call: F) -> F::Output {
+    use tracing::Instrument;
+    call.instrument(tracing::info_span!("soap.call", operation = %method))
+        .await
+}
+
+#[cfg(not(feature = "tracing"))]
+async fn instrumented<F: Future>(_method: &str, call: F) -> F::Output {
+    call.await
+}
+
+/// A SOAP request about to be sent, as seen by interceptors.
+#[derive(Debug, Clone)]
+pub struct SoapRequest {
+    pub operation: String,
+    pub url: String,
+    /// HTTP headers, in the order they will be sent
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+/// A SOAP response as received, before it is parsed.
+#[derive(Debug, Clone)]
+pub struct SoapResponse {
+    pub operation: String,
+    pub status: u16,
+    pub body: String,
+}
+
+/// Error returned by an interceptor to abort the call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterceptorError(pub String);
+
+impl fmt::Display for InterceptorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for InterceptorError {}
+
+/// Hooks called around each HTTP exchange of the client.
+///
+/// Interceptors run in the order they were added. `before_send` can modify
+/// the request, and an error from either hook aborts the call.
+pub trait RequestInterceptor: Send + Sync {
+    fn before_send(&self, _req: &mut SoapRequest) -> Result<(), InterceptorError> {
+        Ok(())
+    }
+
+    fn after_receive(&self, _resp: &SoapResponse) -> Result<(), InterceptorError> {
+        Ok(())
+    }
+}
+
+/// Logs request and response bodies at debug level, in a span carrying the
+/// operation name.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingInterceptor;
+
+#[cfg(feature = "tracing")]
+impl RequestInterceptor for TracingInterceptor {
+    fn before_send(&self, req: &mut SoapRequest) -> Result<(), InterceptorError> {
+        let span = tracing::debug_span!("soap", operation = %req.operation);
+        let _enter = span.enter();
+        tracing::debug!(url = %req.url, body = %req.body, "sending request");
+        Ok(())
+    }
+
+    fn after_receive(&self, resp: &SoapResponse) -> Result<(), InterceptorError> {
+        let span = tracing::debug_span!("soap", operation = %resp.operation);
+        let _enter = span.enter();
+        tracing::debug!(status = resp.status, body = %resp.body, "received response");
+        Ok(())
+    }
+}
+
+/// Configures the HTTP client of a generated service.
+///
+/// The client keeps a pool of connections to the endpoint, reused by every
+/// call made through the service.
+#[derive(Debug, Clone, Default)]
+pub struct ClientBuilder {
+    max_idle_per_host: Option<usize>,
+    idle_timeout: Option<Duration>,
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of idle connections kept open to each host. Connections
+    /// in use are not limited.
+    pub fn max_idle_per_host(mut self, n: usize) -> Self {
+        self.max_idle_per_host = Some(n);
+        self
+    }
+
+    /// Time after which an idle connection is closed.
+    pub fn idle_timeout(mut self, d: Duration) -> Self {
+        self.idle_timeout = Some(d);
+        self
+    }
+
+    pub fn build(self) -> Result<Client, crate::Error> {
+        let mut builder = Client::builder();
+        if let Some(n) = self.max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(n);
+        }
+        if let Some(d) = self.idle_timeout {
+            builder = builder.pool_idle_timeout(d);
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+/// Body of a SOAP response, along with the elements of its header.
+#[derive(Debug, Clone)]
+pub struct CallResponse {
+    pub body: xmltree::Element,
+    pub headers: Vec<xmltree::Element>,
+}
+
+/// An operation called by a generated client, as declared by the WSDL.
+///
+/// Generated clients read it from the binding of the operation with
+/// `dynamic::request_layout`. The elements of the input named after a part
+/// are placed as the binding declares, the other ones go in the body.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OperationCall<'a> {
+    pub name: &'a str,
+    /// namespace bound to the prefix of the body element
+    pub namespace: &'a str,
+    /// value of the `SOAPAction` header
+    pub soap_action: &'a str,
+    /// parts sent as SOAP header elements
+    pub header_parts: &'a [&'a str],
+    /// parts left out of the envelope
+    pub omitted_parts: &'a [&'a str],
+    /// order of the parts in the body, from the `parameterOrder` of RPC
+    /// operations. Elements of other names follow, in the order of the input
+    pub body_order: &'a [&'a str],
+}
+
+/// Sends the request of `operation` and returns the response body.
+async fn send<Input: ToElements>(
+    client: &Client,
+    base_url: &str,
+    operation: &OperationCall<'_>,
+    input: &Input,
+    interceptors: &[Box<dyn RequestInterceptor>],
+) -> Result<String, crate::Error> {
+    let mut m = Method::new(operation.name);
+    let mut headers = Vec::new();
+    let mut elements = input.to_elements();
+    let order = operation.body_order;
+    elements.sort_by_key(|el| {
+        order
+            .iter()
+            .position(|p| *p == el.name)
+            .unwrap_or(order.len())
+    });
+
+    for el in elements {
+        if operation.header_parts.contains(&el.name.as_str()) {
+            headers.push(el);
+        } else if !operation.omitted_parts.contains(&el.name.as_str()) {
+            m = m.with(el);
+        }
+    }
+    let mut envelope = headers
+        .into_iter()
+        .fold(m.as_envelope(operation.namespace), |envelope, header| {
+            envelope.with_header(header)
+        });
+    // inputs of simple types are the text of the body element
+    if let (Some(text), Some(body)) = (input.to_text(), envelope.body.first_mut()) {
+        body.children.push(xmltree::XMLNode::Text(text));
+    }
+
+    let mut req = SoapRequest {
+        operation: operation.name.to_string(),
+        url: base_url.to_string(),
+        headers: vec![
+            ("Content-Type".to_string(), "text/xml".to_string()),
+            ("MessageType".to_string(), "Call".to_string()),
+            ("SOAPAction".to_string(), operation.soap_action.to_string()),
+        ],
+        body: envelope.to_xml(),
+    };
+    lifecycle!(bytes = req.body.len(), "request serialized");
+    for interceptor in interceptors {
+        interceptor.before_send(&mut req)?;
+    }
+    trace!("sending: {}", req.body);
+    lifecycle!(url = %req.url, "sending request");
+
+    let mut builder = client.post(&req.url);
+    for (name, value) in &req.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    let response = builder.body(req.body).send().await?;
+
+    let resp = SoapResponse {
+        operation: req.operation,
+        status: response.status().as_u16(),
+        body: response.text().await?,
+    };
+    trace!("received: {}", resp.body);
+    lifecycle!(status = resp.status, "response received");
+    for interceptor in interceptors {
+        interceptor.after_receive(&resp)?;
+    }
+
+    Ok(resp.body)
+}
 
 pub async fn one_way<Input: ToElements>(
     client: &Client,
     base_url: &str,
-    ns: &str,
-    method: &str,
+    operation: &OperationCall<'_>,
     input: &Input,
+    interceptors: &[Box<dyn RequestInterceptor>],
 ) -> Result<(), crate::Error> {
-    let mut v = input.to_elements();
-    let mut m = Method::new(method);
+    instrumented(operation.name, async {
+        send(client, base_url, operation, input, interceptors).await?;
+        Ok(())
+    })
+    .await
+}
 
-    for el in v.drain(..) {
-        m = m.with(el);
-    }
-    let s = m.as_xml(ns);
-    trace!("sending: {}", s);
+/// Calls `operation` and returns the response without deserializing it, so
+/// that the elements of the SOAP header are available.
+pub async fn call<Input: ToElements>(
+    client: &Client,
+    base_url: &str,
+    operation: &OperationCall<'_>,
+    input: &Input,
+    interceptors: &[Box<dyn RequestInterceptor>],
+) -> Result<CallResponse, crate::Error> {
+    instrumented(operation.name, async {
+        let response = send(client, base_url, operation, input, interceptors).await?;
 
-    let response: String = client
-        .post(base_url)
-        .header("Content-Type", "text/xml")
-        .header("MessageType", "Call")
-        .body(s)
-        .send()
-        .await?
-        .text()
-        .await?;
+        let r = Response::from_xml(&response)?;
+        trace!("parsed: {:#?}", r);
+        lifecycle!("response deserialized");
 
-    trace!("received: {}", response);
-    Ok(())
+        Ok(CallResponse {
+            body: r.body,
+            headers: r.headers,
+        })
+    })
+    .await
 }
 
 pub async fn request_response<Input: ToElements, Output: Debug + FromElement, Error>(
     client: &Client,
     base_url: &str,
-    ns: &str,
-    method: &str,
+    operation: &OperationCall<'_>,
     input: &Input,
+    interceptors: &[Box<dyn RequestInterceptor>],
 ) -> Result<Result<Output, Error>, crate::Error> {
-    let mut v = input.to_elements();
-    let mut m = Method::new(method);
-
-    for el in v.drain(..) {
-        m = m.with(el);
-    }
-    let s = m.as_xml(ns);
-    trace!("sending: {}", s);
-
-    let response: String = client
-        .post(base_url)
-        .header("Content-Type", "text/xml")
-        .header("MessageType", "Call")
-        .body(s)
-        .send()
-        .await?
-        .text()
-        .await?;
-
-    trace!("received: {}", response);
-    let r = Response::from_xml(&response).unwrap();
-    trace!("parsed: {:#?}", r);
-    let o = Output::from_element(&r.body);
-    trace!("output: {:#?}", o);
-
-    o.map(Ok)
+    instrumented(operation.name, async {
+        let response = send(client, base_url, operation, input, interceptors).await?;
+
+        let r = Response::from_xml(&response).unwrap();
+        trace!("parsed: {:#?}", r);
+        let o = Output::from_element(&r.body);
+        trace!("output: {:#?}", o);
+        lifecycle!(ok = o.is_ok(), "response deserialized");
+
+        o.map(Ok)
+    })
+    .await
+}
+
+/// Calls an operation declaring faults.
+///
+/// A SOAP fault whose detail is one of the declared faults is returned as
+/// such, other errors are converted to `Fault`.
+pub async fn request_fault_response<Input: ToElements, Output: FromElement, Fault: FromFault>(
+    client: &Client,
+    base_url: &str,
+    operation: &OperationCall<'_>,
+    input: &Input,
+    interceptors: &[Box<dyn RequestInterceptor>],
+) -> Result<Output, Fault> {
+    instrumented(operation.name, async {
+        let response = send(client, base_url, operation, input, interceptors).await?;
+
+        let r = Response::from_xml(&response).map_err(declared_fault::<Fault>)?;
+        trace!("parsed: {:#?}", r);
+        let output = Output::from_element(&r.body)?;
+        lifecycle!("response deserialized");
+
+        Ok(output)
+    })
+    .await
+}
+
+/// Calls an operation declaring faults but no output.
+///
+/// An empty response, or one without a fault, is a success. Faults are
+/// returned as by `request_fault_response`.
+pub async fn request_fault<Input: ToElements, Fault: FromFault>(
+    client: &Client,
+    base_url: &str,
+    operation: &OperationCall<'_>,
+    input: &Input,
+    interceptors: &[Box<dyn RequestInterceptor>],
+) -> Result<(), Fault> {
+    instrumented(operation.name, async {
+        let response = send(client, base_url, operation, input, interceptors).await?;
+        if response.trim().is_empty() {
+            return Ok(());
+        }
+
+        Response::from_xml(&response).map_err(declared_fault::<Fault>)?;
+        lifecycle!("response deserialized");
+
+        Ok(())
+    })
+    .await
+}
+
+/// Converts `error` to `Fault`, as one of its declared faults when it is a
+/// SOAP fault whose detail is one of them.
+fn declared_fault<Fault: FromFault>(error: RpcError) -> Fault {
+    if let RpcError::Fault { fault_detail, .. } = &error {
+        let element = fault_detail.children.iter().find_map(|c| c.as_element());
+        match element.map(Fault::from_detail).transpose() {
+            Ok(Some(Some(fault))) => {
+                lifecycle!("declared fault deserialized");
+                return fault;
+            }
+            Ok(_) => {}
+            Err(error) => return error.into(),
+        }
+    }
+    crate::Error::from(error).into()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread::{self, JoinHandle};
+
+    /// Serves a single HTTP exchange with `body` as the response, returning
+    /// the server URL and a handle yielding the raw request.
+    pub(crate) fn mock_server(body: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text[..end]
+                        .lines()
+                        .map(|l| l.splitn(2, ':').collect::<Vec<_>>())
+                        .find(|h| h[0].eq_ignore_ascii_case("content-length"))
+                        .map(|h| h[1].trim().parse::<usize>().unwrap())
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        (url, handle)
+    }
+
+    /// Operation of the `urn:test` namespace, without a SOAP action.
+    fn operation(name: &'static str) -> OperationCall<'static> {
+        OperationCall {
+            name,
+            namespace: "urn:test",
+            soap_action: "\"\"",
+            ..Default::default()
+        }
+    }
+
+    struct Nothing;
+
+    impl ToElements for Nothing {
+        fn to_elements(&self) -> Vec<xmltree::Element> {
+            vec![]
+        }
+    }
+
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl RequestInterceptor for Recorder {
+        fn before_send(&self, req: &mut SoapRequest) -> Result<(), InterceptorError> {
+            req.headers
+                .push(("X-Request-Id".to_string(), "42".to_string()));
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("send {}", req.operation));
+            Ok(())
+        }
+
+        fn after_receive(&self, resp: &SoapResponse) -> Result<(), InterceptorError> {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("receive {} {}", resp.operation, resp.status));
+            Ok(())
+        }
+    }
+
+    struct Deny;
+
+    impl RequestInterceptor for Deny {
+        fn before_send(&self, _req: &mut SoapRequest) -> Result<(), InterceptorError> {
+            Err(InterceptorError("denied".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn response_headers() {
+        let (url, server) = mock_server(
+            "<soap:Envelope xmlns:soap=\"http://schemas.xmlsoap.org/soap/envelope/\">\
+             <soap:Header><NextPageToken>abc</NextPageToken></soap:Header>\
+             <soap:Body><ListResponse><count>2</count></ListResponse></soap:Body>\
+             </soap:Envelope>",
+        );
+
+        let response = call(&Client::new(), &url, &operation("List"), &Nothing, &[])
+            .await
+            .unwrap();
+        server.join().unwrap();
+
+        assert_eq!(response.body.name, "ListResponse");
+        assert_eq!(response.headers.len(), 1);
+        assert_eq!(response.headers[0].name, "NextPageToken");
+        assert_eq!(response.headers[0].get_text().unwrap(), "abc");
+    }
+
+    #[tokio::test]
+    async fn pooled_client() {
+        let (url, server) = mock_server("<Envelope><Body/></Envelope>");
+        let client = ClientBuilder::new()
+            .max_idle_per_host(1)
+            .idle_timeout(Duration::from_secs(30))
+            .build()
+            .unwrap();
+
+        one_way(&client, &url, &operation("Ping"), &Nothing, &[])
+            .await
+            .unwrap();
+        assert!(server.join().unwrap().contains("Ping"));
+    }
+
+    #[derive(Debug)]
+    struct Pong;
+
+    impl FromElement for Pong {
+        fn from_element(_element: &xmltree::Element) -> Result<Self, crate::Error> {
+            Ok(Pong)
+        }
+    }
+
+    #[derive(Debug)]
+    enum PingError {
+        Busy(String),
+        Other(crate::Error),
+    }
+
+    impl From<crate::Error> for PingError {
+        fn from(error: crate::Error) -> Self {
+            PingError::Other(error)
+        }
+    }
+
+    impl FromFault for PingError {
+        fn from_detail(element: &xmltree::Element) -> Result<Option<Self>, crate::Error> {
+            match element.name.as_str() {
+                "Busy" => Ok(Some(PingError::Busy(
+                    element.get_text().unwrap_or_default().to_string(),
+                ))),
+                _ => Ok(None),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn declared_faults() {
+        let (url, server) = mock_server(
+            "<soap:Envelope xmlns:soap=\"http://schemas.xmlsoap.org/soap/envelope/\">\
+             <soap:Body><soap:Fault>\
+             <faultcode>soap:Server</faultcode><faultstring>busy</faultstring>\
+             <detail><Busy>retry later</Busy></detail>\
+             </soap:Fault></soap:Body></soap:Envelope>",
+        );
+        let response: Result<Pong, PingError> =
+            request_fault_response(&Client::new(), &url, &operation("Ping"), &Nothing, &[]).await;
+        server.join().unwrap();
+        match response {
+            Err(PingError::Busy(reason)) => assert_eq!(reason, "retry later"),
+            other => panic!("expected a declared fault, got {:?}", other),
+        }
+
+        let (url, server) = mock_server(
+            "<soap:Envelope xmlns:soap=\"http://schemas.xmlsoap.org/soap/envelope/\">\
+             <soap:Body><PingResponse/></soap:Body></soap:Envelope>",
+        );
+        let response: Result<Pong, PingError> =
+            request_fault_response(&Client::new(), &url, &operation("Ping"), &Nothing, &[]).await;
+        server.join().unwrap();
+        assert!(response.is_ok());
+
+        let (url, server) = mock_server(
+            "<soap:Envelope xmlns:soap=\"http://schemas.xmlsoap.org/soap/envelope/\">\
+             <soap:Body><soap:Fault>\
+             <faultcode>soap:Server</faultcode><faultstring>down</faultstring>\
+             <detail><Down/></detail>\
+             </soap:Fault></soap:Body></soap:Envelope>",
+        );
+        let response: Result<Pong, PingError> =
+            request_fault_response(&Client::new(), &url, &operation("Ping"), &Nothing, &[]).await;
+        server.join().unwrap();
+        assert!(matches!(
+            response,
+            Err(PingError::Other(crate::Error::Rpc(RpcError::Fault { .. })))
+        ));
+    }
+
+    #[tokio::test]
+    async fn declared_faults_without_output() {
+        let (url, server) = mock_server(
+            "<soap:Envelope xmlns:soap=\"http://schemas.xmlsoap.org/soap/envelope/\">\
+             <soap:Body><soap:Fault>\
+             <faultcode>soap:Server</faultcode><faultstring>busy</faultstring>\
+             <detail><Busy>retry later</Busy></detail>\
+             </soap:Fault></soap:Body></soap:Envelope>",
+        );
+        let response: Result<(), PingError> =
+            request_fault(&Client::new(), &url, &operation("Ping"), &Nothing, &[]).await;
+        server.join().unwrap();
+        match response {
+            Err(PingError::Busy(reason)) => assert_eq!(reason, "retry later"),
+            other => panic!("expected a declared fault, got {:?}", other),
+        }
+
+        let (url, server) = mock_server("");
+        let response: Result<(), PingError> =
+            request_fault(&Client::new(), &url, &operation("Ping"), &Nothing, &[]).await;
+        server.join().unwrap();
+        assert!(response.is_ok());
+    }
+
+    /// Input of an operation with a part for each of the body, a header and
+    /// an attachment.
+    struct Upload;
+
+    impl ToElements for Upload {
+        fn to_elements(&self) -> Vec<xmltree::Element> {
+            ["body_part", "auth", "file"]
+                .iter()
+                .map(|name| xmltree::Element::new(name))
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn bound_parts() {
+        let (url, server) = mock_server("<Envelope><Body/></Envelope>");
+        let operation = OperationCall {
+            header_parts: &["auth"],
+            omitted_parts: &["file"],
+            ..operation("Upload")
+        };
+
+        one_way(&Client::new(), &url, &operation, &Upload, &[])
+            .await
+            .unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.contains(
+            "<soap:Header><auth /></soap:Header>\
+             <soap:Body><ns:Upload><body_part /></ns:Upload></soap:Body>"
+        ));
+    }
+
+    #[tokio::test]
+    async fn parameter_order() {
+        let (url, server) = mock_server("<Envelope><Body/></Envelope>");
+        let operation = OperationCall {
+            omitted_parts: &["auth"],
+            body_order: &["file", "body_part"],
+            ..operation("Upload")
+        };
+
+        one_way(&Client::new(), &url, &operation, &Upload, &[])
+            .await
+            .unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.contains("<ns:Upload><file /><body_part /></ns:Upload>"));
+    }
+
+    struct Symbol;
+
+    impl ToElements for Symbol {
+        fn to_elements(&self) -> Vec<xmltree::Element> {
+            vec![]
+        }
+
+        fn to_text(&self) -> Option<String> {
+            Some("ACME".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn text_input() {
+        let (url, server) = mock_server("<Envelope><Body/></Envelope>");
+
+        one_way(&Client::new(), &url, &operation("Quote"), &Symbol, &[])
+            .await
+            .unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.contains("<soap:Body><ns:Quote>ACME</ns:Quote></soap:Body>"));
+    }
+
+    #[tokio::test]
+    async fn soap_action_header() {
+        let wsdl = crate::wsdl::parse(include_bytes!("../assets/example.wsdl")).unwrap();
+        let soap_action = wsdl.operations["GetLastTradePrice"].soap_action_header(true);
+        let (url, server) = mock_server("<Envelope><Body/></Envelope>");
+
+        one_way(
+            &Client::new(),
+            &url,
+            &OperationCall {
+                name: "GetLastTradePrice",
+                namespace: &wsdl.target_namespace,
+                soap_action: &soap_action,
+                ..Default::default()
+            },
+            &Nothing,
+            &[],
+        )
+        .await
+        .unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.contains("soapaction: \"http://example.com/GetLastTradePrice\"\r\n"));
+    }
+
+    #[tokio::test]
+    async fn interceptors() {
+        let (url, server) = mock_server("<Envelope><Body/></Envelope>");
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let interceptors: Vec<Box<dyn RequestInterceptor>> = vec![Box::new(Recorder(log.clone()))];
+
+        one_way(
+            &Client::new(),
+            &url,
+            &operation("Ping"),
+            &Nothing,
+            &interceptors,
+        )
+        .await
+        .unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.contains("x-request-id: 42"));
+        assert_eq!(*log.lock().unwrap(), vec!["send Ping", "receive Ping 200"]);
+
+        let interceptors: Vec<Box<dyn RequestInterceptor>> = vec![Box::new(Deny)];
+        match one_way(
+            &Client::new(),
+            &url,
+            &operation("Ping"),
+            &Nothing,
+            &interceptors,
+        )
+        .await
+        {
+            Err(crate::Error::Interceptor(e)) => assert_eq!(e.0, "denied"),
+            other => panic!("expected the interceptor to abort, got {:?}", other),
+        }
+    }
 }
@@ -14,9 +14,14 @@ pub mod internal {
     pub use xmltree;
 }
 
+pub mod dynamic;
 mod error;
 pub mod gen;
 pub mod http;
+pub mod lint;
+pub mod ns;
 pub mod rpser;
+pub mod soap;
+pub mod typescript;
 pub mod wsdl;
 pub use error::*;
@@ -0,0 +1,168 @@
+//! Checks for common authoring mistakes in WSDL documents.
+
+use crate::wsdl::{builtin_type, SimpleType, Type, Wsdl};
+use std::collections::HashMap;
+
+/// Number of fields above which a type is reported as too large.
+pub const MAX_FIELDS: usize = 30;
+
+/// A likely mistake found by `lint`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WsdlLint {
+    /// operation without `wsdl:documentation`
+    UndocumentedOperation(String),
+    /// complex type with more than `MAX_FIELDS` fields, which should likely be split
+    TooManyFields { type_name: String, fields: usize },
+    /// message part whose element is not a known type
+    UnknownPartElement { message: String, element: String },
+    /// operation whose input and output have the same type, often a copy-paste error
+    SameInputAndOutput(String),
+    /// operations whose names only differ by case, in alphabetical order
+    DuplicateOperationNames(Vec<String>),
+}
+
+/// Lists the likely mistakes of a document, grouped by kind and sorted by name.
+pub fn lint(wsdl: &Wsdl) -> Vec<WsdlLint> {
+    let mut lints = Vec::new();
+
+    let mut operations = wsdl.operations.values().collect::<Vec<_>>();
+    operations.sort_by(|a, b| a.name.cmp(&b.name));
+    for operation in &operations {
+        if operation.documentation.is_none() {
+            lints.push(WsdlLint::UndocumentedOperation(operation.name.clone()));
+        }
+    }
+
+    let mut types = wsdl.types.iter().collect::<Vec<_>>();
+    types.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, t) in types {
+        if let Type::Complex(c) = t {
+            if c.fields.len() > MAX_FIELDS {
+                lints.push(WsdlLint::TooManyFields {
+                    type_name: name.clone(),
+                    fields: c.fields.len(),
+                });
+            }
+        }
+    }
+
+    let mut messages = wsdl.messages.iter().collect::<Vec<_>>();
+    messages.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, message) in messages {
+        for part in &message.parts {
+            // parts of RPC style messages can refer to primitives
+            let primitive = !matches!(builtin_type(&part.element), SimpleType::Complex(_));
+            if !primitive && !wsdl.types.contains_key(&part.element) {
+                lints.push(WsdlLint::UnknownPartElement {
+                    message: name.clone(),
+                    element: part.element.clone(),
+                });
+            }
+        }
+    }
+
+    let element = |message: &Option<String>| {
+        message
+            .as_ref()
+            .and_then(|m| wsdl.messages.get(m))
+            .map(|m| m.part_element.as_str())
+    };
+    for operation in &operations {
+        let input = element(&operation.input);
+        if input.is_some() && input == element(&operation.output) {
+            lints.push(WsdlLint::SameInputAndOutput(operation.name.clone()));
+        }
+    }
+
+    let mut by_lowercase: HashMap<String, Vec<String>> = HashMap::new();
+    for operation in &operations {
+        by_lowercase
+            .entry(operation.name.to_lowercase())
+            .or_default()
+            .push(operation.name.clone());
+    }
+    let mut duplicates = by_lowercase
+        .into_iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|(_, names)| names)
+        .collect::<Vec<_>>();
+    duplicates.sort();
+    lints.extend(
+        duplicates
+            .into_iter()
+            .map(WsdlLint::DuplicateOperationNames),
+    );
+
+    lints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wsdl::parse;
+
+    #[test]
+    fn lints() {
+        let fields = (0..31)
+            .map(|i| format!(r#"<xsd:element name="f{}" type="xsd:string"/>"#, i))
+            .collect::<String>();
+        let source = format!(
+            r#"<?xml version="1.0"?>
+<definitions name="Lint"
+             targetNamespace="http://example.com/lint.wsdl"
+             xmlns:tns="http://example.com/lint.wsdl"
+             xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+             xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <types>
+    <xsd:schema targetNamespace="http://example.com/lint.wsdl">
+      <xsd:complexType name="Wide">
+        <xsd:sequence>{}</xsd:sequence>
+      </xsd:complexType>
+      <xsd:element name="Order" type="tns:Wide"/>
+    </xsd:schema>
+  </types>
+  <message name="OrderMessage">
+    <part name="body" element="tns:Order"/>
+  </message>
+  <message name="MissingMessage">
+    <part name="body" element="tns:Missing"/>
+    <part name="count" type="xsd:int"/>
+  </message>
+  <portType name="LintPortType">
+    <operation name="GetOrder">
+      <documentation>Fetches an order.</documentation>
+      <input message="tns:OrderMessage"/>
+      <output message="tns:MissingMessage"/>
+    </operation>
+    <operation name="getorder">
+      <input message="tns:OrderMessage"/>
+      <output message="tns:OrderMessage"/>
+    </operation>
+  </portType>
+  <service name="LintService"/>
+</definitions>"#,
+            fields
+        );
+        let wsdl = parse(source.as_bytes()).unwrap();
+
+        assert_eq!(
+            lint(&wsdl),
+            vec![
+                WsdlLint::UndocumentedOperation("getorder".to_string()),
+                WsdlLint::TooManyFields {
+                    type_name: "Wide".to_string(),
+                    fields: 31,
+                },
+                WsdlLint::UnknownPartElement {
+                    message: "MissingMessage".to_string(),
+                    element: "Missing".to_string(),
+                },
+                WsdlLint::SameInputAndOutput("getorder".to_string()),
+                WsdlLint::DuplicateOperationNames(vec![
+                    "GetOrder".to_string(),
+                    "getorder".to_string()
+                ]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! URIs of the XML namespaces used by WSDL documents and SOAP messages.
+
+/// XML schema types, as in `xsd:string`.
+pub const XSD_NS: &str = "http://www.w3.org/2001/XMLSchema";
+
+/// XML schema instance attributes, as in `xsi:nil` and `xsi:type`.
+pub const XSI_NS: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// SOAP 1.1 envelopes.
+pub const SOAP11_NS: &str = "http://schemas.xmlsoap.org/soap/envelope/";
+
+/// SOAP 1.2 envelopes.
+pub const SOAP12_NS: &str = "http://www.w3.org/2003/05/soap-envelope";
+
+/// `encodingStyle` of the SOAP 1.1 encoding rules.
+pub const SOAP_ENC_NS: &str = "http://schemas.xmlsoap.org/soap/encoding/";
+
+/// WSDL 1.1 definitions.
+pub const WSDL11_NS: &str = "http://schemas.xmlsoap.org/wsdl/";
+
+/// WSDL 1.1 bindings to SOAP 1.1, as in `soap:binding` and `soap:address`.
+pub const WSDL_SOAP11_NS: &str = "http://schemas.xmlsoap.org/wsdl/soap/";
+
+/// WSDL 1.1 bindings to SOAP 1.2, as in `soap12:binding` and `soap12:address`.
+pub const WSDL_SOAP12_NS: &str = "http://schemas.xmlsoap.org/wsdl/soap12/";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn namespaces() {
+        assert_eq!(XSD_NS, "http://www.w3.org/2001/XMLSchema");
+        assert_eq!(XSI_NS, "http://www.w3.org/2001/XMLSchema-instance");
+        assert_eq!(SOAP11_NS, "http://schemas.xmlsoap.org/soap/envelope/");
+        assert_eq!(SOAP12_NS, "http://www.w3.org/2003/05/soap-envelope");
+        assert_eq!(SOAP_ENC_NS, "http://schemas.xmlsoap.org/soap/encoding/");
+        assert_eq!(WSDL11_NS, "http://schemas.xmlsoap.org/wsdl/");
+        assert_eq!(WSDL_SOAP11_NS, "http://schemas.xmlsoap.org/wsdl/soap/");
+        assert_eq!(WSDL_SOAP12_NS, "http://schemas.xmlsoap.org/wsdl/soap12/");
+    }
+}
@@ -6,8 +6,16 @@ use std::fmt;
 use std::result;
 
 use self::xml::BuildElement;
+use crate::soap::Envelope;
+use crate::wsdl::Part;
 use xmltree::Element;
 
+/// Prefix bound to the target namespace in request envelopes.
+pub const TARGET_PREFIX: &str = "ns";
+
+/// Namespace of the `xsi:nil` attribute written for absent nillable values.
+pub use crate::ns::XSI_NS;
+
 /// XML method representation.
 #[derive(Debug)]
 pub struct Method {
@@ -34,27 +42,31 @@ impl Method {
 
     /// Convert method to full XML envelope.
     pub fn as_xml(&self, api_url: &str) -> String {
-        let namespace = "ns";
-
-        let envelope = Element::node("soap:Envelope")
-            .with_attr("xmlns:soap", "http://schemas.xmlsoap.org/soap/envelope/")
-            .with_attr(format!("xmlns:{}", namespace), api_url)
-            .with_children(vec![
-                Element::node("soap:Header"),
-                Element::node("soap:Body").with_child(
-                    Element::node(format!("{}:{}", namespace, self.name))
-                        .with_children_from_iter(self.args.iter()),
-                ),
-            ]);
-
-        envelope.to_string()
+        self.as_envelope(api_url).to_xml()
+    }
+
+    /// Envelope of the method, to which headers can be added.
+    pub fn as_envelope(&self, api_url: &str) -> Envelope {
+        let namespace = TARGET_PREFIX;
+
+        Envelope::new()
+            .with_namespace(namespace, api_url)
+            .with_body(
+                Element::node(format!("{}:{}", namespace, self.name))
+                    .with_children_from_iter(self.args.iter()),
+            )
     }
 }
 
 /// XML response representation.
 #[derive(Debug)]
 pub struct Response {
+    /// first element of the body
     pub body: Element,
+    /// every element of the body, in document order
+    pub body_elements: Vec<Element>,
+    /// children of the SOAP header, if any
+    pub headers: Vec<Element>,
 }
 
 impl Response {
@@ -66,7 +78,23 @@ impl Response {
         if element.name != "Envelope" {
             return Err(RpcError::UnexpectedElement { tag: element.name });
         }
+        let headers = element
+            .get_child("Header")
+            .map(|h| {
+                h.children
+                    .iter()
+                    .filter_map(|c| c.as_element())
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
         element = element.descend(&["Body"])?;
+        let body_elements = element
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .cloned()
+            .collect();
         element = element.descend_first()?;
 
         if element.name == "Fault" {
@@ -85,7 +113,29 @@ impl Response {
             });
         }
 
-        Ok(Response { body: element })
+        Ok(Response {
+            body: element,
+            body_elements,
+            headers,
+        })
+    }
+
+    /// Element of a part of the output message.
+    ///
+    /// Parts of document style responses are elements of the body, named
+    /// after the part element, while parts of RPC style responses are
+    /// children of the wrapper element, named after the part.
+    pub fn part(&self, part: &Part) -> Option<&Element> {
+        self.body_elements
+            .iter()
+            .find(|e| e.name == part.element)
+            .or_else(|| self.body.get_child(part.name.as_str()))
+    }
+
+    /// Elements of the output parts, as listed by `Wsdl::output_parts`,
+    /// skipping the parts missing from the response.
+    pub fn parts<'a>(&'a self, parts: &[&Part]) -> Vec<&'a Element> {
+        parts.iter().filter_map(|part| self.part(part)).collect()
     }
 }
 
@@ -130,8 +180,8 @@ pub type Result<T> = result::Result<T, RpcError>;
 
 #[cfg(test)]
 mod test {
+    use super::xml::BuildElement;
     use super::*;
-    use rpser::xml::BuildElement;
 
     #[test]
     fn can_deal_with_fault() {
@@ -183,7 +233,7 @@ mod test {
                 assert_eq!(response.body.name, "loginResponse");
                 let return_element = response.body.descend_first().unwrap();
                 assert_eq!(return_element.name, "loginReturn");
-                assert_eq!(return_element.text, Some("a3a8ecc6d5".into()));
+                assert_eq!(return_element.get_text(), Some("a3a8ecc6d5".into()));
             }
             other => panic!(
                 "expected to receive fault in this test, received {:?}",
@@ -191,4 +241,38 @@ mod test {
             ),
         };
     }
+
+    #[test]
+    fn reads_soap_encoded_array() {
+        let encoded_response = r#"<?xml version="1.0" encoding="utf-8"?>
+            <soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/" xmlns:soapenc="http://schemas.xmlsoap.org/soap/encoding/" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+                <soapenv:Body>
+                    <ns1:getSpacesResponse soapenv:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/" xmlns:ns1="https://confluence/plugins/servlet/soap-axis1/confluenceservice-v2">
+                        <getSpacesReturn xsi:type="soapenc:Array" soapenc:arrayType="xsd:string[2]">
+                            <item xsi:type="xsd:string">DOC</item>
+                            <item xsi:type="xsd:string">DEV</item>
+                        </getSpacesReturn>
+                    </ns1:getSpacesResponse>
+                </soapenv:Body>
+            </soapenv:Envelope>
+        "#;
+
+        let response = Response::from_xml(encoded_response).unwrap();
+        let array = response.body.descend_first().unwrap();
+        let (array_type, items) = array.as_array().unwrap();
+        assert_eq!(array_type.item_type, "xsd:string");
+        assert_eq!(array_type.size, Some(2));
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].as_string().unwrap(), "DEV");
+
+        let truncated = array.clone().with_attr("arrayType", "xsd:string[3]");
+        assert_eq!(
+            truncated.as_array(),
+            Err(xml::Error::ArraySizeMismatch {
+                name: "getSpacesReturn".to_string(),
+                expected: 3,
+                given: 2,
+            })
+        );
+    }
 }
@@ -22,10 +22,49 @@ pub enum Error {
     ParseIntError { name: String, inner: ParseIntError },
     /// Can't parse received element.
     ParseDateTimeError { name: String, inner: ParseError },
+    /// Boolean element holding a value other than `true`, `false`, `1` or `0`.
+    ParseBoolError { name: String, value: String },
+    /// Expected a SOAP encoded array with an `arrayType` attribute.
+    ExpectedArray { name: String },
+    /// SOAP encoded array does not hold the number of items its `arrayType` declares.
+    ArraySizeMismatch {
+        name: String,
+        expected: usize,
+        given: usize,
+    },
     /// empty
     Empty,
 }
 
+/// Item type and size declared by the `arrayType` attribute of a SOAP encoded array.
+///
+/// `arrayType="xsd:string[3]"` declares three `xsd:string` items, while
+/// `arrayType="ns:Type[]"` leaves the size unspecified.
+#[derive(Debug, PartialEq)]
+pub struct ArrayType {
+    pub item_type: String,
+    pub size: Option<usize>,
+}
+
+impl ArrayType {
+    /// Parse the value of an `arrayType` attribute.
+    pub fn parse(value: &str) -> Option<ArrayType> {
+        let start = value.rfind('[')?;
+        if !value.ends_with(']') {
+            return None;
+        }
+        let size = match &value[start + 1..value.len() - 1] {
+            "" => None,
+            n => Some(n.parse().ok()?),
+        };
+
+        Some(ArrayType {
+            item_type: value[..start].to_string(),
+            size,
+        })
+    }
+}
+
 /// Helper trait for building `xmltree::Element`.
 ///
 /// Such convenience methods were not available in `xmltree::Element`, so they are added
@@ -109,6 +148,12 @@ pub trait BuildElement {
 
     /// Extract the value of `DateTime` type from the text.
     fn as_datetime(&self) -> Result<DateTime<Utc>, Error>;
+
+    /// Extract the `arrayType` of a SOAP encoded array.
+    fn array_type(&self) -> Option<ArrayType>;
+
+    /// Extract the items of a SOAP encoded array, checking them against its `arrayType`.
+    fn as_array(&self) -> Result<(ArrayType, Vec<Element>), Error>;
 }
 
 impl BuildElement for Element {
@@ -219,12 +264,16 @@ impl BuildElement for Element {
         }
     }
 
-    fn descend_first(mut self) -> Result<Element, Error> {
-        if self.children.is_empty() {
-            Err(Error::ExpectedNotEmpty { parent: self.name })
-        } else {
-            Ok(self.children.remove(0).as_element().unwrap().clone())
-        }
+    fn descend_first(self) -> Result<Element, Error> {
+        // text, comments and processing instructions may come before the element
+        let parent = self.name;
+        self.children
+            .into_iter()
+            .find_map(|c| match c {
+                xmltree::XMLNode::Element(e) => Some(e),
+                _ => None,
+            })
+            .ok_or(Error::ExpectedNotEmpty { parent })
     }
 
     fn get_at_path(&self, path: &[&str]) -> Result<Element, Error> {
@@ -296,7 +345,41 @@ impl BuildElement for Element {
 
     fn as_boolean(&self) -> Result<bool, Error> {
         let text = get_typed_string(self, "boolean")?;
-        Ok(text == "true")
+        match text.trim() {
+            "true" | "1" => Ok(true),
+            "false" | "0" => Ok(false),
+            value => Err(Error::ParseBoolError {
+                name: self.name.clone(),
+                value: value.to_string(),
+            }),
+        }
+    }
+
+    fn array_type(&self) -> Option<ArrayType> {
+        self.attributes
+            .get("arrayType")
+            .and_then(|value| ArrayType::parse(value))
+    }
+
+    fn as_array(&self) -> Result<(ArrayType, Vec<Element>), Error> {
+        let array_type = self.array_type().ok_or_else(|| Error::ExpectedArray {
+            name: self.name.clone(),
+        })?;
+        let items: Vec<Element> = self
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .cloned()
+            .collect();
+
+        match array_type.size {
+            Some(expected) if expected != items.len() => Err(Error::ArraySizeMismatch {
+                name: self.name.clone(),
+                expected,
+                given: items.len(),
+            }),
+            _ => Ok((array_type, items)),
+        }
     }
 }
 
@@ -314,3 +397,29 @@ fn get_typed_string(element: &Element, value_type: &str) -> Result<String, Error
         },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn booleans() {
+        let boolean = |text: &str| {
+            Element::node("active")
+                .with_attr("type", "xsd:boolean")
+                .with_text(text)
+                .as_boolean()
+        };
+        assert_eq!(boolean("true"), Ok(true));
+        assert_eq!(boolean("1"), Ok(true));
+        assert_eq!(boolean("false"), Ok(false));
+        assert_eq!(boolean(" 0 "), Ok(false));
+        assert_eq!(
+            boolean("yes"),
+            Err(Error::ParseBoolError {
+                name: "active".to_string(),
+                value: "yes".to_string(),
+            })
+        );
+    }
+}
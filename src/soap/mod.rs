@@ -0,0 +1,213 @@
+//! SOAP envelopes, along with the headers of the WS-* extensions.
+
+#[cfg(feature = "rm")]
+pub mod reliable;
+pub mod security;
+#[cfg(feature = "quick-xml")]
+mod stream;
+
+#[cfg(feature = "quick-xml")]
+pub use stream::{stream_deserialize, Event, FeedResult, FromXmlEvents};
+
+use crate::ns::{SOAP12_NS, XSI_NS};
+use crate::rpser::xml::BuildElement;
+use chrono::Duration;
+use xmltree::Element;
+
+/// Namespace of SOAP 1.1 envelopes.
+pub use crate::ns::SOAP11_NS as SOAP_ENV_NS;
+pub use crate::ns::{SOAP_ENC_NS, XSD_NS};
+
+#[derive(Debug)]
+pub enum SoapError {
+    #[cfg(feature = "quick-xml")]
+    Xml(quick_xml::Error),
+    /// the document is not well-formed XML
+    Parse(xmltree::ParseError),
+    /// the body holds a SOAP fault, with its `faultstring` (or `Reason/Text` in SOAP 1.2)
+    Fault(String),
+    /// the document is not a SOAP envelope, or an item rejected its events
+    Invalid(String),
+}
+
+#[cfg(feature = "quick-xml")]
+impl From<quick_xml::Error> for SoapError {
+    fn from(error: quick_xml::Error) -> Self {
+        SoapError::Xml(error)
+    }
+}
+
+impl From<xmltree::ParseError> for SoapError {
+    fn from(error: xmltree::ParseError) -> Self {
+        SoapError::Parse(error)
+    }
+}
+
+/// A SOAP envelope, written with the `soap` prefix.
+#[derive(Debug, Clone, Default)]
+pub struct Envelope {
+    /// namespaces declared on the envelope, as `(prefix, uri)`
+    pub namespaces: Vec<(String, String)>,
+    pub headers: Vec<Element>,
+    pub body: Vec<Element>,
+}
+
+impl Envelope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_namespace(mut self, prefix: &str, uri: &str) -> Self {
+        self.namespaces.push((prefix.to_string(), uri.to_string()));
+        self
+    }
+
+    pub fn with_header(mut self, header: Element) -> Self {
+        self.headers.push(header);
+        self
+    }
+
+    pub fn with_body(mut self, body: Element) -> Self {
+        self.body.push(body);
+        self
+    }
+
+    /// Adds the WS-ReliableMessaging header of a message in a sequence.
+    #[cfg(feature = "rm")]
+    pub fn with_rm_sequence(self, header: reliable::SequenceHeader) -> Self {
+        self.with_header(header.to_element())
+    }
+
+    /// Adds a WS-Security timestamp to the header, declaring the envelope
+    /// valid for `ttl` from now.
+    pub fn with_timestamp(mut self, ttl: Duration) -> Self {
+        let timestamp = security::Timestamp::new(ttl).to_element();
+        security::add_to_security_header(&mut self.headers, timestamp);
+        self
+    }
+
+    /// Adds a WS-Security username token to the header, with a nonce and its
+    /// creation time. The password is sent as a digest if `digest` is set.
+    pub fn with_username_token(mut self, username: &str, password: &str, digest: bool) -> Self {
+        let token = security::UsernameToken::new(username, password, digest).to_element();
+        security::add_to_security_header(&mut self.headers, token);
+        self
+    }
+
+    pub fn to_element(&self) -> Element {
+        let mut envelope = Element::node("soap:Envelope")
+            .with_attr("xmlns:soap", SOAP_ENV_NS)
+            .with_attr("xmlns:xsi", XSI_NS);
+        for (prefix, uri) in &self.namespaces {
+            envelope = envelope.with_attr(format!("xmlns:{}", prefix), uri.as_str());
+        }
+
+        envelope.with_children(vec![
+            Element::node("soap:Header").with_children_from_iter(self.headers.iter()),
+            Element::node("soap:Body").with_children_from_iter(self.body.iter()),
+        ])
+    }
+
+    pub fn to_xml(&self) -> String {
+        self.to_element().to_string()
+    }
+
+    /// Reads a SOAP 1.1 or 1.2 envelope, with the children of its header and
+    /// body. The namespaces declared on the envelope are kept, except the
+    /// envelope namespace itself and `xsi`, which `to_element` declares.
+    pub fn from_xml(bytes: &[u8]) -> Result<Envelope, SoapError> {
+        let root = Element::parse(bytes)?;
+        let soap_ns = match root.namespace.as_deref() {
+            Some(ns) if root.name == "Envelope" && (ns == SOAP_ENV_NS || ns == SOAP12_NS) => ns,
+            _ => {
+                return Err(SoapError::Invalid(format!(
+                    "expected a SOAP envelope, found {}",
+                    root.name
+                )))
+            }
+        };
+        let children = |name: &str| -> Vec<Element> {
+            root.get_child((name, soap_ns))
+                .map(|e| {
+                    e.children
+                        .iter()
+                        .filter_map(|c| c.as_element().cloned())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        if root.get_child(("Body", soap_ns)).is_none() {
+            return Err(SoapError::Invalid("the envelope has no body".to_string()));
+        }
+
+        let namespaces = root
+            .namespaces
+            .iter()
+            .flat_map(|ns| ns.0.iter())
+            .filter(|(prefix, uri)| {
+                !prefix.is_empty()
+                    && !matches!(prefix.as_str(), "xml" | "xmlns")
+                    && uri.as_str() != soap_ns
+                    && uri.as_str() != XSI_NS
+            })
+            .map(|(prefix, uri)| (prefix.clone(), uri.clone()))
+            .collect();
+
+        Ok(Envelope {
+            namespaces,
+            headers: children("Header"),
+            body: children("Body"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_xml() {
+        let envelope = Envelope::new()
+            .with_namespace("tns", "http://example.com/orders")
+            .with_header(Element::node("tns:Session").with_text("42"))
+            .with_body(
+                Element::node("tns:GetOrder")
+                    .with_children(vec![Element::node("tns:id").with_text("7")]),
+            );
+        let parsed = Envelope::from_xml(envelope.to_xml().as_bytes()).unwrap();
+        assert_eq!(parsed.namespaces, envelope.namespaces);
+        assert_eq!(parsed.headers.len(), 1);
+        assert_eq!(parsed.headers[0].name, "Session");
+        assert_eq!(parsed.headers[0].get_text().as_deref(), Some("42"));
+        assert_eq!(parsed.body.len(), 1);
+        assert_eq!(parsed.body[0].name, "GetOrder");
+        assert_eq!(
+            parsed.body[0].namespace.as_deref(),
+            Some("http://example.com/orders")
+        );
+        assert!(parsed.body[0].get_child("id").is_some());
+
+        let soap12 = format!(
+            r#"<env:Envelope xmlns:env="{}"><env:Body><Ping/></env:Body></env:Envelope>"#,
+            SOAP12_NS
+        );
+        let parsed = Envelope::from_xml(soap12.as_bytes()).unwrap();
+        assert!(parsed.namespaces.is_empty());
+        assert!(parsed.headers.is_empty());
+        assert_eq!(parsed.body[0].name, "Ping");
+
+        assert!(matches!(
+            Envelope::from_xml(b"<Envelope><Body/></Envelope>"),
+            Err(SoapError::Invalid(_))
+        ));
+        let no_body = format!(r#"<soap:Envelope xmlns:soap="{}"/>"#, SOAP_ENV_NS);
+        assert!(matches!(
+            Envelope::from_xml(no_body.as_bytes()),
+            Err(SoapError::Invalid(_))
+        ));
+        assert!(matches!(
+            Envelope::from_xml(b"<soap:Envelope"),
+            Err(SoapError::Parse(_))
+        ));
+    }
+}
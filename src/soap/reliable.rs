@@ -0,0 +1,135 @@
+//! WS-ReliableMessaging sequences.
+//!
+//! A `Sequence` added as an interceptor to a generated client numbers the
+//! messages it sends, so the service can deliver them exactly once.
+
+use crate::http::{InterceptorError, RequestInterceptor, SoapRequest};
+use crate::rpser::xml::BuildElement;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use xmltree::Element;
+
+/// Namespace of WS-ReliableMessaging 1.0.
+pub const RM_NS: &str = "http://schemas.xmlsoap.org/ws/2005/02/rm";
+
+/// `wsrm:Sequence` header of a message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequenceHeader {
+    pub identifier: String,
+    /// position of the message in the sequence, starting at 1
+    pub message_number: u64,
+    pub last_message: bool,
+}
+
+impl SequenceHeader {
+    pub fn to_element(&self) -> Element {
+        let mut children = vec![
+            Element::node("wsrm:Identifier").with_text(self.identifier.as_str()),
+            Element::node("wsrm:MessageNumber").with_text(self.message_number.to_string()),
+        ];
+        if self.last_message {
+            children.push(Element::node("wsrm:LastMessage"));
+        }
+
+        Element::node("wsrm:Sequence")
+            .with_attr("xmlns:wsrm", RM_NS)
+            .with_attr("soap:mustUnderstand", "1")
+            .with_children(children)
+    }
+}
+
+/// Numbers the messages of a session, as an interceptor of a generated client.
+#[derive(Debug)]
+pub struct Sequence {
+    identifier: String,
+    last_number: AtomicU64,
+    closing: AtomicBool,
+}
+
+impl Sequence {
+    /// Starts numbering messages in the sequence created by the service under `identifier`.
+    pub fn new(identifier: &str) -> Self {
+        Sequence {
+            identifier: identifier.to_string(),
+            last_number: AtomicU64::new(0),
+            closing: AtomicBool::new(false),
+        }
+    }
+
+    /// Marks the next message as the last one of the sequence.
+    pub fn close(&self) {
+        self.closing.store(true, Ordering::SeqCst);
+    }
+
+    /// Header of the next message.
+    pub fn next_header(&self) -> SequenceHeader {
+        SequenceHeader {
+            identifier: self.identifier.clone(),
+            message_number: self.last_number.fetch_add(1, Ordering::SeqCst) + 1,
+            last_message: self.closing.load(Ordering::SeqCst),
+        }
+    }
+}
+
+impl RequestInterceptor for Sequence {
+    fn before_send(&self, req: &mut SoapRequest) -> Result<(), InterceptorError> {
+        let mut envelope = Element::parse(req.body.as_bytes())
+            .map_err(|e| InterceptorError(format!("cannot add the sequence header: {}", e)))?;
+        let header = envelope
+            .get_mut_child("Header")
+            .ok_or_else(|| InterceptorError("the envelope has no header".to_string()))?;
+        header
+            .children
+            .push(xmltree::XMLNode::Element(self.next_header().to_element()));
+        req.body = envelope.to_string();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soap::Envelope;
+
+    #[test]
+    fn sequence_headers() {
+        let sequence = Sequence::new("urn:uuid:1");
+        let mut req = SoapRequest {
+            operation: "Ping".to_string(),
+            url: "http://localhost".to_string(),
+            headers: vec![],
+            body: Envelope::new().with_body(Element::new("Ping")).to_xml(),
+        };
+        sequence.before_send(&mut req).unwrap();
+        sequence.close();
+
+        assert_eq!(
+            sequence.next_header(),
+            SequenceHeader {
+                identifier: "urn:uuid:1".to_string(),
+                message_number: 2,
+                last_message: true,
+            }
+        );
+
+        let envelope = Element::parse(req.body.as_bytes()).unwrap();
+        let header = envelope
+            .get_child("Header")
+            .and_then(|h| h.get_child("Sequence"))
+            .unwrap();
+        assert_eq!(header.namespace.as_deref(), Some(RM_NS));
+        assert_eq!(
+            header
+                .get_child("MessageNumber")
+                .unwrap()
+                .get_text()
+                .unwrap(),
+            "1"
+        );
+        assert!(header.get_child("LastMessage").is_none());
+
+        let envelope = Envelope::new().with_rm_sequence(sequence.next_header());
+        let header = &envelope.headers[0];
+        assert_eq!(header.name, "wsrm:Sequence");
+        assert!(header.get_child("wsrm:LastMessage").is_some());
+    }
+}
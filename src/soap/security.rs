@@ -0,0 +1,335 @@
+//! WS-Security headers.
+//!
+//! A `Timestamp` declares the window in which a message is valid, so a
+//! service can reject messages replayed after they expire. Requests carry one
+//! through `Envelope::with_timestamp`, and the timestamps of responses are
+//! checked by a `TimestampValidator` added as an interceptor.
+//!
+//! A `UsernameToken` authenticates the sender of a request with a username
+//! and a password, sent either as is or as a digest. Requests carry one
+//! through `Envelope::with_username_token`.
+
+use crate::http::{InterceptorError, RequestInterceptor, SoapResponse};
+use crate::rpser::xml::BuildElement;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use std::fmt;
+use xmltree::Element;
+
+/// Namespace of the WS-Security 1.0 extension.
+pub const WSSE_NS: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-secext-1.0.xsd";
+/// Namespace of the WS-Security 1.0 utilities, holding the timestamp.
+pub const WSU_NS: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-utility-1.0.xsd";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecurityError {
+    /// the message has no `wsu:Timestamp` in its `wsse:Security` header
+    MissingTimestamp,
+    /// `wsu:Created` or `wsu:Expires` is missing or not a date, with its name
+    InvalidTimestamp(String),
+    /// the message was created after the current time, beyond the clock skew
+    NotYetValid(DateTime<Utc>),
+    /// the message expired before the current time, beyond the clock skew
+    Expired(DateTime<Utc>),
+}
+
+impl fmt::Display for SecurityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SecurityError::MissingTimestamp => write!(f, "the message has no timestamp"),
+            SecurityError::InvalidTimestamp(name) => {
+                write!(f, "the timestamp has no valid {} date", name)
+            }
+            SecurityError::NotYetValid(created) => {
+                write!(f, "the message was created in the future, at {}", created)
+            }
+            SecurityError::Expired(expires) => write!(f, "the message expired at {}", expires),
+        }
+    }
+}
+
+impl std::error::Error for SecurityError {}
+
+/// `wsu:Timestamp` of a message, valid from `created` to `expires`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timestamp {
+    pub created: DateTime<Utc>,
+    pub expires: DateTime<Utc>,
+}
+
+impl Timestamp {
+    /// Timestamp of a message created now and valid for `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        let created = Utc::now();
+        Timestamp {
+            created,
+            expires: created + ttl,
+        }
+    }
+
+    pub fn to_element(&self) -> Element {
+        let date = |d: &DateTime<Utc>| d.to_rfc3339_opts(SecondsFormat::Millis, true);
+        Element::node("wsu:Timestamp")
+            .with_attr("xmlns:wsu", WSU_NS)
+            .with_children(vec![
+                Element::node("wsu:Created").with_text(date(&self.created)),
+                Element::node("wsu:Expires").with_text(date(&self.expires)),
+            ])
+    }
+
+    /// Reads the timestamp of a parsed envelope.
+    pub fn from_envelope(envelope: &Element) -> Result<Self, SecurityError> {
+        let timestamp = envelope
+            .get_child("Header")
+            .and_then(|h| h.get_child(("Security", WSSE_NS)))
+            .and_then(|s| s.get_child(("Timestamp", WSU_NS)))
+            .ok_or(SecurityError::MissingTimestamp)?;
+        let date = |name: &str| {
+            timestamp
+                .get_child((name, WSU_NS))
+                .and_then(|e| e.get_text())
+                .and_then(|text| DateTime::parse_from_rfc3339(text.trim()).ok())
+                .map(|d| d.with_timezone(&Utc))
+                .ok_or_else(|| SecurityError::InvalidTimestamp(name.to_string()))
+        };
+
+        Ok(Timestamp {
+            created: date("Created")?,
+            expires: date("Expires")?,
+        })
+    }
+
+    /// Checks that `now` is in the validity window, widened by `skew` on
+    /// both ends to allow for the difference between the clocks of the
+    /// client and the service.
+    pub fn validate(&self, now: DateTime<Utc>, skew: Duration) -> Result<(), SecurityError> {
+        if self.created > now + skew {
+            return Err(SecurityError::NotYetValid(self.created));
+        }
+        if self.expires < now - skew {
+            return Err(SecurityError::Expired(self.expires));
+        }
+        Ok(())
+    }
+}
+
+/// `wsse:Password` type of a password sent as is.
+pub const PASSWORD_TEXT: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-username-token-profile-1.0#PasswordText";
+/// `wsse:Password` type of a password sent as a digest.
+pub const PASSWORD_DIGEST: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-username-token-profile-1.0#PasswordDigest";
+/// Encoding of the `wsse:Nonce` of a username token.
+pub const BASE64_BINARY: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-soap-message-security-1.0#Base64Binary";
+
+/// `wsse:UsernameToken` of a request, created at `created` with a random
+/// `nonce` so a service can reject replayed requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsernameToken {
+    pub username: String,
+    pub password: String,
+    /// sends the password as a digest instead of as is
+    pub digest: bool,
+    pub nonce: Vec<u8>,
+    pub created: DateTime<Utc>,
+}
+
+impl UsernameToken {
+    /// Token created now, with a random 16 bytes nonce.
+    pub fn new(username: &str, password: &str, digest: bool) -> Self {
+        let mut nonce = vec![0; 16];
+        getrandom::getrandom(&mut nonce).expect("cannot generate the nonce of a username token");
+        UsernameToken {
+            username: username.to_string(),
+            password: password.to_string(),
+            digest,
+            nonce,
+            created: Utc::now(),
+        }
+    }
+
+    fn created(&self) -> String {
+        self.created.to_rfc3339_opts(SecondsFormat::Millis, true)
+    }
+
+    /// `Base64(SHA-1(nonce + created + password))`, as defined by the
+    /// Username Token Profile.
+    pub fn password_digest(&self) -> String {
+        let mut sha1 = sha1_smol::Sha1::new();
+        sha1.update(&self.nonce);
+        sha1.update(self.created().as_bytes());
+        sha1.update(self.password.as_bytes());
+        base64::encode(sha1.digest().bytes())
+    }
+
+    pub fn to_element(&self) -> Element {
+        let (password_type, password) = match self.digest {
+            true => (PASSWORD_DIGEST, self.password_digest()),
+            false => (PASSWORD_TEXT, self.password.clone()),
+        };
+        Element::node("wsse:UsernameToken")
+            .with_attr("xmlns:wsu", WSU_NS)
+            .with_children(vec![
+                Element::node("wsse:Username").with_text(self.username.as_str()),
+                Element::node("wsse:Password")
+                    .with_attr("Type", password_type)
+                    .with_text(password),
+                Element::node("wsse:Nonce")
+                    .with_attr("EncodingType", BASE64_BINARY)
+                    .with_text(base64::encode(&self.nonce)),
+                Element::node("wsu:Created").with_text(self.created()),
+            ])
+    }
+}
+
+/// Adds `child` to the `wsse:Security` header of `headers`, which is created
+/// if needed, so every WS-Security token of a message shares a single header.
+pub(crate) fn add_to_security_header(headers: &mut Vec<Element>, child: Element) {
+    let position = match headers.iter().position(|h| h.name == "wsse:Security") {
+        Some(position) => position,
+        None => {
+            headers.push(
+                Element::node("wsse:Security")
+                    .with_attr("xmlns:wsse", WSSE_NS)
+                    .with_attr("soap:mustUnderstand", "1"),
+            );
+            headers.len() - 1
+        }
+    };
+    headers[position]
+        .children
+        .push(xmltree::XMLNode::Element(child));
+}
+
+/// Rejects responses without a timestamp, or whose timestamp is not valid
+/// at the time they are received, as an interceptor of a generated client.
+#[derive(Debug, Clone)]
+pub struct TimestampValidator {
+    /// accepted difference between the clocks of the client and the service
+    pub skew: Duration,
+}
+
+impl TimestampValidator {
+    pub fn new(skew: Duration) -> Self {
+        TimestampValidator { skew }
+    }
+}
+
+impl Default for TimestampValidator {
+    /// Accepts a clock skew of five minutes.
+    fn default() -> Self {
+        TimestampValidator::new(Duration::minutes(5))
+    }
+}
+
+impl RequestInterceptor for TimestampValidator {
+    fn after_receive(&self, resp: &SoapResponse) -> Result<(), InterceptorError> {
+        let envelope = Element::parse(resp.body.as_bytes())
+            .map_err(|e| InterceptorError(format!("cannot read the timestamp: {}", e)))?;
+        Timestamp::from_envelope(&envelope)
+            .and_then(|timestamp| timestamp.validate(Utc::now(), self.skew))
+            .map_err(|e| InterceptorError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soap::Envelope;
+
+    #[test]
+    fn timestamps() {
+        let envelope = Envelope::new()
+            .with_body(Element::new("Ping"))
+            .with_timestamp(Duration::minutes(5));
+        assert_eq!(envelope.headers.len(), 1);
+        let header = &envelope.headers[0];
+        assert_eq!(header.name, "wsse:Security");
+        assert!(header.get_child("wsu:Timestamp").is_some());
+
+        let envelope = Element::parse(envelope.to_xml().as_bytes()).unwrap();
+        let timestamp = Timestamp::from_envelope(&envelope).unwrap();
+        assert_eq!(timestamp.expires - timestamp.created, Duration::minutes(5));
+
+        let skew = Duration::seconds(30);
+        assert_eq!(timestamp.validate(timestamp.created, skew), Ok(()));
+        assert_eq!(
+            timestamp.validate(timestamp.created - Duration::minutes(1), skew),
+            Err(SecurityError::NotYetValid(timestamp.created))
+        );
+        assert_eq!(
+            timestamp.validate(timestamp.expires + Duration::seconds(10), skew),
+            Ok(())
+        );
+        assert_eq!(
+            timestamp.validate(timestamp.expires + Duration::minutes(1), skew),
+            Err(SecurityError::Expired(timestamp.expires))
+        );
+
+        let validator = TimestampValidator::default();
+        let response = |body: String| SoapResponse {
+            operation: "Ping".to_string(),
+            status: 200,
+            body,
+        };
+        let fresh = Envelope::new().with_timestamp(Duration::minutes(1));
+        assert!(validator.after_receive(&response(fresh.to_xml())).is_ok());
+        let expired = Envelope::new().with_timestamp(Duration::minutes(-10));
+        assert!(validator
+            .after_receive(&response(expired.to_xml()))
+            .is_err());
+        assert_eq!(
+            validator.after_receive(&response(Envelope::new().to_xml())),
+            Err(InterceptorError("the message has no timestamp".to_string()))
+        );
+    }
+
+    #[test]
+    fn username_tokens() {
+        let envelope = Envelope::new()
+            .with_timestamp(Duration::minutes(5))
+            .with_username_token("alice", "secret", true);
+        assert_eq!(envelope.headers.len(), 1);
+        let envelope = Element::parse(envelope.to_xml().as_bytes()).unwrap();
+        let security = envelope
+            .get_child("Header")
+            .and_then(|h| h.get_child(("Security", WSSE_NS)))
+            .unwrap();
+        assert!(security.get_child(("Timestamp", WSU_NS)).is_some());
+        let token = security.get_child(("UsernameToken", WSSE_NS)).unwrap();
+        let child = |name: &str, ns: &str| token.get_child((name, ns)).unwrap();
+        assert_eq!(
+            child("Username", WSSE_NS).get_text().as_deref(),
+            Some("alice")
+        );
+        let password = child("Password", WSSE_NS);
+        assert_eq!(password.attributes["Type"], PASSWORD_DIGEST);
+        let digest = base64::decode(password.get_text().unwrap().as_bytes()).unwrap();
+        assert_eq!(digest.len(), 20);
+        let nonce = base64::decode(child("Nonce", WSSE_NS).get_text().unwrap().as_bytes());
+        assert_eq!(nonce.unwrap().len(), 16);
+        assert!(child("Created", WSU_NS).get_text().is_some());
+
+        let token = UsernameToken {
+            username: "alice".to_string(),
+            password: "secret".to_string(),
+            digest: true,
+            nonce: b"0123456789abcdef".to_vec(),
+            created: DateTime::parse_from_rfc3339("2020-01-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        };
+        assert_eq!(token.password_digest(), "FSEZzqDTpwJ881p+O3WMmlVU9tc=");
+
+        let text = UsernameToken {
+            digest: false,
+            ..token
+        }
+        .to_element();
+        let password = text.get_child("wsse:Password").unwrap();
+        assert_eq!(password.attributes["Type"], PASSWORD_TEXT);
+        assert_eq!(password.get_text().as_deref(), Some("secret"));
+    }
+}
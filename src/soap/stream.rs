@@ -0,0 +1,264 @@
+//! Streaming deserialization of large SOAP responses.
+//!
+//! Responses listing thousands of items are read event by event, and every
+//! item is handed to the caller as soon as its end tag is read, instead of
+//! building the whole document as an `xmltree::Element`.
+
+use super::SoapError;
+use quick_xml::Reader;
+use std::io::{BufRead, BufReader, Read};
+
+pub use quick_xml::events::Event;
+
+/// Outcome of feeding an event to an item being deserialized.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedResult {
+    /// the item needs more events
+    Continue,
+    /// the item is complete, returned for the end tag of its element
+    Done,
+    /// the event does not fit the item, with the reason
+    Invalid(String),
+}
+
+/// Item built from the XML events of its element.
+pub trait FromXmlEvents: Default {
+    /// Updates the item with the next event of its element, from its start
+    /// tag (or empty tag) to its end tag. Whitespace-only text is skipped.
+    fn feed(&mut self, event: &Event) -> FeedResult;
+}
+
+/// Reads the items of a response one at a time.
+///
+/// The items are the children of the first element of the SOAP body, as in
+/// `<soap:Body><ListResponse><item/><item/></ListResponse></soap:Body>`. A
+/// SOAP fault is returned as an error. The iterator stops after the first
+/// error.
+pub fn stream_deserialize<T: FromXmlEvents>(
+    reader: impl Read,
+) -> impl Iterator<Item = Result<T, SoapError>> {
+    let mut reader = Reader::from_reader(BufReader::new(reader));
+    reader.trim_text(true);
+    Items {
+        reader,
+        buf: Vec::new(),
+        depth: 0,
+        body_depth: None,
+        wrapper: Wrapper::Pending,
+        item: None,
+        finished: false,
+    }
+}
+
+/// First element of the body, holding the items.
+enum Wrapper {
+    Pending,
+    Items,
+    /// a fault, with the name of the last element opened in it and the fault string
+    Fault(Vec<u8>, Option<String>),
+}
+
+struct Items<R: BufRead, T> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    /// number of elements currently open
+    depth: usize,
+    body_depth: Option<usize>,
+    wrapper: Wrapper,
+    item: Option<T>,
+    finished: bool,
+}
+
+impl<R: BufRead, T: FromXmlEvents> Items<R, T> {
+    fn next_item(&mut self) -> Result<Option<T>, SoapError> {
+        loop {
+            self.buf.clear();
+            let event = self.reader.read_event(&mut self.buf)?;
+            let opened = match &event {
+                Event::Start(_) => {
+                    self.depth += 1;
+                    true
+                }
+                Event::End(_) => {
+                    self.depth -= 1;
+                    false
+                }
+                Event::Empty(_) => true,
+                Event::Eof => {
+                    return Err(SoapError::Invalid("unexpected end of document".to_string()))
+                }
+                _ => false,
+            };
+
+            let body_depth = match self.body_depth {
+                Some(depth) => depth,
+                None => {
+                    if let Event::Start(e) = &event {
+                        if e.local_name() == b"Body" {
+                            self.body_depth = Some(self.depth);
+                        }
+                    }
+                    continue;
+                }
+            };
+            // depth of the items, or of the wrapper for an empty tag
+            let item_depth = body_depth + 2;
+
+            if let Some(item) = self.item.as_mut() {
+                let result = item.feed(&event);
+                let closed = self.depth < item_depth;
+                return match (result, closed) {
+                    (FeedResult::Invalid(reason), _) => Err(SoapError::Invalid(reason)),
+                    (FeedResult::Done, true) => Ok(self.item.take()),
+                    (FeedResult::Continue, false) => continue,
+                    (FeedResult::Done, false) => Err(SoapError::Invalid(
+                        "item completed before its end tag".to_string(),
+                    )),
+                    (FeedResult::Continue, true) => {
+                        Err(SoapError::Invalid("incomplete item".to_string()))
+                    }
+                };
+            }
+
+            // the body or the wrapper was closed
+            if !opened && self.depth < item_depth - 1 {
+                return match &self.wrapper {
+                    Wrapper::Fault(_, fault) => {
+                        Err(SoapError::Fault(fault.clone().unwrap_or_default()))
+                    }
+                    _ => Ok(None),
+                };
+            }
+
+            match (&mut self.wrapper, &event) {
+                (Wrapper::Pending, Event::Start(e)) => {
+                    self.wrapper = match e.local_name() {
+                        b"Fault" => Wrapper::Fault(Vec::new(), None),
+                        _ => Wrapper::Items,
+                    };
+                }
+                // empty wrapper, without any item
+                (Wrapper::Pending, Event::Empty(_)) => return Ok(None),
+                (Wrapper::Items, Event::Start(_)) => {
+                    let mut item = T::default();
+                    match item.feed(&event) {
+                        FeedResult::Invalid(reason) => return Err(SoapError::Invalid(reason)),
+                        _ => self.item = Some(item),
+                    }
+                }
+                (Wrapper::Items, Event::Empty(_)) => {
+                    let mut item = T::default();
+                    return match item.feed(&event) {
+                        FeedResult::Done => Ok(Some(item)),
+                        FeedResult::Invalid(reason) => Err(SoapError::Invalid(reason)),
+                        FeedResult::Continue => {
+                            Err(SoapError::Invalid("incomplete item".to_string()))
+                        }
+                    };
+                }
+                (Wrapper::Fault(name, _), Event::Start(e)) => *name = e.local_name().to_vec(),
+                (Wrapper::Fault(name, fault), Event::Text(e))
+                    if fault.is_none() && (name == b"faultstring" || name == b"Text") =>
+                {
+                    *fault = Some(String::from_utf8_lossy(&e.unescaped()?).into_owned());
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<R: BufRead, T: FromXmlEvents> Iterator for Items<R, T> {
+    type Item = Result<T, SoapError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.next_item() {
+            Ok(Some(item)) => Some(Ok(item)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(error) => {
+                self.finished = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Quote {
+        symbol: String,
+        in_symbol: bool,
+    }
+
+    impl FromXmlEvents for Quote {
+        fn feed(&mut self, event: &Event) -> FeedResult {
+            match event {
+                Event::Start(e) if e.local_name() == b"symbol" => self.in_symbol = true,
+                Event::Text(e) if self.in_symbol => {
+                    self.symbol = String::from_utf8_lossy(e.escaped()).into_owned()
+                }
+                Event::End(e) if e.local_name() == b"symbol" => self.in_symbol = false,
+                Event::End(e) if e.local_name() == b"quote" => return FeedResult::Done,
+                Event::Empty(e) if e.local_name() == b"quote" => return FeedResult::Done,
+                _ => {}
+            }
+            FeedResult::Continue
+        }
+    }
+
+    fn envelope(body: &str) -> String {
+        format!(
+            r#"<?xml version="1.0"?>
+<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+  <soap:Header/>
+  <soap:Body>{}</soap:Body>
+</soap:Envelope>"#,
+            body
+        )
+    }
+
+    #[test]
+    fn stream_items() {
+        let response = envelope(
+            r#"
+    <ListQuotesResponse>
+      <quote><symbol>ACME</symbol></quote>
+      <quote><symbol>INIT</symbol></quote>
+      <quote/>
+    </ListQuotesResponse>"#,
+        );
+        let quotes = stream_deserialize::<Quote>(response.as_bytes())
+            .map(|q| q.unwrap().symbol)
+            .collect::<Vec<_>>();
+        assert_eq!(quotes, vec!["ACME", "INIT", ""]);
+
+        let response = envelope("<ListQuotesResponse/>");
+        assert_eq!(stream_deserialize::<Quote>(response.as_bytes()).count(), 0);
+    }
+
+    #[test]
+    fn stream_fault() {
+        let response = envelope(
+            r#"
+    <soap:Fault>
+      <faultcode>soap:Server</faultcode>
+      <faultstring>Quotes unavailable</faultstring>
+    </soap:Fault>"#,
+        );
+        let mut quotes = stream_deserialize::<Quote>(response.as_bytes());
+        match quotes.next() {
+            Some(Err(SoapError::Fault(fault))) => assert_eq!(fault, "Quotes unavailable"),
+            other => panic!("expected a fault, got {:?}", other),
+        }
+        assert!(quotes.next().is_none());
+    }
+}
@@ -0,0 +1,311 @@
+//! TypeScript declarations of the types and operations of a WSDL document.
+
+use crate::wsdl::{SimpleType, Type, Wsdl};
+use case::CaseExt;
+use std::fmt::Write;
+
+/// Renders a `.d.ts` file declaring the types and operations of `wsdl`.
+///
+/// Complex types become interfaces, whose nillable fields are optional and
+/// repeated fields arrays. Simple types and aliases become type aliases, and
+/// enumerations unions of their values. Operations become functions taking
+/// the element of their input message and returning a promise of the element
+/// of their output message. Types are named as in the generated Rust code,
+/// and declarations are sorted by name.
+pub fn to_typescript(wsdl: &Wsdl) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "// Generated from the {} WSDL.", wsdl.name).unwrap();
+
+    write_types(&mut out, wsdl, Notation::TypeScript);
+
+    let element = |message: &Option<String>| {
+        message
+            .as_ref()
+            .and_then(|m| wsdl.messages.get(m))
+            .map(|m| Notation::TypeScript.type_name(&SimpleType::Complex(m.part_element.clone())))
+    };
+    let mut operations = wsdl.operations.iter().collect::<Vec<_>>();
+    operations.sort_by(|a, b| a.0.cmp(b.0));
+    for (_, operation) in operations {
+        writeln!(out).unwrap();
+        if let Some(documentation) = &operation.documentation {
+            writeln!(out, "{}", doc_comment(documentation)).unwrap();
+        }
+        let input = element(&operation.input)
+            .map(|input| format!("input: {}", input))
+            .unwrap_or_default();
+        let output = element(&operation.output).unwrap_or_else(|| "void".to_string());
+        writeln!(
+            out,
+            "export function {}({}): Promise<{}>;",
+            operation.name, input, output
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+/// Notation of the declarations written by `write_types`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Notation {
+    /// TypeScript, with types named as in the generated Rust code
+    TypeScript,
+    /// the TypeScript-like notation of `Wsdl::to_interface_description`,
+    /// with the names of the document and of the XML schema primitives
+    Description,
+}
+
+impl Notation {
+    /// Type of the values of `simple_type`.
+    fn type_name(self, simple_type: &SimpleType) -> String {
+        match (self, simple_type) {
+            (Notation::TypeScript, SimpleType::Complex(name)) => name.to_camel(),
+            (Notation::TypeScript, SimpleType::Boolean) => "boolean".to_string(),
+            (Notation::TypeScript, SimpleType::Float) | (Notation::TypeScript, SimpleType::Int) => {
+                "number".to_string()
+            }
+            // dates, binary data, decimals and names are written as text
+            (Notation::TypeScript, _) => "string".to_string(),
+            (Notation::Description, SimpleType::Complex(name)) => name.clone(),
+            (Notation::Description, simple) => {
+                simple.primitive_name().unwrap_or("string").to_string()
+            }
+        }
+    }
+
+    /// Keywords declaring a type alias and an interface.
+    fn keywords(self) -> (&'static str, &'static str) {
+        match self {
+            Notation::TypeScript => ("export type", "export interface"),
+            Notation::Description => ("type", "type"),
+        }
+    }
+}
+
+/// Writes the declarations of the types of `wsdl`, sorted by name.
+///
+/// Complex types become interfaces, whose nillable fields are optional and
+/// repeated fields arrays. Simple types and aliases become type aliases, and
+/// enumerations unions of their values.
+pub(crate) fn write_types(out: &mut String, wsdl: &Wsdl, notation: Notation) {
+    let (alias, interface) = notation.keywords();
+    let mut types = wsdl.types.iter().collect::<Vec<_>>();
+    types.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, t) in types {
+        writeln!(out).unwrap();
+        let name = notation.type_name(&SimpleType::Complex(name.clone()));
+        match t {
+            Type::Complex(c) => {
+                writeln!(out, "{} {} {{", interface, name).unwrap();
+                let mut fields = c.fields.iter().collect::<Vec<_>>();
+                fields.sort_by(|a, b| a.0.cmp(b.0));
+                for (field, (attributes, field_type)) in fields {
+                    if let Some(documentation) = &attributes.documentation {
+                        writeln!(out, "  {}", doc_comment(documentation)).unwrap();
+                    }
+                    writeln!(
+                        out,
+                        "  {}{}: {}{};",
+                        property_name(field),
+                        if attributes.nillable { "?" } else { "" },
+                        notation.type_name(field_type),
+                        if attributes.is_repeated() { "[]" } else { "" },
+                    )
+                    .unwrap();
+                }
+                writeln!(out, "}}").unwrap();
+            }
+            Type::Simple(simple) => {
+                writeln!(out, "{} {} = {};", alias, name, notation.type_name(simple)).unwrap()
+            }
+            Type::Alias(target) => {
+                let target = notation.type_name(&SimpleType::Complex(target.clone()));
+                writeln!(out, "{} {} = {};", alias, name, target).unwrap()
+            }
+            Type::Enumeration(values) => {
+                let values = values.iter().map(|v| string_literal(v)).collect::<Vec<_>>();
+                writeln!(out, "{} {} = {};", alias, name, values.join(" | ")).unwrap()
+            }
+            // kept as text to preserve their precision
+            Type::Decimal { .. } => {
+                let decimal = notation.type_name(&SimpleType::Decimal);
+                writeln!(out, "{} {} = {};", alias, name, decimal).unwrap()
+            }
+        }
+    }
+}
+
+/// Name of an interface property, quoted if it is not an identifier.
+fn property_name(name: &str) -> String {
+    let identifier = !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    match identifier {
+        true => name.to_string(),
+        false => string_literal(name),
+    }
+}
+
+/// Double quoted JavaScript string literal of `value`.
+fn string_literal(value: &str) -> String {
+    let mut literal = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            // line terminators in JavaScript, unlike in JSON
+            '\u{2028}' | '\u{2029}' => write!(literal, "\\u{:04x}", c as u32).unwrap(),
+            c if c.is_control() => write!(literal, "\\u{:04x}", c as u32).unwrap(),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// JSDoc comment holding `text`, whose `*/` would end the comment.
+fn doc_comment(text: &str) -> String {
+    format!("/** {} */", text.replace("*/", "*\\/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wsdl::parse;
+
+    #[test]
+    fn declarations() {
+        let wsdl = parse(
+            r#"<?xml version="1.0"?>
+<definitions name="Orders"
+             targetNamespace="http://example.com/orders.wsdl"
+             xmlns:tns="http://example.com/orders.wsdl"
+             xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+             xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <types>
+    <xsd:schema targetNamespace="http://example.com/orders.wsdl">
+      <xsd:element name="PlaceOrder">
+        <xsd:complexType>
+          <xsd:sequence>
+            <xsd:element name="customer" type="xsd:string">
+              <xsd:annotation>
+                <xsd:documentation>Name of the customer.</xsd:documentation>
+              </xsd:annotation>
+            </xsd:element>
+            <xsd:element name="items" type="tns:Item" maxOccurs="unbounded"/>
+            <xsd:element name="status" type="tns:Status"/>
+            <xsd:element name="express-delivery" type="xsd:boolean" minOccurs="0"/>
+          </xsd:sequence>
+        </xsd:complexType>
+      </xsd:element>
+      <xsd:complexType name="Item">
+        <xsd:sequence>
+          <xsd:element name="sku" type="xsd:string"/>
+          <xsd:element name="quantity" type="xsd:int"/>
+        </xsd:sequence>
+      </xsd:complexType>
+      <xsd:simpleType name="Status">
+        <xsd:restriction base="xsd:string">
+          <xsd:enumeration value="pending"/>
+          <xsd:enumeration value="shipped"/>
+        </xsd:restriction>
+      </xsd:simpleType>
+      <xsd:element name="OrderId" type="xsd:int"/>
+    </xsd:schema>
+  </types>
+  <message name="PlaceOrderInput">
+    <part name="body" element="tns:PlaceOrder"/>
+  </message>
+  <message name="PlaceOrderOutput">
+    <part name="body" element="tns:OrderId"/>
+  </message>
+  <portType name="OrdersPortType">
+    <operation name="CancelOrder">
+      <input message="tns:PlaceOrderInput"/>
+    </operation>
+    <operation name="PlaceOrder">
+      <documentation>Places an order.</documentation>
+      <input message="tns:PlaceOrderInput"/>
+      <output message="tns:PlaceOrderOutput"/>
+    </operation>
+  </portType>
+  <service name="OrdersService"/>
+</definitions>"#
+                .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            to_typescript(&wsdl),
+            r#"// Generated from the OrdersService WSDL.
+
+export interface Item {
+  quantity: number;
+  sku: string;
+}
+
+export type OrderId = number;
+
+export interface PlaceOrder {
+  /** Name of the customer. */
+  customer: string;
+  "express-delivery"?: boolean;
+  items: Item[];
+  status: Status;
+}
+
+export type Status = "pending" | "shipped";
+
+export function CancelOrder(input: PlaceOrder): Promise<void>;
+
+/** Places an order. */
+export function PlaceOrder(input: PlaceOrder): Promise<OrderId>;
+"#
+        );
+    }
+
+    #[test]
+    fn escaping() {
+        let wsdl = parse(
+            r#"<?xml version="1.0"?>
+<definitions name="Quoting"
+             targetNamespace="http://example.com/quoting.wsdl"
+             xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+             xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <types>
+    <xsd:schema targetNamespace="http://example.com/quoting.wsdl">
+      <xsd:complexType name="Quote">
+        <xsd:sequence>
+          <xsd:element name="text" type="xsd:string">
+            <xsd:annotation>
+              <xsd:documentation>Text with */ inside.</xsd:documentation>
+            </xsd:annotation>
+          </xsd:element>
+        </xsd:sequence>
+      </xsd:complexType>
+      <xsd:simpleType name="Mark">
+        <xsd:restriction base="xsd:string">
+          <xsd:enumeration value="say &quot;hi&quot;"/>
+          <xsd:enumeration value="C:\temp&#x2028;"/>
+        </xsd:restriction>
+      </xsd:simpleType>
+    </xsd:schema>
+  </types>
+  <portType name="QuotingPortType"/>
+  <service name="QuotingService"/>
+</definitions>"#
+                .as_bytes(),
+        )
+        .unwrap();
+
+        let typescript = to_typescript(&wsdl);
+        assert!(typescript.contains(r#"export type Mark = "say \"hi\"" | "C:\\temp\u2028";"#));
+        assert!(typescript.contains("  /** Text with *\\/ inside. */\n  text: string;"));
+    }
+}
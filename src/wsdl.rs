@@ -1,8 +1,18 @@
 //! WSDL inspection helpers.
 
-use std::collections::HashMap;
+use crate::ns::{SOAP_ENC_NS, WSDL11_NS, WSDL_SOAP11_NS, WSDL_SOAP12_NS, XSD_NS};
+use crate::typescript::Notation;
+use case::CaseExt;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use xmltree::Element;
 
+pub mod builder;
+
+pub use builder::{MessageBuilder, OperationBuilder};
+
 #[derive(Debug)]
 pub enum WsdlError {
     Parse(xmltree::ParseError),
@@ -10,6 +20,43 @@ pub enum WsdlError {
     AttributeNotFound(&'static str),
     NotAnElement,
     Empty,
+    /// element with several children where only one is allowed, holds its name
+    MultipleChildren(&'static str),
+    ImportNotFound(String),
+    /// import that was not fetched within `ParseOptions::import_timeout`, holds its URL
+    ImportTimeout(String),
+    /// schema element that is neither a complex nor a simple type, holds its name
+    UnsupportedType(String),
+    /// type defined more than once, holds its name
+    DuplicateType(String),
+    /// message defined more than once, holds its name
+    DuplicateMessage(String),
+    /// operation defined more than once, holds its name
+    DuplicateOperation(String),
+    UnknownOperation(String),
+    /// path to a field that does not match the schema
+    InvalidPath(String),
+    /// value of an enumeration field that is not one of its members
+    NotInEnumeration {
+        path: String,
+        value: String,
+    },
+    /// the root element is not in the WSDL 1.1 namespace, holds the detected namespace
+    UnsupportedWsdlVersion(String),
+    /// feature of the document that is not implemented, holds its description
+    UnsupportedFeature(String),
+    /// `minOccurs` or `maxOccurs` that is neither a number nor `unbounded`, holds it
+    InvalidOccurence(String),
+    /// `nillable` that is not an `xs:boolean`, holds it
+    InvalidNillable(String),
+    /// document rejected as potentially malicious, holds the reason
+    SecurityLimitExceeded(String),
+    Io(std::io::Error),
+    /// the HTTP client fetching the imports could not be built
+    #[cfg(feature = "async")]
+    HttpClient(reqwest::Error),
+    #[cfg(feature = "zip")]
+    Zip(zip::result::ZipError),
 }
 
 impl From<xmltree::ParseError> for WsdlError {
@@ -18,62 +65,632 @@ impl From<xmltree::ParseError> for WsdlError {
     }
 }
 
+impl From<std::io::Error> for WsdlError {
+    fn from(error: std::io::Error) -> Self {
+        WsdlError::Io(error)
+    }
+}
+
+#[cfg(feature = "zip")]
+impl From<zip::result::ZipError> for WsdlError {
+    fn from(error: zip::result::ZipError) -> Self {
+        WsdlError::Zip(error)
+    }
+}
+
+/// Surprising reading of a document, collected with `ParseOptions::collect_warnings`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WsdlWarning {
+    /// field with `minOccurs="0"` and at most one occurence, which is read as
+    /// nillable although the schema does not declare it `nillable="true"`
+    NillableNormalized { type_name: String, field: String },
+    /// XSD 1.1 element, as `xs:assert`, in a schema that does not declare
+    /// `vc:minVersion="1.1"` and is thus rejected by XSD 1.0 processors
+    UndeclaredXsd11Feature(String),
+}
+
+/// Version of XML Schema used by the schemas of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum XsdVersion {
+    V1_0,
+    /// declared with `vc:minVersion="1.1"`, or using elements only
+    /// available in XSD 1.1
+    V1_1,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for XsdVersion {
+    fn default() -> Self {
+        XsdVersion::V1_0
+    }
+}
+
 /// WSDL document.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Wsdl {
     pub name: String,
     pub target_namespace: String,
     pub types: HashMap<String, Type>,
     pub messages: HashMap<String, Message>,
+    /// operations by name, holding the first declaration of overloaded operations
     pub operations: HashMap<String, Operation>,
+    /// every declaration of the operations declared more than once, by name,
+    /// in document order
+    pub overloads: HashMap<String, Vec<Operation>>,
+    pub bindings: HashMap<String, Binding>,
+    /// service ports, in document order
+    pub endpoints: Vec<Endpoint>,
+    /// namespaces declared on the root element, by prefix (empty for the default namespace)
+    pub namespaces: HashMap<String, String>,
+    /// type definitions discarded by the `DuplicateStrategy`, in document order
+    pub overridden_types: Vec<(String, Type)>,
+    /// `targetNamespace` of the schema declaring each type, when it has one
+    pub type_namespaces: HashMap<String, String>,
+    /// `schemaLocation` of the imported or included schema declaring each
+    /// type, as given to the resolver, for types not declared in the document.
+    /// Kept beside `types`, like `type_namespaces`, because `Type` is an enum
+    /// with no room for a `source_location` field common to every variant.
+    pub type_locations: HashMap<String, String>,
+    /// warnings met while parsing, when `ParseOptions::collect_warnings` is set
+    pub warnings: Vec<WsdlWarning>,
+    /// highest version of XML Schema used by the schemas, imported ones included
+    pub schema_version: XsdVersion,
+    /// documentation of the document, by `"service:Name"`, `"operation:Name"`,
+    /// `"message:Name"`, `"type:Name"` and `"field:Type.field"`. Operations
+    /// are named by their key in `operations`
+    pub documentation: HashMap<String, String>,
+    /// whether operations are keyed by their lowercased name, from
+    /// `ParseOptions::normalize_operation_case`
+    pub normalize_operation_case: bool,
+}
+
+/// Names of the types and operations that differ between two versions of a WSDL.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WsdlDiff {
+    pub added_types: Vec<String>,
+    pub removed_types: Vec<String>,
+    pub changed_types: Vec<String>,
+    pub added_operations: Vec<String>,
+    pub removed_operations: Vec<String>,
+    /// operations whose definition or messages changed
+    pub changed_operations: Vec<String>,
+}
+
+impl WsdlDiff {
+    pub fn is_empty(&self) -> bool {
+        *self == WsdlDiff::default()
+    }
+}
+
+/// Counts of the definitions of a parsed document, as returned by `Wsdl::stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WsdlStats {
+    /// simple types, restricted decimals included
+    pub simple_types: usize,
+    pub complex_types: usize,
+    pub enumerations: usize,
+    pub aliases: usize,
+    pub messages: usize,
+    /// operations with an input and an output message
+    pub request_response_operations: usize,
+    /// operations with an input message only
+    pub one_way_operations: usize,
+    /// operations with an output message only
+    pub notification_operations: usize,
+    /// services declaring at least one port
+    pub services: usize,
+    pub bindings: usize,
+}
+
+/// What to do when several schemas define a type with the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateStrategy {
+    /// fail with `WsdlError::DuplicateType`
+    Error,
+    KeepFirst,
+    KeepLast,
+}
+
+// written by hand: `#[default]` on enum variants needs Rust 1.62
+#[allow(clippy::derivable_impls)]
+impl Default for DuplicateStrategy {
+    fn default() -> Self {
+        DuplicateStrategy::Error
+    }
 }
 
 #[derive(Debug, Clone)]
+pub struct ParseOptions {
+    pub duplicate_strategy: DuplicateStrategy,
+    /// record the `WsdlWarning`s of the document in `Wsdl::warnings`
+    pub collect_warnings: bool,
+    /// maximum size in bytes of the document and of each imported schema
+    pub max_input_size: Option<usize>,
+    /// time allowed to fetch each import over HTTP, 30 seconds by default, or
+    /// `None` to wait indefinitely
+    pub import_timeout: Option<Duration>,
+    /// key `Wsdl::operations` by lowercased name, so that binding operations
+    /// match the port type operations whatever their case
+    pub normalize_operation_case: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            duplicate_strategy: DuplicateStrategy::default(),
+            collect_warnings: false,
+            max_input_size: None,
+            import_timeout: Some(Duration::from_secs(30)),
+            normalize_operation_case: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Key of the operation named `name` in `Wsdl::operations`. The name
+    /// itself stays in `Operation::name`.
+    pub fn operation_key(&self, name: &str) -> String {
+        operation_key(self.normalize_operation_case, name)
+    }
+}
+
+/// Key of the operation named `name`, lowercased when `normalize_case` is set.
+fn operation_key(normalize_case: bool, name: &str) -> String {
+    match normalize_case {
+        true => name.to_lowercase(),
+        false => name.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum SimpleType {
     Boolean,
     String,
     Float,
     Int,
     DateTime,
+    /// `xs:NOTATION`, the name of a notation declared in the schema. It is
+    /// only checked to be a string.
+    Notation,
+    /// `xs:hexBinary`, binary data written as pairs of hex digits
+    HexBinary,
+    Decimal,
+    /// `xs:QName`, a name with an optional prefix
+    QName,
+    /// `xs:NCName`, a name without colons
+    NCName,
+    /// `xs:Name`
+    Name,
     Complex(String),
 }
 
-#[derive(Debug, Clone)]
+impl SimpleType {
+    /// Name of the XML schema primitive, or `None` for references to other types.
+    pub fn primitive_name(&self) -> Option<&'static str> {
+        match self {
+            SimpleType::Boolean => Some("boolean"),
+            SimpleType::String => Some("string"),
+            SimpleType::Float => Some("float"),
+            SimpleType::Int => Some("int"),
+            SimpleType::DateTime => Some("dateTime"),
+            SimpleType::Notation => Some("NOTATION"),
+            SimpleType::HexBinary => Some("hexBinary"),
+            SimpleType::Decimal => Some("decimal"),
+            SimpleType::QName => Some("QName"),
+            SimpleType::NCName => Some("NCName"),
+            SimpleType::Name => Some("Name"),
+            SimpleType::Complex(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Occurence {
     Unbounded,
     Num(u32),
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct TypeAttribute {
     pub nillable: bool,
     pub min_occurs: Option<Occurence>,
     pub max_occurs: Option<Occurence>,
+    /// text of the `xsd:annotation/xsd:documentation` of the element
+    pub documentation: Option<String>,
+    /// value of the `fixed` attribute, the only value the field can have.
+    /// A fixed field is never nil, even when declared `nillable="true"`.
+    pub fixed: Option<String>,
+    /// `form` of the element, overriding the `elementFormDefault` of its schema
+    pub form: Option<ElementForm>,
+    /// kind of the nested compositor holding the field, `None` for fields
+    /// directly in the compositor of their type
+    pub compositor: Option<ContentModel>,
+    /// `maxOccurs` of the compositors holding the field when they repeat,
+    /// already applied to `max_occurs`
+    pub group_max_occurs: Option<Occurence>,
+    /// whether the field is a child element or an attribute
+    pub kind: FieldKind,
+    /// children of the `xsd:annotation/xsd:appinfo` elements of the field,
+    /// holding hints for applications
+    pub appinfo: Vec<Element>,
 }
 
-#[derive(Debug, Clone)]
+impl TypeAttribute {
+    /// Whether the field can appear more than once.
+    pub fn is_repeated(&self) -> bool {
+        match self.max_occurs {
+            Some(Occurence::Unbounded) => true,
+            Some(Occurence::Num(n)) => n > 1,
+            None => false,
+        }
+    }
+}
+
+/// How a field of a complex type is written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldKind {
+    Element,
+    /// `xs:attribute` of the type
+    Attribute,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for FieldKind {
+    fn default() -> Self {
+        FieldKind::Element
+    }
+}
+
+/// Whether a local element is qualified by the target namespace.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ElementForm {
+    Qualified,
+    Unqualified,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for ElementForm {
+    fn default() -> Self {
+        ElementForm::Unqualified
+    }
+}
+
+impl ElementForm {
+    fn parse(form: Option<&String>) -> Option<ElementForm> {
+        match form.map(|f| f.as_str()) {
+            Some("qualified") => Some(ElementForm::Qualified),
+            Some("unqualified") => Some(ElementForm::Unqualified),
+            _ => None,
+        }
+    }
+}
+
+/// compositor holding the fields of a complex type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContentModel {
+    Sequence,
+    All,
+    /// exactly one of the fields is present
+    Choice,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for ContentModel {
+    fn default() -> Self {
+        ContentModel::Sequence
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct ComplexType {
     pub fields: HashMap<String, (TypeAttribute, SimpleType)>,
+    pub content_model: ContentModel,
+    /// `elementFormDefault` of the schema declaring the type
+    pub element_form_default: ElementForm,
+    /// `test` of the XSD 1.1 `xs:assert` elements of the type, in document order
+    pub assertions: Vec<String>,
+    /// head of the `substitutionGroup` of the element, without its prefix.
+    /// The named type of an element standing in a substitution group, as in
+    /// `<element name="A" type="tns:AType" substitutionGroup="tns:Head"/>`,
+    /// is the member, while the element is an alias of it.
+    pub substitution_group: Option<String>,
+    /// declared with `abstract="true"`, only standing for the elements of its
+    /// substitution group
+    pub abstract_: bool,
+}
+
+impl ComplexType {
+    /// Form of the field named `field`, defaulting to the one of the schema.
+    pub fn field_form(&self, field: &str) -> ElementForm {
+        self.fields
+            .get(field)
+            .and_then(|(attributes, _)| attributes.form)
+            .unwrap_or(self.element_form_default)
+    }
+
+    /// Fields tagged as recommended by an `<appinfo><recommended/></appinfo>`
+    /// annotation, in alphabetical order.
+    pub fn recommended_fields(&self) -> Vec<&str> {
+        let mut fields = self
+            .fields
+            .iter()
+            .filter(|(_, (attributes, _))| {
+                attributes.appinfo.iter().any(|e| e.name == "recommended")
+            })
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        fields.sort_unstable();
+        fields
+    }
+
+    /// Whether `field` is written as an attribute rather than a child element.
+    pub fn is_attribute(&self, field: &str) -> bool {
+        matches!(self.fields.get(field), Some((attributes, _)) if attributes.kind == FieldKind::Attribute)
+    }
+
+    /// Finds which alternative of a choice is present in `element`.
+    ///
+    /// Returns the field name along with its element, or `None` if this type
+    /// is not a choice or if none of its alternatives is present.
+    pub fn choice(&self, element: &Element) -> Option<(String, Element)> {
+        if self.content_model != ContentModel::Choice {
+            return None;
+        }
+
+        element
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .find(|c| self.fields.contains_key(&c.name))
+            .map(|c| (c.name.clone(), c.clone()))
+    }
 }
 
+/// A field of a message, with the fields of its type expanded.
 #[derive(Debug, Clone)]
+pub struct SchemaNode {
+    pub name: String,
+    pub simple_type: SimpleType,
+    pub nillable: bool,
+    /// the field can appear more than once, and maps to a `Vec`
+    pub repeated: bool,
+    /// fields of a complex type, sorted by name
+    pub children: Vec<SchemaNode>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Type {
     Simple(SimpleType),
     Complex(ComplexType),
+    /// global element referring to another type, as in `<element name="A" type="tns:B"/>`
+    Alias(String),
+    /// restriction to a list of values, as in `<enumeration value="Active"/>`
+    Enumeration(Vec<String>),
+    /// `xs:decimal` restricted by `xs:totalDigits` or `xs:fractionDigits`
+    Decimal {
+        total_digits: Option<u32>,
+        fraction_digits: Option<u32>,
+    },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Message {
     pub part_name: String,
     pub part_element: String,
+    /// prefix of the part element, as written in the document
+    pub part_prefix: Option<String>,
+    /// every part of the message, in document order, the first one included
+    pub parts: Vec<Part>,
 }
 
-#[derive(Debug)]
+/// A named part of a message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Part {
+    pub name: String,
+    /// element, or type for RPC style messages, of the part
+    pub element: String,
+}
+
+/// Types of an operation, named as in the generated code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationSignature {
+    pub name: String,
+    pub input_type: Option<String>,
+    pub output_type: Option<String>,
+    pub fault_types: Vec<String>,
+}
+
+/// Namespace qualified XML name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QName {
+    pub prefix: Option<String>,
+    pub namespace: Option<String>,
+    pub local_name: String,
+}
+
+impl std::fmt::Display for QName {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.prefix {
+            Some(prefix) => write!(f, "{}:{}", prefix, self.local_name),
+            None => f.write_str(&self.local_name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Operation {
     pub name: String,
     pub input: Option<String>,
     pub output: Option<String>,
-    pub faults: Option<Vec<String>>,
+    pub faults: Option<Vec<FaultRef>>,
+    /// `name` of the input, distinguishing overloaded operations
+    pub input_name: Option<String>,
+    /// `name` of the output, distinguishing overloaded operations
+    pub output_name: Option<String>,
+    /// `soapAction` of the operation in its binding
+    pub soap_action: Option<String>,
+    /// text of the `wsdl:documentation` of the operation
+    pub documentation: Option<String>,
+    /// names of the parts in the `parameterOrder` attribute, the order of
+    /// the arguments of RPC style operations
+    pub parameter_order: Option<Vec<String>>,
+    /// extensibility elements of the operation, from namespaces other than
+    /// WSDL, by name in the `{namespace}local` notation. The first one of a
+    /// name is kept.
+    pub extensions: HashMap<String, Element>,
+}
+
+impl Operation {
+    /// Value of the `SOAPAction` HTTP header of the operation.
+    ///
+    /// SOAP 1.1 requires the value to be quoted, as in `"urn:foo"`, and
+    /// `quoted` should be `true` unless the service rejects quotes. Operations
+    /// without an action get an empty value.
+    pub fn soap_action_header(&self, quoted: bool) -> String {
+        let action = self.soap_action.as_deref().unwrap_or_default();
+        if quoted {
+            format!("\"{}\"", action)
+        } else {
+            action.to_string()
+        }
+    }
+
+    /// Whether the operation declares a fault with the message `fault_name`.
+    pub fn has_fault(&self, fault_name: &str) -> bool {
+        let fault_name = split_namespace(fault_name);
+        self.faults
+            .iter()
+            .flatten()
+            .any(|f| f.message == fault_name)
+    }
+
+    /// `name` of the declared faults, in document order, as opposed to the
+    /// names of their messages.
+    pub fn fault_names(&self) -> Vec<&str> {
+        self.faults
+            .iter()
+            .flatten()
+            .map(|f| f.name.as_str())
+            .collect()
+    }
+}
+
+/// service port, as in `<port name="..." binding="..."><soap:address location="..."/></port>`
+#[derive(Debug, Clone)]
+pub struct Endpoint {
+    pub name: String,
+    pub binding: String,
+    pub url: String,
+    /// name of the service declaring the port
+    pub service: String,
+    /// SOAP version of the address, `None` for non SOAP ports such as `http:address`
+    pub soap_version: Option<SoapVersion>,
+}
+
+/// SOAP version of a port, from the namespace of its address element.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SoapVersion {
+    Soap11,
+    Soap12,
+}
+
+/// fault declared by an operation, as in `<fault name="invalid_input" message="tns:InvalidInputFault"/>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaultRef {
+    pub name: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub name: String,
+    pub port_type: String,
+    /// transport URI declared by the `soap:binding` element, for SOAP bindings
+    pub transport: Option<String>,
+    /// SOAP details of the bound operations, by operation name, holding the
+    /// first declaration of overloaded operations
+    pub operations: HashMap<String, BindingOperation>,
+    /// every declaration of the operations bound more than once, by name, in
+    /// document order
+    pub overloads: HashMap<String, Vec<BindingOperation>>,
+}
+
+impl Binding {
+    /// Binding of the operation named `name` whose input and output are named
+    /// `input_name` and `output_name`, telling overloaded operations apart.
+    ///
+    /// Operations that are not overloaded are found whatever the names are.
+    pub fn overload(
+        &self,
+        name: &str,
+        input_name: Option<&str>,
+        output_name: Option<&str>,
+    ) -> Option<&BindingOperation> {
+        match self.overloads.get(name) {
+            Some(overloads) => overloads.iter().find(|o| {
+                o.input_name.as_deref() == input_name && o.output_name.as_deref() == output_name
+            }),
+            None => self.operations.get(name),
+        }
+    }
+}
+
+/// SOAP details of an operation in a binding.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BindingOperation {
+    /// `soapAction` attribute of the `soap:operation` element
+    pub soap_action: Option<String>,
+    pub input: Option<MessageBinding>,
+    pub output: Option<MessageBinding>,
+    /// `name` of the input, distinguishing overloaded operations
+    pub input_name: Option<String>,
+    /// `name` of the output, distinguishing overloaded operations
+    pub output_name: Option<String>,
+}
+
+/// Where the parts of a message go in the SOAP envelope.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MessageBinding {
+    /// parts listed by the `parts` attribute of `soap:body`, `None` when every
+    /// part not bound elsewhere goes in the body
+    pub body_parts: Option<Vec<String>>,
+    /// `(message, part)` pairs bound to `soap:header` elements
+    pub header_parts: Vec<(String, String)>,
+    /// `use` of the `soap:body` element
+    pub encoding: BodyEncoding,
+}
+
+/// How the parts of a message are written in the SOAP body.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BodyEncoding {
+    /// `use="literal"`, the parts follow their schema
+    Literal,
+    /// `use="encoded"`, with the first URI of the `encodingStyle` attribute
+    Encoded(String),
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for BodyEncoding {
+    fn default() -> Self {
+        BodyEncoding::Literal
+    }
+}
+
+impl MessageBinding {
+    /// Whether the part named `part` of `message` goes in the SOAP body.
+    pub fn in_body(&self, message: &str, part: &str) -> bool {
+        match &self.body_parts {
+            Some(parts) => parts.iter().any(|p| p == part),
+            None => !self.in_header(message, part),
+        }
+    }
+
+    /// Whether the part named `part` of `message` goes in a SOAP header.
+    pub fn in_header(&self, message: &str, part: &str) -> bool {
+        self.header_parts
+            .iter()
+            .any(|(m, p)| m == message && p == part)
+    }
 }
 
 //FIXME: splitting the namespace is the naive way, we should keep the namespace
@@ -85,239 +702,5329 @@ fn split_namespace(s: &str) -> &str {
     }
 }
 
-pub fn parse(bytes: &[u8]) -> Result<Wsdl, WsdlError> {
-    let mut types = HashMap::new();
-    let mut messages = HashMap::new();
-    let mut operations = HashMap::new();
-
-    let elements = Element::parse(bytes)?;
-    trace!("elements: {:#?}", elements);
-    let target_namespace = elements
-        .attributes
-        .get("targetNamespace")
-        .ok_or(WsdlError::AttributeNotFound("targetNamespace"))?
-        .to_string();
-
-    let types_el = elements
-        .get_child("types")
-        .ok_or(WsdlError::ElementNotFound("types"))?
+/// Finds the first child element named `name`, ignoring namespace prefixes.
+///
+/// xmltree keeps the prefix apart from the name, but names where it was kept,
+/// as in `wsdl:types`, are matched as well.
+fn get_child_local<'a>(element: &'a Element, name: &str) -> Option<&'a Element> {
+    element
         .children
         .iter()
         .filter_map(|c| c.as_element())
-        .next()
-        .ok_or(WsdlError::Empty)?;
+        .find(|c| split_namespace(&c.name) == name)
+}
 
-    for elem in types_el.children.iter().filter_map(|c| c.as_element()) {
-        trace!("type: {:#?}", elem);
-        let name = elem
-            .attributes
-            .get("name")
-            .ok_or(WsdlError::AttributeNotFound("name"))?;
+fn split_prefix(s: &str) -> Option<&str> {
+    s.find(':').map(|index| &s[..index])
+}
 
-        // sometimes we have <element name="TypeName"><complexType>...</complexType></element>,
-        // sometimes we have <complexType name="TypeName">...</complexType>
-        //let current_child = elem.children.get(0).ok_or(WsdlError::Empty)?
-        //    .as_element().ok_or(WsdlError::NotAnElement)?;
+/// Namespaces of the built-in types of XML schema, along with the ones of the
+/// drafts still used by some documents.
+const XSD_NAMESPACES: &[&str] = &[
+    XSD_NS,
+    "http://www.w3.org/2000/10/XMLSchema",
+    "http://www.w3.org/1999/XMLSchema",
+];
 
-        let child = if elem.name == "complexType" {
-            elem
-        } else {
-            elem.children
-                .get(0)
-                .ok_or(WsdlError::Empty)?
-                .as_element()
-                .ok_or(WsdlError::NotAnElement)?
-        };
+/// Type named `type_name` in `element`, a built-in type only when its
+/// prefix, or the default namespace, resolves to the XML schema namespace.
+fn simple_type(element: &Element, type_name: &str) -> SimpleType {
+    match resolve_qname(element, type_name) {
+        (Some(namespace), local) if XSD_NAMESPACES.contains(&namespace) => builtin_type(local),
+        (_, local) => SimpleType::Complex(local.to_string()),
+    }
+}
 
-        if child.name == "complexType" {
-            let mut fields = HashMap::new();
-            for field in child
-                .children
-                .get(0)
-                .ok_or(WsdlError::Empty)?
-                .as_element()
-                .ok_or(WsdlError::NotAnElement)?
-                .children
-                .iter()
-                .filter_map(|c| c.as_element())
-            {
-                let field_name = field
-                    .attributes
-                    .get("name")
-                    .ok_or(WsdlError::AttributeNotFound("name"))?;
-                let field_type = field
-                    .attributes
-                    .get("type")
-                    .ok_or(WsdlError::AttributeNotFound("type"))?;
-                let nillable = match field.attributes.get("nillable").map(|s| s.as_str()) {
-                    Some("true") => true,
-                    Some("false") => false,
-                    _ => false,
-                };
+/// Built-in type of XML schema named `type_name`, ignoring its prefix, or
+/// else the complex type of this name.
+pub(crate) fn builtin_type(type_name: &str) -> SimpleType {
+    match split_namespace(type_name) {
+        "boolean" => SimpleType::Boolean,
+        "string" => SimpleType::String,
+        "int" => SimpleType::Int,
+        "float" => SimpleType::Float,
+        "dateTime" => SimpleType::DateTime,
+        "NOTATION" => SimpleType::Notation,
+        "hexBinary" => SimpleType::HexBinary,
+        "decimal" => SimpleType::Decimal,
+        "QName" => SimpleType::QName,
+        "NCName" => SimpleType::NCName,
+        "Name" => SimpleType::Name,
+        s => SimpleType::Complex(s.to_string()),
+    }
+}
 
-                let min_occurs = match field.attributes.get("minOccurs").map(|s| s.as_str()) {
-                    None => None,
-                    Some("unbounded") => Some(Occurence::Unbounded),
-                    Some(n) => Some(Occurence::Num(
-                        n.parse().expect("occurence should be a number"),
-                    )),
-                };
-                let max_occurs = match field.attributes.get("maxOccurs").map(|s| s.as_str()) {
-                    None => None,
-                    Some("unbounded") => Some(Occurence::Unbounded),
-                    Some(n) => Some(Occurence::Num(
-                        n.parse().expect("occurence should be a number"),
-                    )),
-                };
-                trace!("field {:?} -> {:?}", field_name, field_type);
-                let type_attributes = TypeAttribute {
-                    nillable,
-                    min_occurs,
-                    max_occurs,
-                };
+impl Wsdl {
+    pub fn operation_count(&self) -> usize {
+        self.operations.len()
+    }
 
-                let simple_type = match split_namespace(field_type.as_str()) {
-                    "boolean" => SimpleType::Boolean,
-                    "string" => SimpleType::String,
-                    "int" => SimpleType::Int,
-                    "float" => SimpleType::Float,
-                    "dateTime" => SimpleType::DateTime,
-                    s => SimpleType::Complex(s.to_string()),
-                };
-                fields.insert(field_name.to_string(), (type_attributes, simple_type));
-            }
+    pub fn type_count(&self) -> usize {
+        self.types.len()
+    }
 
-            types.insert(name.to_string(), Type::Complex(ComplexType { fields }));
-        } else {
-            trace!("child {:#?}", child);
-            unimplemented!("not a complex type");
-        }
+    pub fn message_count(&self) -> usize {
+        self.messages.len()
     }
 
-    for message in elements
-        .children
-        .iter()
-        .filter_map(|c| c.as_element())
-        .filter(|c| c.name == "message")
-    {
-        trace!("message: {:#?}", message);
-        let name = message
-            .attributes
-            .get("name")
-            .ok_or(WsdlError::AttributeNotFound("name"))?;
-        let c = message
-            .children
-            .iter()
-            .filter_map(|c| c.as_element())
-            .next()
-            .unwrap();
-        //FIXME: namespace
-        let part_name = c
-            .attributes
-            .get("name")
-            .ok_or(WsdlError::AttributeNotFound("name"))?
-            .to_string();
-        let part_element = split_namespace(
-            c.attributes
-                .get("element")
-                .ok_or(WsdlError::AttributeNotFound("element"))?,
-        )
-        .to_string();
+    pub fn has_operation(&self, name: &str) -> bool {
+        self.operation(name).is_some()
+    }
 
-        messages.insert(
-            name.to_string(),
-            Message {
-                part_name,
-                part_element,
-            },
-        );
+    /// Key of the operation named `name` in `operations` and `overloads`,
+    /// lowercased when the document was parsed with `normalize_operation_case`.
+    pub fn operation_key(&self, name: &str) -> String {
+        operation_key(self.normalize_operation_case, name)
     }
 
-    let port_type_el = elements
-        .get_child("portType")
-        .ok_or(WsdlError::ElementNotFound("portType"))?;
+    /// Operation named `name`, looked up by its key.
+    pub fn operation(&self, name: &str) -> Option<&Operation> {
+        self.operations.get(&self.operation_key(name))
+    }
 
-    for operation in port_type_el.children.iter().filter_map(|c| c.as_element()) {
-        let operation_name = operation
-            .attributes
-            .get("name")
-            .ok_or(WsdlError::AttributeNotFound("name"))?;
+    /// Documentation of the operation named `name`, looked up by its key.
+    pub fn operation_documentation(&self, name: &str) -> Option<&str> {
+        self.documentation
+            .get(&format!("operation:{}", self.operation_key(name)))
+            .map(|d| d.as_str())
+    }
 
-        let mut input = None;
-        let mut output = None;
-        let mut faults = None;
-        for child in operation
-            .children
-            .iter()
-            .filter_map(|c| c.as_element())
-            .filter(|c| c.attributes.get("message").is_some())
-        {
-            let message = split_namespace(
-                child
-                    .attributes
-                    .get("message")
-                    .ok_or(WsdlError::AttributeNotFound("message"))?,
-            );
-            // FIXME: not testing for unicity
-            match child.name.as_str() {
-                "input" => input = Some(message.to_string()),
-                "output" => output = Some(message.to_string()),
-                "fault" => {
-                    if faults.is_none() {
-                        faults = Some(Vec::new());
-                    }
-                    if let Some(v) = faults.as_mut() {
-                        v.push(message.to_string());
-                    }
+    pub fn has_type(&self, name: &str) -> bool {
+        self.types.contains_key(name)
+    }
+
+    pub fn has_message(&self, name: &str) -> bool {
+        self.messages.contains_key(name)
+    }
+
+    pub fn stats(&self) -> WsdlStats {
+        let mut stats = WsdlStats {
+            messages: self.messages.len(),
+            bindings: self.bindings.len(),
+            services: self
+                .endpoints
+                .iter()
+                .map(|e| &e.service)
+                .collect::<HashSet<_>>()
+                .len(),
+            ..Default::default()
+        };
+        for t in self.types.values() {
+            match t {
+                Type::Simple(_) | Type::Decimal { .. } => stats.simple_types += 1,
+                Type::Complex(_) => stats.complex_types += 1,
+                Type::Enumeration(_) => stats.enumerations += 1,
+                Type::Alias(_) => stats.aliases += 1,
+            }
+        }
+        for operation in self.operations.values() {
+            match (&operation.input, &operation.output) {
+                (Some(_), Some(_)) => stats.request_response_operations += 1,
+                (Some(_), None) => stats.one_way_operations += 1,
+                (None, Some(_)) => stats.notification_operations += 1,
+                (None, None) => {}
+            }
+        }
+        stats
+    }
+
+    /// Follows a chain of `Type::Alias` from `name` down to the aliased type.
+    ///
+    /// Returns `None` if a type in the chain is missing or if the chain loops.
+    pub fn resolve_alias<'a>(&'a self, name: &str) -> Option<&'a Type> {
+        let mut current = self.types.get(name)?;
+        for _ in 0..self.types.len() {
+            match current {
+                Type::Alias(target) => current = self.types.get(target)?,
+                t => return Some(t),
+            }
+        }
+        None
+    }
+
+    /// Follows the `base` of the simple type `type_name`, through named
+    /// restrictions and aliases, down to the XSD primitive it restricts.
+    /// Restrictions of `xs:decimal` by digits resolve to `SimpleType::Decimal`.
+    ///
+    /// Returns `None` for complex types and enumerations, if a type in the
+    /// chain is missing or if the chain loops.
+    pub fn effective_primitive(&self, type_name: &str) -> Option<SimpleType> {
+        let mut current = self.types.get(type_name)?;
+        for _ in 0..self.types.len() {
+            match current {
+                Type::Alias(target) | Type::Simple(SimpleType::Complex(target)) => {
+                    current = self.types.get(target)?
                 }
-                _ => return Err(WsdlError::ElementNotFound("operation member")),
+                Type::Simple(primitive) => return Some(primitive.clone()),
+                Type::Decimal { .. } => return Some(SimpleType::Decimal),
+                Type::Complex(_) | Type::Enumeration(_) => return None,
             }
         }
+        None
+    }
 
-        operations.insert(
-            operation_name.to_string(),
-            Operation {
-                name: operation_name.to_string(),
-                input,
-                output,
-                faults,
-            },
-        );
+    /// Adds an operation, failing if one with the same name exists.
+    pub fn add_operation(&mut self, op: Operation) -> Result<(), WsdlError> {
+        let key = self.operation_key(&op.name);
+        if self.operations.contains_key(&key) {
+            return Err(WsdlError::DuplicateOperation(op.name));
+        }
+        self.operations.insert(key, op);
+        Ok(())
     }
 
-    //FIXME: ignoring bindings for now
-    //FIXME: ignoring service for now
-    let service_name = elements
-        .get_child("service")
-        .ok_or(WsdlError::ElementNotFound("service"))?
-        .attributes
-        .get("name")
-        .ok_or(WsdlError::AttributeNotFound("name"))?;
+    /// Adds a message, failing if one with the same name exists.
+    pub fn add_message(&mut self, name: &str, message: Message) -> Result<(), WsdlError> {
+        if self.messages.contains_key(name) {
+            return Err(WsdlError::DuplicateMessage(name.to_string()));
+        }
+        self.messages.insert(name.to_string(), message);
+        Ok(())
+    }
 
-    debug!("service name: {}", service_name);
-    debug!("parsed types: {:#?}", types);
-    debug!("parsed messages: {:#?}", messages);
-    debug!("parsed operations: {:#?}", operations);
+    /// Adds a type, failing if one with the same name exists.
+    pub fn add_type(&mut self, name: &str, t: Type) -> Result<(), WsdlError> {
+        if self.types.contains_key(name) {
+            return Err(WsdlError::DuplicateType(name.to_string()));
+        }
+        self.types.insert(name.to_string(), t);
+        Ok(())
+    }
 
-    Ok(Wsdl {
-        name: service_name.to_string(),
-        target_namespace,
-        types,
-        messages,
-        operations,
-    })
-}
+    /// Namespace of the type named `name`: the `targetNamespace` of its schema,
+    /// or the one of the document if the schema has none.
+    pub fn type_namespace(&self, name: &str) -> &str {
+        self.type_namespaces
+            .get(name)
+            .map_or(&self.target_namespace, |ns| ns)
+    }
 
-#[cfg(test)]
+    /// Location of the imported or included schema declaring the type named
+    /// `name`, or `None` if the document declares it.
+    pub fn source_location(&self, name: &str) -> Option<&str> {
+        self.type_locations.get(name).map(|l| l.as_str())
+    }
+
+    /// URL of the first endpoint, if any.
+    pub fn primary_endpoint_url(&self) -> Option<&str> {
+        self.endpoints.first().map(|e| e.url.as_str())
+    }
+
+    /// URL of the SOAP port a client should use by default.
+    ///
+    /// Looks at the ports of the first service, preferring the first SOAP 1.2
+    /// port over the first SOAP 1.1 one.
+    pub fn default_endpoint(&self) -> Option<&str> {
+        let service = &self.endpoints.first()?.service;
+        let ports = self.endpoints.iter().filter(|e| &e.service == service);
+        let mut soap11 = None;
+        for port in ports {
+            match port.soap_version {
+                Some(SoapVersion::Soap12) => return Some(&port.url),
+                Some(SoapVersion::Soap11) if soap11.is_none() => soap11 = Some(port.url.as_str()),
+                _ => {}
+            }
+        }
+        soap11
+    }
+
+    /// URL of the first endpoint, failing if the service declares none.
+    pub fn require_endpoint_url(&self) -> Result<&str, WsdlError> {
+        self.primary_endpoint_url()
+            .ok_or(WsdlError::ElementNotFound("address"))
+    }
+
+    /// Binding of the port type declaring `operation`.
+    ///
+    /// When several bindings expose the operation, as for SOAP 1.1 and 1.2
+    /// bindings of the same port type, the first one by name is returned.
+    pub fn binding_for_operation(&self, operation: &str) -> Option<&Binding> {
+        let mut bindings = self
+            .bindings
+            .values()
+            .filter(|b| {
+                b.operations
+                    .keys()
+                    .any(|name| self.operation_key(name) == self.operation_key(operation))
+            })
+            .collect::<Vec<_>>();
+        bindings.sort_by(|a, b| a.name.cmp(&b.name));
+        bindings.first().copied()
+    }
+
+    /// Distinct transport URIs declared by the SOAP bindings, in alphabetical order.
+    pub fn transports(&self) -> Vec<String> {
+        let mut transports: Vec<String> = self
+            .bindings
+            .values()
+            .filter_map(|b| b.transport.clone())
+            .collect();
+        transports.sort();
+        transports.dedup();
+        transports
+    }
+
+    /// Names of the primitives referenced by the types, as in `primitive_name`.
+    pub fn used_primitives(&self) -> HashSet<&'static str> {
+        self.types
+            .values()
+            .flat_map(|t| match t {
+                Type::Simple(s) => vec![s],
+                Type::Complex(c) => c.fields.values().map(|(_, s)| s).collect(),
+                Type::Decimal { .. } => vec![&SimpleType::Decimal],
+                Type::Alias(_) | Type::Enumeration(_) => vec![],
+            })
+            .filter_map(SimpleType::primitive_name)
+            .collect()
+    }
+
+    /// Compares with a `previous` version of the document.
+    ///
+    /// All the lists of the result are sorted.
+    pub fn diff(&self, previous: &Wsdl) -> WsdlDiff {
+        let (added_types, removed_types, changed_types) =
+            diff_maps(&self.types, &previous.types, |a, b| a == b);
+        let messages = |wsdl: &Wsdl, operation: &Operation| {
+            [&operation.input, &operation.output]
+                .iter()
+                .map(|m| m.as_ref().and_then(|m| wsdl.messages.get(m)).cloned())
+                .collect::<Vec<_>>()
+        };
+        let (added_operations, removed_operations, changed_operations) =
+            diff_maps(&self.operations, &previous.operations, |a, b| {
+                a == b && messages(self, a) == messages(previous, b)
+            });
+
+        WsdlDiff {
+            added_types,
+            removed_types,
+            changed_types,
+            added_operations,
+            removed_operations,
+            changed_operations,
+        }
+    }
+
+    /// Whether both documents declare the same types, messages and operations,
+    /// regardless of the order of the declarations and of namespace prefixes.
+    ///
+    /// Extension and `appinfo` elements are compared by namespace and local
+    /// name.
+    pub fn semantically_equal(&self, other: &Wsdl) -> bool {
+        let types = |wsdl: &Wsdl| -> HashMap<String, Type> {
+            wsdl.types
+                .iter()
+                .map(|(name, t)| {
+                    let mut t = t.clone();
+                    if let Type::Complex(c) = &mut t {
+                        for (attributes, _) in c.fields.values_mut() {
+                            attributes.appinfo.iter_mut().for_each(strip_prefixes);
+                        }
+                    }
+                    (name.clone(), t)
+                })
+                .collect()
+        };
+        let messages = |wsdl: &Wsdl| -> HashMap<String, Vec<Part>> {
+            wsdl.messages
+                .iter()
+                .map(|(name, message)| (name.clone(), message.parts.clone()))
+                .collect()
+        };
+        let operations = |wsdl: &Wsdl| -> HashMap<String, Operation> {
+            wsdl.operations
+                .iter()
+                .map(|(name, operation)| {
+                    let mut operation = operation.clone();
+                    if let Some(faults) = operation.faults.as_mut() {
+                        faults.sort_by(|a, b| a.name.cmp(&b.name));
+                    }
+                    operation.extensions.values_mut().for_each(strip_prefixes);
+                    (name.clone(), operation)
+                })
+                .collect()
+        };
+
+        types(self) == types(other)
+            && messages(self) == messages(other)
+            && operations(self) == operations(other)
+    }
+
+    /// Renders the service, its operations and its types as a Markdown page.
+    ///
+    /// Operations and types are listed in alphabetical order, and messages
+    /// are shown by the element of their first part.
+    pub fn render_as_markdown(&self) -> String {
+        use std::fmt::Write;
+
+        let element = |message: &str| {
+            self.messages
+                .get(message)
+                .map_or(message, |m| m.part_element.as_str())
+                .to_string()
+        };
+        let mut out = String::new();
+
+        writeln!(out, "# {}\n", self.name).unwrap();
+        writeln!(out, "- Namespace: `{}`", self.target_namespace).unwrap();
+        writeln!(
+            out,
+            "- Endpoint: {}",
+            self.primary_endpoint_url().unwrap_or("none")
+        )
+        .unwrap();
+
+        writeln!(out, "\n## Operations\n").unwrap();
+        writeln!(out, "| Operation | Input | Output | Faults |").unwrap();
+        writeln!(out, "| --- | --- | --- | --- |").unwrap();
+        let mut operations = self.operations.iter().collect::<Vec<_>>();
+        operations.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, operation) in operations {
+            let faults = operation
+                .faults
+                .iter()
+                .flatten()
+                .map(|f| element(&f.message))
+                .collect::<Vec<_>>();
+            writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                name,
+                operation.input.as_deref().map(element).unwrap_or_default(),
+                operation.output.as_deref().map(element).unwrap_or_default(),
+                faults.join(", ")
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "\n## Types").unwrap();
+        let mut types = self.types.iter().collect::<Vec<_>>();
+        types.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, t) in types {
+            writeln!(out, "\n### {}\n", name).unwrap();
+            match t {
+                Type::Complex(c) => {
+                    writeln!(out, "| Field | Type | Description |").unwrap();
+                    writeln!(out, "| --- | --- | --- |").unwrap();
+                    let mut fields = c.fields.iter().collect::<Vec<_>>();
+                    fields.sort_by(|a, b| a.0.cmp(b.0));
+                    for (field, (attributes, field_type)) in fields {
+                        let type_name = match field_type {
+                            SimpleType::Complex(t) => t.as_str(),
+                            simple => simple.primitive_name().unwrap_or_default(),
+                        };
+                        let occurence = match (attributes.is_repeated(), attributes.nillable) {
+                            (true, _) => "[]",
+                            (false, true) => "?",
+                            (false, false) => "",
+                        };
+                        let description = attributes
+                            .documentation
+                            .as_deref()
+                            .unwrap_or_default()
+                            .split_whitespace()
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                            .replace('|', "\\|");
+                        writeln!(
+                            out,
+                            "| {} | `{}{}` | {} |",
+                            field, type_name, occurence, description
+                        )
+                        .unwrap();
+                    }
+                }
+                Type::Simple(simple) => writeln!(
+                    out,
+                    "Restriction of `{}`.",
+                    simple.primitive_name().unwrap_or_default()
+                )
+                .unwrap(),
+                Type::Alias(target) => writeln!(out, "Element of type `{}`.", target).unwrap(),
+                Type::Enumeration(values) => {
+                    let values = values
+                        .iter()
+                        .map(|v| format!("`{}`", v))
+                        .collect::<Vec<_>>();
+                    writeln!(out, "One of {}.", values.join(", ")).unwrap()
+                }
+                Type::Decimal {
+                    total_digits,
+                    fraction_digits,
+                } => {
+                    let digits = |d: &Option<u32>| d.map_or("any".to_string(), |d| d.to_string());
+                    writeln!(
+                        out,
+                        "Decimal with {} total digits and {} fraction digits.",
+                        digits(total_digits),
+                        digits(fraction_digits)
+                    )
+                    .unwrap()
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Describes the operations and types of the service in a TypeScript-like
+    /// notation, for consumers of the model written in other languages.
+    ///
+    /// Operations take the element of their input message and return the
+    /// element of their output message, or `void`, followed by the elements
+    /// of their faults. Types are declared as by `typescript::to_typescript`,
+    /// but with the names of the document and of the XML schema primitives.
+    /// Operations and types are sorted by name.
+    pub fn to_interface_description(&self) -> String {
+        use std::fmt::Write;
+
+        let element = |message: &str| {
+            self.messages
+                .get(message)
+                .map_or(message, |m| m.part_element.as_str())
+                .to_string()
+        };
+        let mut out = String::new();
+
+        writeln!(out, "service {} {{", self.name).unwrap();
+        let mut operations = self.operations.values().collect::<Vec<_>>();
+        operations.sort_by(|a, b| a.name.cmp(&b.name));
+        for operation in operations {
+            let input = operation
+                .input
+                .as_deref()
+                .map(|input| format!("input: {}", element(input)))
+                .unwrap_or_default();
+            let output = operation
+                .output
+                .as_deref()
+                .map_or("void".to_string(), element);
+            let faults = operation
+                .faults
+                .iter()
+                .flatten()
+                .map(|f| element(&f.message))
+                .collect::<Vec<_>>();
+            write!(out, "  {}({}): {}", operation.name, input, output).unwrap();
+            if !faults.is_empty() {
+                write!(out, " throws {}", faults.join(", ")).unwrap();
+            }
+            writeln!(out, ";").unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+
+        crate::typescript::write_types(&mut out, self, Notation::Description);
+
+        out
+    }
+
+    /// Renders a Rust struct for the complex type `type_name`, as a starting
+    /// point for hand-written code.
+    ///
+    /// Fields are listed in alphabetical order. Nillable fields are wrapped in
+    /// `Option` and repeated ones in `Vec`, as in generated code. Returns
+    /// `None` if there is no complex type with this name.
+    pub fn render_rust_struct(&self, type_name: &str, mapper: &dyn NameMapper) -> Option<String> {
+        use std::fmt::Write;
+
+        let c = match self.types.get(type_name)? {
+            Type::Complex(c) => c,
+            _ => return None,
+        };
+        let mut fields = c.fields.iter().collect::<Vec<_>>();
+        fields.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut out = String::new();
+        writeln!(out, "#[derive(Clone, Debug, Default)]").unwrap();
+        writeln!(out, "pub struct {} {{", mapper.type_name(type_name)).unwrap();
+        for (name, (attributes, field_type)) in fields {
+            let rust_type = match field_type {
+                SimpleType::Complex(t) => mapper.type_name(t),
+                builtin => crate::gen::builtin_type_path(builtin)
+                    .unwrap_or_default()
+                    .to_string(),
+            };
+            let rust_type = match attributes.is_repeated() {
+                true => format!("Vec<{}>", rust_type),
+                false => rust_type,
+            };
+            let rust_type = match attributes.nillable {
+                true => format!("Option<{}>", rust_type),
+                false => rust_type,
+            };
+            for line in attributes.documentation.iter().flat_map(|d| d.lines()) {
+                writeln!(out, "    /// {}", line.trim()).unwrap();
+            }
+            writeln!(out, "    pub {}: {},", mapper.field_name(name), rust_type).unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+
+        Some(out)
+    }
+
+    /// Tree of the input element of `operation`.
+    ///
+    /// Recursive types are expanded once: a type already being expanded
+    /// higher in the tree appears as a node without children.
+    pub fn input_schema(&self, operation: &str) -> Option<SchemaNode> {
+        let input = self.operation(operation)?.input.as_ref()?;
+        let element = &self.messages.get(input)?.part_element;
+
+        Some(self.schema_node(
+            element,
+            &TypeAttribute::default(),
+            &SimpleType::Complex(element.clone()),
+            &mut Vec::new(),
+        ))
+    }
+
+    /// Namespace declarations needed by the envelopes of `operation`, as
+    /// `(prefix, uri)` pairs sorted by prefix.
+    ///
+    /// Holds the namespaces of the elements of its input and output messages
+    /// and of the types they reference. Namespaces keep the prefix they have on
+    /// the root element of the document, or get `ns1`, `ns2`... if they have
+    /// none. Empty when the operation is unknown.
+    pub fn envelope_namespaces(&self, operation: &str) -> Vec<(String, String)> {
+        let operation = match self.operation(operation) {
+            Some(operation) => operation,
+            None => return Vec::new(),
+        };
+        let mut pending = [&operation.input, &operation.output]
+            .iter()
+            .filter_map(|m| m.as_ref().and_then(|m| self.messages.get(m)))
+            .flat_map(|m| m.parts.iter().map(|p| p.element.as_str()))
+            .collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+        let mut uris = Vec::new();
+        while let Some(name) = pending.pop() {
+            // primitives are not declared by the schemas of the document
+            let t = match self.types.get(name) {
+                Some(t) if seen.insert(name) => t,
+                _ => continue,
+            };
+            uris.push(self.type_namespace(name));
+            match t {
+                Type::Alias(target) => pending.push(target),
+                Type::Complex(c) => {
+                    pending.extend(c.fields.values().filter_map(|(_, t)| match t {
+                        SimpleType::Complex(t) => Some(t.as_str()),
+                        _ => None,
+                    }))
+                }
+                _ => {}
+            }
+        }
+        uris.sort_unstable();
+        uris.dedup();
+
+        let mut generated = (1..)
+            .map(|i| format!("ns{}", i))
+            .filter(|prefix| !self.namespaces.contains_key(prefix));
+        let mut declarations = uris
+            .into_iter()
+            .map(|uri| {
+                let prefix = self
+                    .namespaces
+                    .iter()
+                    .filter(|(prefix, u)| !prefix.is_empty() && *u == uri)
+                    .map(|(prefix, _)| prefix.clone())
+                    .min()
+                    .unwrap_or_else(|| generated.next().unwrap());
+                (prefix, uri.to_string())
+            })
+            .collect::<Vec<_>>();
+        declarations.sort();
+        declarations
+    }
+
+    /// Parts of the output message of `operation`, in document order.
+    ///
+    /// Empty when the operation or its output message is unknown.
+    pub fn output_parts(&self, operation: &str) -> Vec<&Part> {
+        self.operation(operation)
+            .and_then(|o| o.output.as_ref())
+            .and_then(|output| self.messages.get(output))
+            .map(|m| m.parts.iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// Operation named `name` whose input is named `input_name`, telling
+    /// overloaded operations apart.
+    ///
+    /// Operations that are not overloaded are found whatever `input_name` is.
+    pub fn overload(&self, name: &str, input_name: Option<&str>) -> Option<&Operation> {
+        match self.overloads.get(&self.operation_key(name)) {
+            Some(overloads) => overloads
+                .iter()
+                .find(|o| o.input_name.as_deref() == input_name),
+            None => self.operation(name),
+        }
+    }
+
+    /// Signature of `operation`, with its message types named as in the generated code.
+    pub fn operation_signature(&self, operation: &str) -> Option<OperationSignature> {
+        let op = self.operation(operation)?;
+
+        Some(OperationSignature {
+            name: operation.to_snake(),
+            input_type: op.input.as_ref().map(|m| m.to_camel()),
+            output_type: op.output.as_ref().map(|m| m.to_camel()),
+            fault_types: op
+                .faults
+                .iter()
+                .flatten()
+                .map(|f| f.message.to_camel())
+                .collect(),
+        })
+    }
+
+    /// Qualified name of the root element of the body of `operation`'s request.
+    pub fn input_qname(&self, operation: &str) -> Option<QName> {
+        let input = self.operation(operation)?.input.as_ref()?;
+        let message = self.messages.get(input)?;
+
+        Some(QName {
+            prefix: message.part_prefix.clone(),
+            namespace: self
+                .namespaces
+                .get(message.part_prefix.as_deref().unwrap_or(""))
+                .cloned(),
+            local_name: message.part_element.clone(),
+        })
+    }
+
+    /// Finds the operation targeted by a request whose body holds the element
+    /// `element_local_name`, for a service dispatching requests.
+    ///
+    /// The element is first matched against the input elements of the
+    /// operations. Otherwise it is taken as the wrapper element of a
+    /// document/literal wrapped (or RPC) request, named after its operation.
+    pub fn operation_for_input_element(&self, element_local_name: &str) -> Option<&Operation> {
+        let local_name = split_namespace(element_local_name);
+        // sorted, so the same operation is found if several match
+        let mut operations = self.operations.values().collect::<Vec<_>>();
+        operations.sort_by(|a, b| a.name.cmp(&b.name));
+        operations
+            .iter()
+            .find(|operation| {
+                let message = operation
+                    .input
+                    .as_ref()
+                    .and_then(|input| self.messages.get(input));
+                matches!(message, Some(m) if split_namespace(&m.part_element) == local_name)
+            })
+            .or_else(|| {
+                operations.iter().find(|operation| {
+                    self.operation_key(&operation.name) == self.operation_key(local_name)
+                })
+            })
+            .copied()
+    }
+
+    fn schema_node(
+        &self,
+        name: &str,
+        attributes: &TypeAttribute,
+        simple_type: &SimpleType,
+        expanding: &mut Vec<String>,
+    ) -> SchemaNode {
+        let mut children = Vec::new();
+        if let SimpleType::Complex(type_name) = simple_type {
+            if let (Some(Type::Complex(c)), false) =
+                (self.resolve_alias(type_name), expanding.contains(type_name))
+            {
+                expanding.push(type_name.clone());
+                children = c
+                    .fields
+                    .iter()
+                    .map(|(field, (a, t))| self.schema_node(field, a, t, expanding))
+                    .collect();
+                children.sort_by(|a: &SchemaNode, b| a.name.cmp(&b.name));
+                expanding.pop();
+            }
+        }
+
+        SchemaNode {
+            name: name.to_string(),
+            simple_type: simple_type.clone(),
+            nillable: attributes.nillable,
+            repeated: attributes.is_repeated(),
+            children,
+        }
+    }
+}
+
+/// Sorted names of the entries added, removed and changed between `previous` and `current`.
+fn diff_maps<V, F: Fn(&V, &V) -> bool>(
+    current: &HashMap<String, V>,
+    previous: &HashMap<String, V>,
+    same: F,
+) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let sorted = |mut names: Vec<String>| {
+        names.sort();
+        names
+    };
+    let added = current
+        .keys()
+        .filter(|k| !previous.contains_key(*k))
+        .cloned()
+        .collect();
+    let removed = previous
+        .keys()
+        .filter(|k| !current.contains_key(*k))
+        .cloned()
+        .collect();
+    let changed = current
+        .iter()
+        .filter(|(k, v)| previous.get(*k).map(|p| !same(v, p)).unwrap_or(false))
+        .map(|(k, _)| k.clone())
+        .collect();
+
+    (sorted(added), sorted(removed), sorted(changed))
+}
+
+/// Maps the names of a schema to Rust identifiers.
+pub trait NameMapper {
+    fn type_name(&self, name: &str) -> String;
+    fn field_name(&self, name: &str) -> String;
+}
+
+/// Maps names like the generated code: `CamelCase` types and `snake_case` fields.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultNameMapper;
+
+impl NameMapper for DefaultNameMapper {
+    fn type_name(&self, name: &str) -> String {
+        name.to_camel()
+    }
+
+    fn field_name(&self, name: &str) -> String {
+        name.to_snake()
+    }
+}
+
+/// Loads the content of an imported document from its location.
+type Resolver<'a> = &'a dyn Fn(&str) -> Option<Vec<u8>>;
+
+pub fn parse(bytes: &[u8]) -> Result<Wsdl, WsdlError> {
+    parse_with_options(bytes, &ParseOptions::default())
+}
+
+/// Parses a document already decoded as a string.
+pub fn parse_str(s: &str) -> Result<Wsdl, WsdlError> {
+    parse(s.as_bytes())
+}
+
+pub fn parse_with_options(bytes: &[u8], options: &ParseOptions) -> Result<Wsdl, WsdlError> {
+    parse_inner(bytes, None, None, &mut Recovery(None), options)
+}
+
+/// Parses a WSDL document, skipping the parts that cannot be parsed.
+///
+/// Schema types, messages, operations, bindings and ports that fail to parse
+/// are left out, and the errors are returned along with the rest of the
+/// document. If the document itself cannot be read, the `Wsdl` is empty.
+pub fn parse_with_recovery(bytes: &[u8]) -> (Wsdl, Vec<WsdlError>) {
+    let mut recovery = Recovery(Some(Vec::new()));
+    let wsdl = parse_inner(bytes, None, None, &mut recovery, &ParseOptions::default());
+    let mut errors = recovery.0.unwrap_or_default();
+
+    match wsdl {
+        Ok(wsdl) => (wsdl, errors),
+        Err(error) => {
+            errors.push(error);
+            (Wsdl::default(), errors)
+        }
+    }
+}
+
+/// Parses a WSDL document, loading the schemas it imports with `resolver`.
+///
+/// `resolver` is called with the `schemaLocation` of every `xs:import` and
+/// returns the content of the imported schema, or `None` if it is unknown.
+/// It also loads the documents of `wsdl:import`, with their `location`, whose
+/// types, messages and port types are merged into the parsed document. The
+/// port type and messages referenced by qualified name are looked up in the
+/// document of their namespace.
+pub fn parse_with_resolver<R>(bytes: &[u8], resolver: R) -> Result<Wsdl, WsdlError>
+where
+    R: Fn(&str) -> Option<Vec<u8>>,
+{
+    parse_inner(
+        bytes,
+        Some(&resolver),
+        None,
+        &mut Recovery(None),
+        &ParseOptions::default(),
+    )
+}
+
+/// Definitions of imported schemas, read once for several documents.
+///
+/// Each schema is cached without the schemas it imports, which are cached on
+/// their own, so that documents importing them several times still define
+/// their types once.
+///
+/// Schemas are cached by location, so documents sharing a cache must agree
+/// on what their locations point to, as the files of a single directory do.
+/// The cache can be shared by documents parsed concurrently.
+#[derive(Debug, Default)]
+pub struct SchemaCache {
+    schemas: RwLock<HashMap<String, Arc<SchemaDefinitions>>>,
+}
+
+impl SchemaCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of cached schemas.
+    pub fn len(&self) -> usize {
+        self.schemas.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, location: &str) -> Option<Arc<SchemaDefinitions>> {
+        self.schemas.read().unwrap().get(location).cloned()
+    }
+
+    fn insert(&self, location: &str, schema: Arc<SchemaDefinitions>) {
+        self.schemas
+            .write()
+            .unwrap()
+            .insert(location.to_string(), schema);
+    }
+}
+
+/// Parses a WSDL document as `parse_with_resolver`, reusing the imported
+/// schemas already parsed in `cache` and adding the others to it.
+pub fn parse_with_cache<R>(
+    bytes: &[u8],
+    resolver: R,
+    cache: &SchemaCache,
+) -> Result<Wsdl, WsdlError>
+where
+    R: Fn(&str) -> Option<Vec<u8>>,
+{
+    parse_inner(
+        bytes,
+        Some(&resolver),
+        Some(cache),
+        &mut Recovery(None),
+        &ParseOptions::default(),
+    )
+}
+
+/// Parses a WSDL document, loading the schemas it imports with an
+/// asynchronous `resolver`, as for schemas stored in a remote backend.
+///
+/// Imported schemas are fetched one at a time, including the ones they
+/// import, before the document is parsed as with `parse_with_resolver`,
+/// following `options`.
+#[cfg(feature = "async")]
+pub async fn parse_with_async_resolver<R, F>(
+    bytes: &[u8],
+    resolver: R,
+    options: &ParseOptions,
+) -> Result<Wsdl, WsdlError>
+where
+    R: Fn(&str) -> F,
+    F: std::future::Future<Output = Option<Vec<u8>>>,
+{
+    let documents = fetch_imports(
+        bytes,
+        options,
+        |_, location| location.to_string(),
+        |location| {
+            let document = resolver(location);
+            async move { Ok(document.await) }
+        },
+    )
+    .await?;
+
+    parse_inner(
+        bytes,
+        Some(&|location: &str| documents.get(location).cloned()),
+        None,
+        &mut Recovery(None),
+        options,
+    )
+}
+
+/// Parses a WSDL document, fetching the schemas and documents it imports over
+/// HTTP. Their locations are resolved relative to the URL of the document
+/// importing them, `base_url` for the ones imported by `bytes`.
+///
+/// Each import is given `ParseOptions::import_timeout` to be fetched, after
+/// which parsing stops with `WsdlError::ImportTimeout`. Imports that cannot
+/// be fetched otherwise are reported as missing.
+#[cfg(feature = "async")]
+pub async fn parse_with_http_imports(
+    bytes: &[u8],
+    base_url: &str,
+    options: &ParseOptions,
+) -> Result<Wsdl, WsdlError> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(timeout) = options.import_timeout {
+        builder = builder.timeout(timeout);
+    }
+    let client = builder.build().map_err(WsdlError::HttpClient)?;
+    let base_url = reqwest::Url::parse(base_url).ok();
+    // locations relative to the document importing them, if it has a URL
+    let join = |importing: Option<&str>, location: &str| {
+        let base = match importing {
+            Some(importing) => reqwest::Url::parse(importing).ok(),
+            None => base_url.clone(),
+        };
+        base.and_then(|base| base.join(location).ok())
+            .map_or_else(|| location.to_string(), |url| url.to_string())
+    };
+
+    let documents = fetch_imports(bytes, options, join, |location| {
+        let url = reqwest::Url::parse(location).ok();
+        let client = &client;
+        async move {
+            let url = match url {
+                Some(url) => url,
+                None => return Ok(None),
+            };
+            let timeout = |e: &reqwest::Error| match e.is_timeout() {
+                true => Err(WsdlError::ImportTimeout(url.to_string())),
+                false => Ok(None),
+            };
+            let response = match client.get(url.clone()).send().await {
+                Ok(response) if response.status().is_success() => response,
+                Ok(_) => return Ok(None),
+                Err(e) => return timeout(&e),
+            };
+            match response.bytes().await {
+                Ok(content) => Ok(Some(content.to_vec())),
+                Err(e) => timeout(&e),
+            }
+        }
+    })
+    .await?;
+
+    parse_inner(
+        bytes,
+        Some(&|location: &str| documents.get(&join(None, location)).cloned()),
+        None,
+        &mut Recovery(None),
+        options,
+    )
+}
+
+/// Loads with `fetch` the schemas and documents imported by a WSDL document,
+/// including the ones they import, by location.
+///
+/// Locations are given to `fetch` as joined by `join` with the location of
+/// the document importing them, `None` for the imports of `bytes`. The
+/// imports of the loaded documents are rewritten to their joined location.
+#[cfg(feature = "async")]
+async fn fetch_imports<J, R, F>(
+    bytes: &[u8],
+    options: &ParseOptions,
+    join: J,
+    fetch: R,
+) -> Result<HashMap<String, Vec<u8>>, WsdlError>
+where
+    J: Fn(Option<&str>, &str) -> String,
+    R: Fn(&str) -> F,
+    F: std::future::Future<Output = Result<Option<Vec<u8>>, WsdlError>>,
+{
+    let mut documents = HashMap::new();
+    let mut pending = join_locations(&mut parse_element(bytes, options)?, &|location| {
+        join(None, location)
+    })
+    .into_iter()
+    .map(|(_, joined)| joined)
+    .collect::<Vec<_>>();
+    while let Some(location) = pending.pop() {
+        if documents.contains_key(&location) {
+            continue;
+        }
+        if let Some(mut content) = fetch(&location).await? {
+            if let Ok(mut schema) = parse_element(&content, options) {
+                let imports =
+                    join_locations(&mut schema, &|imported| join(Some(&location), imported));
+                // documents that cannot be written back keep their imports as written
+                let mut joined = Vec::new();
+                if imports.iter().any(|(written, joined)| written != joined)
+                    && schema.write(&mut joined).is_ok()
+                {
+                    content = joined;
+                }
+                pending.extend(imports.into_iter().map(|(_, joined)| joined));
+            }
+            documents.insert(location, content);
+        }
+    }
+    Ok(documents)
+}
+
+/// Joins with `join` the `schemaLocation` of every schema import and include
+/// below `element`, and the `location` of every `wsdl:import`, returning the
+/// locations as written and joined.
+#[cfg(feature = "async")]
+fn join_locations(element: &mut Element, join: &dyn Fn(&str) -> String) -> Vec<(String, String)> {
+    let mut locations = Vec::new();
+    for child in element
+        .children
+        .iter_mut()
+        .filter_map(|c| c.as_mut_element())
+    {
+        let attribute = ["schemaLocation", "location"]
+            .iter()
+            .find(|a| child.attributes.contains_key(**a));
+        match (child.name.as_str(), attribute) {
+            ("import", Some(attribute)) | ("include", Some(attribute)) => {
+                let written = child.attributes[*attribute].clone();
+                let joined = join(&written);
+                child
+                    .attributes
+                    .insert(attribute.to_string(), joined.clone());
+                locations.push((written, joined))
+            }
+            _ => locations.extend(join_locations(child, join)),
+        }
+    }
+    locations
+}
+
+/// Removes the namespace prefixes and declarations of `element` and its
+/// descendants, leaving their namespace and local name.
+fn strip_prefixes(element: &mut Element) {
+    element.prefix = None;
+    element.namespaces = None;
+    for child in element.children.iter_mut() {
+        if let Some(child) = child.as_mut_element() {
+            strip_prefixes(child);
+        }
+    }
+}
+
+/// Parses every `.wsdl` file of a directory, in alphabetical order.
+///
+/// Schema imports are resolved relative to the directory, and the schemas
+/// imported by several files are parsed once. A file that fails to parse
+/// does not stop the others: the parsed documents are returned by path, along
+/// with the path and error of every failure, including the directory itself
+/// if it cannot be read.
+#[allow(clippy::type_complexity)]
+pub fn parse_directory(dir: &Path) -> (Vec<(PathBuf, Wsdl)>, Vec<(PathBuf, WsdlError)>) {
+    let mut paths = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && matches!(p.extension(), Some(e) if e == "wsdl"))
+            .collect::<Vec<_>>(),
+        Err(error) => return (Vec::new(), vec![(dir.to_path_buf(), error.into())]),
+    };
+    paths.sort();
+
+    let cache = SchemaCache::new();
+    let mut wsdls = Vec::new();
+    let mut failures = Vec::new();
+    for path in paths {
+        let parsed = std::fs::read(&path)
+            .map_err(WsdlError::from)
+            .and_then(|bytes| {
+                let resolver = |location: &str| std::fs::read(dir.join(location)).ok();
+                parse_with_cache(&bytes, resolver, &cache)
+            });
+        match parsed {
+            Ok(wsdl) => wsdls.push((path, wsdl)),
+            Err(error) => failures.push((path, error)),
+        }
+    }
+    (wsdls, failures)
+}
+
+/// Parses a WSDL distributed as a ZIP archive along with its imported schemas.
+///
+/// The root WSDL is the `.wsdl` file closest to the root of the archive (the
+/// first one in alphabetical order if there are several at the same depth).
+/// Imports are resolved relative to its directory.
+#[cfg(feature = "zip")]
+pub fn parse_from_zip<R: std::io::Read + std::io::Seek>(reader: R) -> Result<Wsdl, WsdlError> {
+    use std::io::Read;
+
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut files = HashMap::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)?;
+        files.insert(file.name().to_string(), content);
+    }
+
+    let root = files
+        .keys()
+        .filter(|name| name.ends_with(".wsdl"))
+        .min_by_key(|name| (name.matches('/').count(), name.as_str()))
+        .ok_or(zip::result::ZipError::FileNotFound)?;
+    let base = match root.rfind('/') {
+        Some(index) => &root[..=index],
+        None => "",
+    };
+
+    parse_with_resolver(&files[root], |location| {
+        files.get(&resolve_relative(base, location)).cloned()
+    })
+}
+
+/// Joins a relative `location` to the `base` directory, removing `.` and `..` segments.
+#[cfg(feature = "zip")]
+fn resolve_relative(base: &str, location: &str) -> String {
+    let mut segments: Vec<&str> = base.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in location.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            s => segments.push(s),
+        }
+    }
+    segments.join("/")
+}
+
+/// Types parsed from the schemas of a document and from the schemas they import.
+#[derive(Default)]
+struct Schemas {
+    types: HashMap<String, Type>,
+    namespaces: HashMap<String, String>,
+    overridden: Vec<(String, Type)>,
+    warnings: Vec<WsdlWarning>,
+    /// locations of the imported schemas
+    imported: HashSet<String>,
+    version: XsdVersion,
+    /// `xsd:annotation/xsd:documentation` of the global definitions, by name
+    documentation: HashMap<String, String>,
+    /// location of the imported schema being parsed
+    location: Option<String>,
+    locations: HashMap<String, String>,
+    /// elements of a named type standing in substitution groups, as
+    /// `(type, head, abstract)`, applied to their type once every schema is read
+    typed_substitutions: Vec<(String, Option<String>, bool)>,
+    /// anonymous simple types of fields, named after their field, by name,
+    /// with the type holding the field
+    inline: HashMap<String, String>,
+}
+
+impl Schemas {
+    fn insert(
+        &mut self,
+        name: String,
+        t: Type,
+        namespace: Option<&String>,
+        strategy: DuplicateStrategy,
+    ) -> Result<(), WsdlError> {
+        // declared types take their name from the anonymous types named after a field
+        if let Some(owner) = self.inline.remove(&name) {
+            let renamed = self.free_name(&name);
+            self.rename_inline(&owner, &name, &renamed);
+        }
+        let existing = match self.types.get(&name) {
+            Some(existing) => existing,
+            None => {
+                self.define(name, t, namespace);
+                return Ok(());
+            }
+        };
+        // `<element name="T" type="tns:T"/>` only declares the element of the type T
+        if matches!(&t, Type::Alias(target) if *target == name) {
+            return Ok(());
+        }
+        if matches!(existing, Type::Alias(target) if *target == name) {
+            self.define(name, t, namespace);
+            return Ok(());
+        }
+
+        match strategy {
+            DuplicateStrategy::Error => Err(WsdlError::DuplicateType(name)),
+            DuplicateStrategy::KeepFirst => {
+                self.overridden.push((name, t));
+                Ok(())
+            }
+            DuplicateStrategy::KeepLast => {
+                let first = self.define(name.clone(), t, namespace).unwrap();
+                self.overridden.push((name, first));
+                Ok(())
+            }
+        }
+    }
+
+    /// Defines `t`, the anonymous simple type of a field of `owner` named
+    /// `name` after it, with a numeric suffix if a type already has this name.
+    fn insert_inline(&mut self, owner: &str, name: String, t: Type, namespace: Option<&String>) {
+        let free = match self.types.contains_key(&name) {
+            true => self.free_name(&name),
+            false => name.clone(),
+        };
+        self.define(free.clone(), t, namespace);
+        self.inline.insert(free.clone(), owner.to_string());
+        if free != name {
+            self.rename_field_types(owner, &name, &free);
+        }
+    }
+
+    /// First of `name2`, `name3`... not naming a type.
+    fn free_name(&self, name: &str) -> String {
+        (2..)
+            .map(|i| format!("{}{}", name, i))
+            .find(|n| !self.types.contains_key(n))
+            .unwrap()
+    }
+
+    /// Renames the anonymous type `from` of a field of `owner` to `to`.
+    fn rename_inline(&mut self, owner: &str, from: &str, to: &str) {
+        if let Some(t) = self.types.remove(from) {
+            let namespace = self.namespaces.remove(from);
+            self.define(to.to_string(), t, namespace.as_ref());
+            match self.locations.remove(from) {
+                Some(location) => self.locations.insert(to.to_string(), location),
+                None => self.locations.remove(to),
+            };
+            self.inline.insert(to.to_string(), owner.to_string());
+            self.rename_field_types(owner, from, to);
+        }
+    }
+
+    /// Makes the fields of `owner` of type `from` of type `to`.
+    fn rename_field_types(&mut self, owner: &str, from: &str, to: &str) {
+        if let Some(Type::Complex(c)) = self.types.get_mut(owner) {
+            for (_, field_type) in c.fields.values_mut() {
+                if matches!(field_type, SimpleType::Complex(name) if name == from) {
+                    *field_type = SimpleType::Complex(to.to_string());
+                }
+            }
+        }
+    }
+
+    /// Sets the definition of a type along with its namespace, returning the previous definition.
+    fn define(&mut self, name: String, t: Type, namespace: Option<&String>) -> Option<Type> {
+        match namespace {
+            Some(namespace) => self.namespaces.insert(name.clone(), namespace.clone()),
+            None => self.namespaces.remove(&name),
+        };
+        match &self.location {
+            Some(location) => self.locations.insert(name.clone(), location.clone()),
+            None => self.locations.remove(&name),
+        };
+        self.types.insert(name, t)
+    }
+
+    /// Marks the named types of the elements of substitution groups, as in
+    /// `<element name="A" type="tns:AType" substitutionGroup="tns:Head"/>`,
+    /// and resolves the heads declared through such elements to their type.
+    fn resolve_substitutions(&mut self) {
+        let resolve = |types: &HashMap<String, Type>, name: &str| {
+            let mut name = name.to_string();
+            for _ in 0..types.len() {
+                match types.get(&name) {
+                    Some(Type::Alias(target)) => name = target.clone(),
+                    _ => break,
+                }
+            }
+            name
+        };
+
+        for (type_name, head, abstract_) in std::mem::take(&mut self.typed_substitutions) {
+            let type_name = resolve(&self.types, &type_name);
+            if let Some(Type::Complex(c)) = self.types.get_mut(&type_name) {
+                c.abstract_ |= abstract_;
+                if head.is_some() {
+                    c.substitution_group = head;
+                }
+            }
+        }
+        let heads = self
+            .types
+            .iter()
+            .filter_map(|(name, t)| match t {
+                Type::Complex(ComplexType {
+                    substitution_group: Some(head),
+                    ..
+                }) => Some((name.clone(), resolve(&self.types, head))),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for (name, head) in heads {
+            if let Some(Type::Complex(c)) = self.types.get_mut(&name) {
+                c.substitution_group = Some(head);
+            }
+        }
+    }
+}
+
+/// Errors met while parsing with recovery.
+///
+/// Without recovery, the first error aborts the parse.
+struct Recovery(Option<Vec<WsdlError>>);
+
+impl Recovery {
+    /// Records the error of `result` when recovering, returning `Ok(None)`.
+    fn check<T>(&mut self, result: Result<T, WsdlError>) -> Result<Option<T>, WsdlError> {
+        match (result, self.0.as_mut()) {
+            (Ok(value), _) => Ok(Some(value)),
+            (Err(error), Some(errors)) => {
+                errors.push(error);
+                Ok(None)
+            }
+            (Err(error), None) => Err(error),
+        }
+    }
+}
+
+/// Parses an XML document, rejecting the ones larger than
+/// `ParseOptions::max_input_size`.
+///
+/// Documents declaring entities in their DOCTYPE are always rejected, as
+/// nested entities can expand to gigabytes of text (the "billion laughs"
+/// attack). WSDL documents and schemas do not need them.
+fn parse_element(bytes: &[u8], options: &ParseOptions) -> Result<Element, WsdlError> {
+    if let Some(max) = options.max_input_size {
+        if bytes.len() > max {
+            return Err(WsdlError::SecurityLimitExceeded(format!(
+                "document of {} bytes, larger than {} bytes",
+                bytes.len(),
+                max
+            )));
+        }
+    }
+    let find = |needle: &[u8], from: usize| {
+        bytes[from..]
+            .windows(needle.len())
+            .position(|w| w == needle)
+            .map(|i| from + i)
+    };
+    if let Some(doctype) = find(b"<!DOCTYPE", 0) {
+        if find(b"<!ENTITY", doctype).is_some() {
+            return Err(WsdlError::SecurityLimitExceeded(
+                "entity declarations in DOCTYPE".to_string(),
+            ));
+        }
+    }
+
+    Ok(Element::parse(bytes)?)
+}
+
+fn parse_inner(
+    bytes: &[u8],
+    resolver: Option<Resolver>,
+    cache: Option<&SchemaCache>,
+    recovery: &mut Recovery,
+    options: &ParseOptions,
+) -> Result<Wsdl, WsdlError> {
+    let mut schemas = Schemas::default();
+    let mut messages = HashMap::new();
+    let mut operations = HashMap::new();
+    let mut overloads: HashMap<String, Vec<Operation>> = HashMap::new();
+
+    let elements = parse_element(bytes, options)?;
+    trace!("elements: {:#?}", elements);
+    // WSDL 2.0 uses a different namespace and structure (interface, endpoint...)
+    match elements.namespace.as_deref() {
+        Some(WSDL11_NS) | None => {}
+        Some(namespace) => return Err(WsdlError::UnsupportedWsdlVersion(namespace.to_string())),
+    }
+    let target_namespace = recovery
+        .check(
+            elements
+                .attributes
+                .get("targetNamespace")
+                .ok_or(WsdlError::AttributeNotFound("targetNamespace")),
+        )?
+        .cloned()
+        .unwrap_or_default();
+
+    // definitions of the document followed by the ones of the documents it
+    // imports with `wsdl:import`
+    let mut imports = Vec::new();
+    import_definitions(
+        &elements,
+        resolver,
+        &mut HashSet::new(),
+        &mut imports,
+        recovery,
+        options,
+    )?;
+    let documents = std::iter::once(&elements)
+        .chain(imports.iter())
+        .collect::<Vec<_>>();
+    let children = |name: &'static str| documents.iter().flat_map(move |d| children_named(d, name));
+
+    // some documents put their schemas directly under definitions, without types
+    let schema_elements = documents
+        .iter()
+        .flat_map(|d| match get_child_local(d, "types") {
+            Some(types) => types
+                .children
+                .iter()
+                .filter_map(|c| c.as_element())
+                .collect(),
+            None => d
+                .children
+                .iter()
+                .filter_map(|c| c.as_element())
+                .filter(|c| c.name == "schema")
+                .collect::<Vec<_>>(),
+        })
+        .collect::<Vec<_>>();
+    let has_types = documents
+        .iter()
+        .any(|d| get_child_local(d, "types").is_some());
+    if schema_elements.is_empty() && !has_types {
+        recovery.check::<()>(Err(WsdlError::ElementNotFound("types")))?;
+    }
+
+    for schema in schema_elements {
+        parse_schema(schema, &mut schemas, resolver, cache, recovery, options)?;
+    }
+    schemas.resolve_substitutions();
+    let types = schemas.types;
+
+    // messages by target namespace of their document and name, in document
+    // order, as the port type references them by qualified name
+    let mut qualified_messages = Vec::new();
+    for document in &documents {
+        let namespace = document.attributes.get("targetNamespace").cloned();
+        for message in children_named(document, "message") {
+            trace!("message: {:#?}", message);
+            if let Some((name, message)) = recovery.check(parse_message(message))? {
+                qualified_messages.push((namespace.clone(), name, message));
+            }
+        }
+    }
+
+    // the port type of the document, or else the imported one referenced by
+    // the first binding, looked up in the document of its namespace
+    let port_type_el = get_child_local(&elements, "portType").or_else(|| {
+        let referenced =
+            children("binding").find_map(|b| b.attributes.get("type").map(|t| resolve_qname(b, t)));
+        imports
+            .iter()
+            .filter(|d| match referenced {
+                Some((Some(namespace), _)) => {
+                    d.attributes.get("targetNamespace").map(|t| t.as_str()) == Some(namespace)
+                }
+                _ => true,
+            })
+            .flat_map(|d| children_named(d, "portType"))
+            .find(|p| match referenced {
+                Some((_, name)) => p.attributes.get("name").map(|n| n.as_str()) == Some(name),
+                None => true,
+            })
+    });
+    let port_type_el =
+        recovery.check(port_type_el.ok_or(WsdlError::ElementNotFound("portType")))?;
+
+    // the messages referenced by the port type are the ones of their
+    // namespace, the others keep the first declaration of their name
+    let referenced_messages = port_type_el
+        .iter()
+        .flat_map(|p| children_named(p, "operation"))
+        .flat_map(|o| o.children.iter().filter_map(|c| c.as_element()))
+        .filter_map(|c| c.attributes.get("message").map(|m| resolve_qname(c, m)));
+    for (namespace, name) in referenced_messages {
+        let declared = || qualified_messages.iter().filter(|(_, n, _)| n == name);
+        let message = declared()
+            .find(|(ns, _, _)| ns.as_deref() == namespace)
+            // unprefixed references are often meant in the target namespace
+            .or_else(|| declared().next());
+        if let Some((ns, _, message)) = message {
+            match messages.get(name) {
+                Some((first, _)) if first != ns => {
+                    recovery.check::<()>(Err(WsdlError::DuplicateMessage(name.to_string())))?;
+                }
+                _ => {
+                    messages.insert(name.to_string(), (ns.clone(), message.clone()));
+                }
+            }
+        }
+    }
+    for (namespace, name, message) in qualified_messages {
+        messages.entry(name).or_insert((namespace, message));
+    }
+    let messages = messages
+        .into_iter()
+        .map(|(name, (_, message))| (name, message))
+        .collect::<HashMap<_, _>>();
+
+    for operation in port_type_el
+        .iter()
+        .flat_map(|p| p.children.iter().filter_map(|c| c.as_element()))
+    {
+        if let Some(operation) = recovery.check(parse_operation(operation))? {
+            let key = options.operation_key(&operation.name);
+            match operations.get(&key) {
+                Some(first) => overloads
+                    .entry(key)
+                    .or_insert_with(|| vec![Operation::clone(first)])
+                    .push(operation),
+                None => {
+                    operations.insert(key, operation);
+                }
+            }
+        }
+    }
+
+    let mut bindings = HashMap::new();
+    for binding in children("binding") {
+        if let Some(binding) = recovery.check(parse_binding(binding))? {
+            bindings.insert(binding.name.clone(), binding);
+        }
+    }
+    // operations take the action of the first binding, by name, declaring one.
+    // Overloads take the one of the declaration with the same input and output names
+    let mut sorted_bindings = bindings.values().collect::<Vec<&Binding>>();
+    sorted_bindings.sort_by(|a, b| a.name.cmp(&b.name));
+    for binding in sorted_bindings {
+        for name in binding.operations.keys() {
+            let key = options.operation_key(name);
+            let declarations = operations
+                .get_mut(&key)
+                .into_iter()
+                .chain(overloads.get_mut(&key).into_iter().flatten());
+            for operation in declarations {
+                let action = binding
+                    .overload(
+                        name,
+                        operation.input_name.as_deref(),
+                        operation.output_name.as_deref(),
+                    )
+                    .and_then(|bound| bound.soap_action.as_ref());
+                if let Some(action) = action {
+                    operation.soap_action.get_or_insert_with(|| action.clone());
+                }
+            }
+        }
+    }
+
+    let service_name = recovery
+        .check(
+            children("service")
+                .next()
+                .ok_or(WsdlError::ElementNotFound("service"))
+                .and_then(|s| {
+                    s.attributes
+                        .get("name")
+                        .ok_or(WsdlError::AttributeNotFound("name"))
+                }),
+        )?
+        .cloned()
+        .unwrap_or_default();
+
+    let mut endpoints = Vec::new();
+    for service in children("service") {
+        let service_name = service.attributes.get("name").map_or("", |n| n.as_str());
+        for port in service
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .filter(|c| c.name == "port")
+        {
+            if let Some(Some(endpoint)) = recovery.check(parse_endpoint(service_name, port))? {
+                endpoints.push(endpoint);
+            }
+        }
+    }
+
+    // xml-rs also lists the reserved xml and xmlns prefixes
+    let namespaces = elements
+        .namespaces
+        .iter()
+        .flat_map(|ns| ns.0.iter())
+        .filter(|(prefix, uri)| !uri.is_empty() && *prefix != "xml" && *prefix != "xmlns")
+        .map(|(prefix, uri)| (prefix.clone(), uri.clone()))
+        .collect();
+
+    let mut documentation = HashMap::new();
+    for (kind, element) in children("service")
+        .map(|e| ("service", e))
+        .chain(children("message").map(|e| ("message", e)))
+    {
+        if let (Some(name), Some(text)) =
+            (element.attributes.get("name"), documentation_text(element))
+        {
+            documentation.insert(format!("{}:{}", kind, name), text);
+        }
+    }
+    for (key, operation) in &operations {
+        if let Some(text) = &operation.documentation {
+            documentation.insert(format!("operation:{}", key), text.clone());
+        }
+    }
+    for (name, text) in schemas.documentation {
+        documentation.insert(format!("type:{}", name), text);
+    }
+    for (name, t) in &types {
+        if let Type::Complex(c) = t {
+            for (field, (attributes, _)) in &c.fields {
+                if let Some(text) = &attributes.documentation {
+                    documentation.insert(format!("field:{}.{}", name, field), text.clone());
+                }
+            }
+        }
+    }
+
+    debug!("service name: {}", service_name);
+    debug!("parsed types: {:#?}", types);
+    debug!("parsed messages: {:#?}", messages);
+    debug!("parsed operations: {:#?}", operations);
+    debug!("parsed bindings: {:#?}", bindings);
+    debug!("parsed endpoints: {:#?}", endpoints);
+
+    Ok(Wsdl {
+        name: service_name,
+        target_namespace,
+        types,
+        messages,
+        operations,
+        overloads,
+        bindings,
+        endpoints,
+        namespaces,
+        overridden_types: schemas.overridden,
+        type_namespaces: schemas.namespaces,
+        type_locations: schemas.locations,
+        warnings: schemas.warnings,
+        schema_version: schemas.version,
+        documentation,
+        normalize_operation_case: options.normalize_operation_case,
+    })
+}
+
+/// Trimmed text of the `documentation` child of `element`.
+fn documentation_text(element: &Element) -> Option<String> {
+    get_child_local(element, "documentation")
+        .and_then(|d| d.get_text())
+        .map(|d| d.trim().to_string())
+}
+
+/// Loads the definitions imported by `definitions` with `wsdl:import`, along
+/// with the ones they import, skipping the `visited` locations.
+///
+/// Imports are ignored without a resolver, as schema imports are.
+fn import_definitions(
+    definitions: &Element,
+    resolver: Option<Resolver>,
+    visited: &mut HashSet<String>,
+    imports: &mut Vec<Element>,
+    recovery: &mut Recovery,
+    options: &ParseOptions,
+) -> Result<(), WsdlError> {
+    let resolve = match resolver {
+        Some(resolve) => resolve,
+        None => return Ok(()),
+    };
+    for import in children_named(definitions, "import") {
+        let location = match import.attributes.get("location") {
+            Some(location) if visited.insert(location.to_string()) => location,
+            _ => continue,
+        };
+        let imported = recovery.check(
+            resolve(location)
+                .ok_or_else(|| WsdlError::ImportNotFound(location.to_string()))
+                .and_then(|bytes| parse_element(&bytes, options)),
+        )?;
+        if let Some(imported) = imported {
+            import_definitions(&imported, resolver, visited, imports, recovery, options)?;
+            imports.push(imported);
+        }
+    }
+    Ok(())
+}
+
+/// Child elements of `element` named `name`.
+fn children_named<'a>(element: &'a Element, name: &'a str) -> impl Iterator<Item = &'a Element> {
+    element
+        .children
+        .iter()
+        .filter_map(|c| c.as_element())
+        .filter(move |c| c.name == name)
+}
+
+/// Namespace and local name of a qualified name written in `element`, as in
+/// `tns:StockQuotePortType`. Unprefixed names are in the default namespace.
+fn resolve_qname<'a>(element: &'a Element, qname: &'a str) -> (Option<&'a str>, &'a str) {
+    let prefix = split_prefix(qname).unwrap_or_default();
+    let namespace = element.namespaces.as_ref().and_then(|ns| ns.get(prefix));
+    (namespace, split_namespace(qname))
+}
+
+fn parse_message(message: &Element) -> Result<(String, Message), WsdlError> {
+    let name = message
+        .attributes
+        .get("name")
+        .ok_or(WsdlError::AttributeNotFound("name"))?;
+    let parts = message
+        .children
+        .iter()
+        .filter_map(|c| c.as_element())
+        .filter(|c| c.name == "part")
+        .map(parse_part)
+        .collect::<Result<Vec<_>, _>>()?;
+    //FIXME: namespace
+    let (part_name, element) = parts.first().ok_or(WsdlError::ElementNotFound("part"))?;
+
+    Ok((
+        name.to_string(),
+        Message {
+            part_name: part_name.to_string(),
+            part_element: split_namespace(element).to_string(),
+            part_prefix: split_prefix(element).map(|p| p.to_string()),
+            parts: parts
+                .iter()
+                .map(|(name, element)| Part {
+                    name: name.to_string(),
+                    element: split_namespace(element).to_string(),
+                })
+                .collect(),
+        },
+    ))
+}
+
+/// Name and qualified element, or type, of a message part.
+fn parse_part(part: &Element) -> Result<(String, String), WsdlError> {
+    let name = part
+        .attributes
+        .get("name")
+        .ok_or(WsdlError::AttributeNotFound("name"))?;
+    let element = part
+        .attributes
+        .get("element")
+        .or_else(|| part.attributes.get("type"))
+        .ok_or(WsdlError::AttributeNotFound("element"))?;
+
+    Ok((name.to_string(), element.to_string()))
+}
+
+fn parse_operation(operation: &Element) -> Result<Operation, WsdlError> {
+    let operation_name = operation
+        .attributes
+        .get("name")
+        .ok_or(WsdlError::AttributeNotFound("name"))?;
+
+    let mut input = None;
+    let mut output = None;
+    let mut input_name = None;
+    let mut output_name = None;
+    let mut faults = None;
+    let mut extensions = HashMap::new();
+    for child in operation.children.iter().filter_map(|c| c.as_element()) {
+        match &child.namespace {
+            Some(namespace) if namespace != WSDL11_NS => {
+                extensions
+                    .entry(format!("{{{}}}{}", namespace, child.name))
+                    .or_insert_with(|| child.clone());
+            }
+            _ => {}
+        }
+    }
+    for child in operation
+        .children
+        .iter()
+        .filter_map(|c| c.as_element())
+        .filter(|c| matches!(c.namespace.as_deref(), Some(WSDL11_NS) | None))
+        .filter(|c| c.attributes.get("message").is_some())
+    {
+        let message = split_namespace(
+            child
+                .attributes
+                .get("message")
+                .ok_or(WsdlError::AttributeNotFound("message"))?,
+        );
+        // FIXME: not testing for unicity
+        match child.name.as_str() {
+            "input" => {
+                input = Some(message.to_string());
+                input_name = child.attributes.get("name").cloned();
+            }
+            "output" => {
+                output = Some(message.to_string());
+                output_name = child.attributes.get("name").cloned();
+            }
+            "fault" => {
+                if faults.is_none() {
+                    faults = Some(Vec::new());
+                }
+                if let Some(v) = faults.as_mut() {
+                    let name = child
+                        .attributes
+                        .get("name")
+                        .map(|s| s.as_str())
+                        .unwrap_or(message);
+                    v.push(FaultRef {
+                        name: name.to_string(),
+                        message: message.to_string(),
+                    });
+                }
+            }
+            _ => return Err(WsdlError::ElementNotFound("operation member")),
+        }
+    }
+
+    Ok(Operation {
+        name: operation_name.to_string(),
+        input,
+        output,
+        faults,
+        input_name,
+        output_name,
+        soap_action: None,
+        documentation: documentation_text(operation),
+        parameter_order: operation
+            .attributes
+            .get("parameterOrder")
+            .map(|order| order.split_whitespace().map(|p| p.to_string()).collect()),
+        extensions,
+    })
+}
+
+fn parse_binding(binding: &Element) -> Result<Binding, WsdlError> {
+    let name = binding
+        .attributes
+        .get("name")
+        .ok_or(WsdlError::AttributeNotFound("name"))?;
+    let port_type = split_namespace(
+        binding
+            .attributes
+            .get("type")
+            .ok_or(WsdlError::AttributeNotFound("type"))?,
+    );
+    // soap:binding and soap12:binding only differ by their namespace
+    let transport = get_child_local(binding, "binding")
+        .and_then(|b| b.attributes.get("transport"))
+        .map(|t| t.to_string());
+
+    let mut operations = HashMap::new();
+    let mut overloads: HashMap<String, Vec<BindingOperation>> = HashMap::new();
+    for operation in binding
+        .children
+        .iter()
+        .filter_map(|c| c.as_element())
+        .filter(|c| c.name == "operation")
+    {
+        let name = operation
+            .attributes
+            .get("name")
+            .ok_or(WsdlError::AttributeNotFound("name"))?;
+        let input = get_child_local(operation, "input");
+        let output = get_child_local(operation, "output");
+        let bound = BindingOperation {
+            soap_action: get_child_local(operation, "operation")
+                .and_then(|o| o.attributes.get("soapAction"))
+                .map(|a| a.to_string()),
+            input: input.map(parse_message_binding),
+            output: output.map(parse_message_binding),
+            input_name: input.and_then(|i| i.attributes.get("name")).cloned(),
+            output_name: output.and_then(|o| o.attributes.get("name")).cloned(),
+        };
+        match operations.get(name) {
+            Some(first) => overloads
+                .entry(name.to_string())
+                .or_insert_with(|| vec![BindingOperation::clone(first)])
+                .push(bound),
+            None => {
+                operations.insert(name.to_string(), bound);
+            }
+        }
+    }
+
+    Ok(Binding {
+        name: name.to_string(),
+        port_type: port_type.to_string(),
+        transport,
+        operations,
+        overloads,
+    })
+}
+
+/// Reads the `soap:body` and `soap:header` elements of a binding's input or output.
+fn parse_message_binding(message: &Element) -> MessageBinding {
+    let body = get_child_local(message, "body");
+    let body_parts = body
+        .and_then(|b| b.attributes.get("parts"))
+        .map(|parts| parts.split_whitespace().map(|p| p.to_string()).collect());
+    let encoding = match body
+        .and_then(|b| b.attributes.get("use"))
+        .map(|u| u.as_str())
+    {
+        Some("encoded") => BodyEncoding::Encoded(
+            body.and_then(|b| b.attributes.get("encodingStyle"))
+                .and_then(|style| style.split_whitespace().next())
+                .unwrap_or(SOAP_ENC_NS)
+                .to_string(),
+        ),
+        _ => BodyEncoding::Literal,
+    };
+    let header_parts = message
+        .children
+        .iter()
+        .filter_map(|c| c.as_element())
+        .filter(|c| c.name == "header")
+        .filter_map(
+            |h| match (h.attributes.get("message"), h.attributes.get("part")) {
+                (Some(m), Some(p)) => Some((split_namespace(m).to_string(), p.to_string())),
+                _ => None,
+            },
+        )
+        .collect();
+
+    MessageBinding {
+        body_parts,
+        header_parts,
+        encoding,
+    }
+}
+
+/// Parses a service port, returning `None` if it has no address.
+fn parse_endpoint(service: &str, port: &Element) -> Result<Option<Endpoint>, WsdlError> {
+    // soap:address, soap12:address and http:address only differ by their namespace
+    let address = match get_child_local(port, "address") {
+        Some(address) => address,
+        None => return Ok(None),
+    };
+    let url = match address.attributes.get("location") {
+        Some(url) => url,
+        None => return Ok(None),
+    };
+    let soap_version = match address.namespace.as_deref() {
+        Some(WSDL_SOAP11_NS) => Some(SoapVersion::Soap11),
+        Some(WSDL_SOAP12_NS) => Some(SoapVersion::Soap12),
+        _ => None,
+    };
+
+    Ok(Some(Endpoint {
+        name: port
+            .attributes
+            .get("name")
+            .ok_or(WsdlError::AttributeNotFound("name"))?
+            .to_string(),
+        binding: split_namespace(
+            port.attributes
+                .get("binding")
+                .ok_or(WsdlError::AttributeNotFound("binding"))?,
+        )
+        .to_string(),
+        url: url.to_string(),
+        service: service.to_string(),
+        soap_version,
+    }))
+}
+
+/// Definitions of a schema, without the ones of the schemas it imports or
+/// includes, as cached by `SchemaCache`.
+#[derive(Debug, Default)]
+struct SchemaDefinitions {
+    target_namespace: Option<String>,
+    /// `(include, schemaLocation)` of its imports and includes, in document order
+    imports: Vec<(bool, String)>,
+    version: XsdVersion,
+    /// XSD 1.1 elements used without declaring the version
+    undeclared_xsd11: Vec<String>,
+    /// types, each followed by the anonymous types of its fields, in document order
+    types: Vec<Vec<(String, Type)>>,
+    documentation: Vec<(String, String)>,
+    typed_substitutions: Vec<(String, Option<String>, bool)>,
+    warnings: Vec<WsdlWarning>,
+}
+
+/// Reads the definitions of `schema`, returning them with the errors met.
+fn read_schema(schema: &Element) -> (SchemaDefinitions, Vec<WsdlError>) {
+    let mut definitions = SchemaDefinitions {
+        target_namespace: schema.attributes.get("targetNamespace").cloned(),
+        ..Default::default()
+    };
+    let mut errors = Vec::new();
+    let element_form_default =
+        ElementForm::parse(schema.attributes.get("elementFormDefault")).unwrap_or_default();
+    // `vc:minVersion` of the versioning namespace
+    let declares_xsd11 = matches!(
+        schema.attributes.get("minVersion").map(|v| v.parse::<f32>()),
+        Some(Ok(version)) if version >= 1.1
+    );
+    let xsd11_elements = xsd11_elements(schema);
+    if declares_xsd11 || !xsd11_elements.is_empty() {
+        definitions.version = XsdVersion::V1_1;
+    }
+    if !declares_xsd11 {
+        definitions.undeclared_xsd11 = xsd11_elements;
+    }
+    for elem in schema.children.iter().filter_map(|c| c.as_element()) {
+        if elem.name == "import" || elem.name == "include" {
+            if let Some(location) = elem.attributes.get("schemaLocation") {
+                definitions
+                    .imports
+                    .push((elem.name == "include", location.to_string()));
+            }
+            continue;
+        }
+
+        if let (Some(name), Some(documentation)) = (
+            elem.attributes.get("name"),
+            get_child_local(elem, "annotation").and_then(documentation_text),
+        ) {
+            definitions
+                .documentation
+                .push((name.clone(), documentation));
+        }
+
+        if let (true, Some(target)) = (elem.name == "element", elem.attributes.get("type")) {
+            let head = elem
+                .attributes
+                .get("substitutionGroup")
+                .map(|group| split_namespace(group).to_string());
+            let abstract_ = elem.attributes.get("abstract").map(|a| a.as_str()) == Some("true");
+            if head.is_some() || abstract_ {
+                definitions.typed_substitutions.push((
+                    split_namespace(target).to_string(),
+                    head,
+                    abstract_,
+                ));
+            }
+        }
+
+        match parse_type(elem, element_form_default, &mut definitions.warnings) {
+            Ok(types) => definitions.types.push(types),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    (definitions, errors)
+}
+
+fn parse_schema(
+    schema: &Element,
+    schemas: &mut Schemas,
+    resolver: Option<Resolver>,
+    cache: Option<&SchemaCache>,
+    recovery: &mut Recovery,
+    options: &ParseOptions,
+) -> Result<(), WsdlError> {
+    let (definitions, errors) = read_schema(schema);
+    for error in errors {
+        recovery.check::<()>(Err(error))?;
+    }
+    merge_schema(
+        &definitions,
+        None,
+        schemas,
+        resolver,
+        cache,
+        recovery,
+        options,
+    )
+}
+
+/// Adds `definitions` to `schemas`, along with the ones of the schemas they
+/// import. The definitions of included schemas belong to the `namespace` of
+/// the including schema.
+fn merge_schema(
+    definitions: &SchemaDefinitions,
+    namespace: Option<&String>,
+    schemas: &mut Schemas,
+    resolver: Option<Resolver>,
+    cache: Option<&SchemaCache>,
+    recovery: &mut Recovery,
+    options: &ParseOptions,
+) -> Result<(), WsdlError> {
+    let namespace = namespace.or(definitions.target_namespace.as_ref());
+    schemas.version = schemas.version.max(definitions.version);
+    if options.collect_warnings {
+        schemas.warnings.extend(
+            definitions
+                .undeclared_xsd11
+                .iter()
+                .cloned()
+                .map(WsdlWarning::UndeclaredXsd11Feature),
+        );
+    }
+
+    // imported schemas declare their own namespace, while the definitions
+    // of included schemas belong to the namespace of the including schema
+    for (include, location) in &definitions.imports {
+        let resolve = match resolver {
+            Some(resolve) => resolve,
+            None => break,
+        };
+        if !schemas.imported.insert(location.to_string()) {
+            continue;
+        }
+        let imported = match cache.and_then(|c| c.get(location)) {
+            Some(cached) => Some(cached),
+            None => {
+                let element = recovery.check(
+                    resolve(location)
+                        .ok_or_else(|| WsdlError::ImportNotFound(location.to_string()))
+                        .and_then(|bytes| parse_element(&bytes, options)),
+                )?;
+                match element {
+                    Some(element) => {
+                        let (definitions, errors) = read_schema(&element);
+                        let cached = errors.is_empty();
+                        for error in errors {
+                            recovery.check::<()>(Err(error))?;
+                        }
+                        let definitions = Arc::new(definitions);
+                        if let (Some(cache), true) = (cache, cached) {
+                            cache.insert(location, definitions.clone());
+                        }
+                        Some(definitions)
+                    }
+                    None => None,
+                }
+            }
+        };
+        if let Some(imported) = imported {
+            let importing = schemas.location.replace(location.to_string());
+            merge_schema(
+                &imported,
+                namespace.filter(|_| *include),
+                schemas,
+                resolver,
+                cache,
+                recovery,
+                options,
+            )?;
+            schemas.location = importing;
+        }
+    }
+
+    for (name, documentation) in &definitions.documentation {
+        schemas
+            .documentation
+            .insert(name.clone(), documentation.clone());
+    }
+    schemas
+        .typed_substitutions
+        .extend(definitions.typed_substitutions.iter().cloned());
+    if options.collect_warnings {
+        schemas
+            .warnings
+            .extend(definitions.warnings.iter().cloned());
+    }
+    for types in &definitions.types {
+        // the type comes first, followed by the anonymous types of its fields
+        let mut types = types.iter().cloned();
+        if let Some((owner, t)) = types.next() {
+            recovery.check(schemas.insert(
+                owner.clone(),
+                t,
+                namespace,
+                options.duplicate_strategy,
+            ))?;
+            for (name, t) in types {
+                schemas.insert_inline(&owner, name, t, namespace);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Elements introduced by XSD 1.1.
+const XSD11_ELEMENTS: &[&str] = &[
+    "alternative",
+    "assert",
+    "assertion",
+    "defaultOpenContent",
+    "openContent",
+    "override",
+];
+
+/// Names of the XSD 1.1 elements used below `element`, once each, in
+/// document order. Imported schemas are not included.
+fn xsd11_elements(element: &Element) -> Vec<String> {
+    let mut names = Vec::new();
+    for child in element.children.iter().filter_map(|c| c.as_element()) {
+        if XSD11_ELEMENTS.contains(&child.name.as_str()) {
+            names.push(child.name.clone());
+        }
+        names.extend(xsd11_elements(child));
+    }
+    let mut seen = HashSet::new();
+    names.retain(|name| seen.insert(name.clone()));
+    names
+}
+
+/// Parses a top level schema element or type definition.
+///
+/// Returns the type along with the anonymous simple types of its fields.
+fn parse_type(
+    elem: &Element,
+    element_form_default: ElementForm,
+    warnings: &mut Vec<WsdlWarning>,
+) -> Result<Vec<(String, Type)>, WsdlError> {
+    trace!("type: {:#?}", elem);
+    let name = elem
+        .attributes
+        .get("name")
+        .ok_or(WsdlError::AttributeNotFound("name"))?;
+
+    if let (Some(target), true) = (elem.attributes.get("type"), elem.name == "element") {
+        let aliased = match simple_type(elem, target) {
+            SimpleType::Complex(target) => Type::Alias(target),
+            simple => Type::Simple(simple),
+        };
+        return Ok(vec![(name.to_string(), aliased)]);
+    }
+
+    // sometimes we have <element name="TypeName"><complexType>...</complexType></element>,
+    // sometimes we have <complexType name="TypeName">...</complexType>
+    // anonymous complex types are registered under the name of their element
+    let abstract_ = elem.attributes.get("abstract").map(|a| a.as_str()) == Some("true");
+    let substitution_group = elem
+        .attributes
+        .get("substitutionGroup")
+        .filter(|_| elem.name == "element")
+        .map(|group| split_namespace(group).to_string());
+    let child = if elem.name == "complexType" || elem.name == "simpleType" {
+        elem
+    } else {
+        match elem
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .find(|c| c.name != "annotation")
+        {
+            Some(child) => child,
+            // the members of a substitution group may only stand for their head
+            None if abstract_ || substitution_group.is_some() => {
+                return Ok(vec![(
+                    name.to_string(),
+                    Type::Complex(ComplexType {
+                        element_form_default,
+                        substitution_group,
+                        abstract_,
+                        ..Default::default()
+                    }),
+                )])
+            }
+            None => return Err(WsdlError::Empty),
+        }
+    };
+
+    if child.name == "complexType" {
+        let mut fields = HashMap::new();
+        let mut compositors = child
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .filter(|c| matches!(c.name.as_str(), "sequence" | "choice" | "all"));
+        let compositor = compositors.next();
+        if compositors.next().is_some() {
+            return Err(WsdlError::MultipleChildren("complexType"));
+        }
+        let attributes = child
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .filter(|c| c.name == "attribute")
+            .collect::<Vec<_>>();
+        // types holding only attributes have no compositor
+        if compositor.is_none() && attributes.is_empty() {
+            return Err(WsdlError::Empty);
+        }
+        let content_model = match compositor.map(|c| c.name.as_str()) {
+            Some("all") => ContentModel::All,
+            Some("choice") => ContentModel::Choice,
+            _ => ContentModel::Sequence,
+        };
+
+        let mut types = Vec::new();
+        if let Some(compositor) = compositor {
+            let group = Group::default().nested(compositor, None)?;
+            parse_fields(compositor, &group, name, &mut fields, &mut types, warnings)?;
+        }
+        for attribute in attributes {
+            let (attribute_name, field) = parse_attribute(attribute)?;
+            fields.insert(attribute_name, field);
+        }
+        let assertions = child
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .filter(|c| c.name == "assert")
+            .filter_map(|c| c.attributes.get("test").cloned())
+            .collect();
+
+        types.insert(
+            0,
+            (
+                name.to_string(),
+                Type::Complex(ComplexType {
+                    fields,
+                    content_model,
+                    element_form_default,
+                    assertions,
+                    substitution_group,
+                    abstract_,
+                }),
+            ),
+        );
+        Ok(types)
+    } else if child.name == "simpleType" {
+        Ok(vec![(name.to_string(), parse_simple_type(child)?)])
+    } else {
+        trace!("child {:#?}", child);
+        Err(WsdlError::UnsupportedType(name.to_string()))
+    }
+}
+
+/// Parses the restriction of a `simpleType`.
+fn parse_simple_type(element: &Element) -> Result<Type, WsdlError> {
+    let restriction =
+        get_child_local(element, "restriction").ok_or(WsdlError::ElementNotFound("restriction"))?;
+    let base = restriction
+        .attributes
+        .get("base")
+        .ok_or(WsdlError::AttributeNotFound("base"))?;
+    let values = restriction
+        .children
+        .iter()
+        .filter_map(|c| c.as_element())
+        .filter(|c| c.name == "enumeration")
+        .filter_map(|c| c.attributes.get("value"))
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>();
+
+    let facet = |name: &str| -> Result<Option<u32>, WsdlError> {
+        match get_child_local(restriction, name).and_then(|f| f.attributes.get("value")) {
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| WsdlError::UnsupportedType(name.to_string())),
+            None => Ok(None),
+        }
+    };
+    let total_digits = facet("totalDigits")?;
+    let fraction_digits = facet("fractionDigits")?;
+
+    Ok(if !values.is_empty() {
+        Type::Enumeration(values)
+    } else if simple_type(restriction, base) == SimpleType::Decimal
+        && (total_digits.is_some() || fraction_digits.is_some())
+    {
+        Type::Decimal {
+            total_digits,
+            fraction_digits,
+        }
+    } else {
+        Type::Simple(simple_type(restriction, base))
+    })
+}
+
+/// Parses an `xs:attribute` of a complex type into a field.
+fn parse_attribute(
+    attribute: &Element,
+) -> Result<(String, (TypeAttribute, SimpleType)), WsdlError> {
+    let name = attribute
+        .attributes
+        .get("name")
+        .ok_or(WsdlError::AttributeNotFound("name"))?;
+    let attribute_type = attribute
+        .attributes
+        .get("type")
+        .map_or(SimpleType::String, |t| simple_type(attribute, t));
+    let type_attributes = TypeAttribute {
+        // attributes are optional unless required
+        nillable: attribute.attributes.get("use").map(|u| u.as_str()) != Some("required"),
+        documentation: get_child_local(attribute, "annotation").and_then(documentation_text),
+        fixed: attribute.attributes.get("fixed").cloned(),
+        kind: FieldKind::Attribute,
+        appinfo: parse_appinfo(attribute),
+        ..Default::default()
+    };
+
+    Ok((name.to_string(), (type_attributes, attribute_type)))
+}
+
+/// Children of the `annotation/appinfo` elements of a field.
+fn parse_appinfo(field: &Element) -> Vec<Element> {
+    field
+        .children
+        .iter()
+        .filter_map(|c| c.as_element())
+        .filter(|c| c.name == "annotation")
+        .flat_map(|a| a.children.iter().filter_map(|c| c.as_element()))
+        .filter(|c| c.name == "appinfo")
+        .flat_map(|a| a.children.iter().filter_map(|c| c.as_element()))
+        .cloned()
+        .collect()
+}
+
+/// Compositor enclosing the fields being read.
+#[derive(Clone, Default)]
+struct Group {
+    /// kind of a nested compositor, `None` for the compositor of the type
+    kind: Option<ContentModel>,
+    /// whether the whole group may be absent, as an alternative of a choice
+    optional: bool,
+    /// number of times the group repeats, combined with its enclosing
+    /// compositors, `None` if it appears at most once
+    max_occurs: Option<Occurence>,
+}
+
+impl Group {
+    /// Group of the compositor `element`, nested in `self`.
+    fn nested(&self, element: &Element, kind: Option<ContentModel>) -> Result<Group, WsdlError> {
+        let min_occurs = parse_occurence(element.attributes.get("minOccurs"))?;
+        let max_occurs = match multiply_occurences(
+            self.max_occurs.clone(),
+            parse_occurence(element.attributes.get("maxOccurs"))?,
+        ) {
+            Some(Occurence::Num(n)) if n <= 1 => None,
+            max_occurs => max_occurs,
+        };
+
+        Ok(Group {
+            kind: kind.or(self.kind),
+            optional: self.optional
+                || kind == Some(ContentModel::Choice)
+                || min_occurs == Some(Occurence::Num(0)),
+            max_occurs,
+        })
+    }
+}
+
+/// Product of two `maxOccurs`, `None` standing for 1. Products too large
+/// for a `u32` are unbounded.
+fn multiply_occurences(a: Option<Occurence>, b: Option<Occurence>) -> Option<Occurence> {
+    match (a, b) {
+        (None, b) => b,
+        (a, None) => a,
+        (Some(Occurence::Num(a)), Some(Occurence::Num(b))) => Some(
+            a.checked_mul(b)
+                .map_or(Occurence::Unbounded, Occurence::Num),
+        ),
+        _ => Some(Occurence::Unbounded),
+    }
+}
+
+fn parse_occurence(occurence: Option<&String>) -> Result<Option<Occurence>, WsdlError> {
+    match occurence.map(|s| s.as_str()) {
+        None => Ok(None),
+        Some("unbounded") => Ok(Some(Occurence::Unbounded)),
+        Some(n) => n
+            .parse()
+            .map(|n| Some(Occurence::Num(n)))
+            .map_err(|_| WsdlError::InvalidOccurence(n.to_string())),
+    }
+}
+
+/// Reads the elements of a compositor of `type_name` into `fields`,
+/// flattening nested compositors.
+///
+/// Fields of a repeated group are repeated, and fields of a group that may be
+/// absent are optional. Anonymous enumerations and decimals of the fields are
+/// added to `types`, named after the type and the field.
+fn parse_fields(
+    compositor: &Element,
+    group: &Group,
+    type_name: &str,
+    fields: &mut HashMap<String, (TypeAttribute, SimpleType)>,
+    types: &mut Vec<(String, Type)>,
+    warnings: &mut Vec<WsdlWarning>,
+) -> Result<(), WsdlError> {
+    for field in compositor.children.iter().filter_map(|c| c.as_element()) {
+        let nested = match field.name.as_str() {
+            "sequence" => Some(ContentModel::Sequence),
+            "choice" => Some(ContentModel::Choice),
+            "all" => Some(ContentModel::All),
+            _ => None,
+        };
+        if let Some(kind) = nested {
+            let group = group.nested(field, Some(kind))?;
+            parse_fields(field, &group, type_name, fields, types, warnings)?;
+            continue;
+        }
+
+        let field_name = field
+            .attributes
+            .get("name")
+            .ok_or(WsdlError::AttributeNotFound("name"))?;
+        let field_type = match (
+            field.attributes.get("type"),
+            get_child_local(field, "simpleType"),
+        ) {
+            (Some(field_type), _) => simple_type(field, field_type),
+            (None, Some(inline)) => match parse_simple_type(inline)? {
+                Type::Simple(simple) => simple,
+                t => {
+                    let inline_name = format!("{}{}", type_name, field_name.to_camel());
+                    types.push((inline_name.clone(), t));
+                    SimpleType::Complex(inline_name)
+                }
+            },
+            (None, None) => return Err(WsdlError::AttributeNotFound("type")),
+        };
+        let nillable = match field.attributes.get("nillable").map(|s| s.as_str()) {
+            Some("true") | Some("1") => true,
+            Some("false") | Some("0") | None => false,
+            Some(other) => return Err(WsdlError::InvalidNillable(other.to_string())),
+        };
+
+        let min_occurs = parse_occurence(field.attributes.get("minOccurs"))?;
+        let max_occurs = multiply_occurences(
+            parse_occurence(field.attributes.get("maxOccurs"))?,
+            group.max_occurs.clone(),
+        );
+        let documentation = get_child_local(field, "annotation").and_then(documentation_text);
+        trace!("field {:?} -> {:?}", field_name, field_type);
+        let mut type_attributes = TypeAttribute {
+            nillable,
+            min_occurs,
+            max_occurs,
+            documentation,
+            fixed: field.attributes.get("fixed").cloned(),
+            form: ElementForm::parse(field.attributes.get("form")),
+            compositor: group.kind,
+            group_max_occurs: group.max_occurs.clone(),
+            kind: FieldKind::Element,
+            appinfo: parse_appinfo(field),
+        };
+        // at most one occurence: 0..1 is an optional field, and 1..1
+        // the default. Repeated fields keep their bounds.
+        if !type_attributes.is_repeated() {
+            if let Some(Occurence::Num(0)) = type_attributes.min_occurs {
+                if !type_attributes.nillable {
+                    warnings.push(WsdlWarning::NillableNormalized {
+                        type_name: type_name.to_string(),
+                        field: field_name.to_string(),
+                    });
+                }
+                type_attributes.nillable = true;
+            }
+            type_attributes.min_occurs = None;
+            type_attributes.max_occurs = None;
+        }
+        // only one alternative of a nested choice is present
+        if group.optional && !type_attributes.is_repeated() {
+            type_attributes.nillable = true;
+        }
+
+        fields.insert(field_name.to_string(), (type_attributes, field_type));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
     const WIKIPEDIA_WSDL: &[u8] = include_bytes!("../assets/wikipedia-example.wsdl");
     const EXAMPLE_WSDL: &[u8] = include_bytes!("../assets/example.wsdl");
+    const IMPORT_WSDL: &[u8] = include_bytes!("../assets/import-example.wsdl");
+    const INVENTORY_XSD: &[u8] = include_bytes!("../assets/inventory.xsd");
+    const INCLUDE_WSDL: &[u8] = include_bytes!("../assets/include-example.wsdl");
+    const COMMON_TYPES_XSD: &[u8] = include_bytes!("../assets/common-types.xsd");
+
+    #[test]
+    fn parse_example() {
+        let res = parse(EXAMPLE_WSDL);
+        println!("res: {:?}", res);
+        res.unwrap();
+    }
+
+    /// Wraps schema definitions and WSDL definitions in a minimal WSDL 1.1 document.
+    fn document(schema: &str, definitions: &str) -> String {
+        format!(
+            r#"<?xml version="1.0"?>
+<definitions name="Test"
+             targetNamespace="http://example.com/test.wsdl"
+             xmlns:tns="http://example.com/test.wsdl"
+             xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+             xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+             xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <types>
+    <xsd:schema targetNamespace="http://example.com/test.wsdl">
+      {}
+    </xsd:schema>
+  </types>
+  {}
+  <service name="TestService"/>
+</definitions>"#,
+            schema, definitions
+        )
+    }
+
+    #[test]
+    fn documentation_map() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert_eq!(
+            wsdl.documentation.get("service:StockQuoteService"),
+            Some(&"My first service".to_string())
+        );
+
+        let source = document(
+            r#"
+      <xsd:element name="Customer">
+        <xsd:annotation>
+          <xsd:documentation>A customer</xsd:documentation>
+        </xsd:annotation>
+        <xsd:complexType>
+          <xsd:sequence>
+            <xsd:element name="id" type="xsd:int">
+              <xsd:annotation>
+                <xsd:documentation>Identifier of the customer.</xsd:documentation>
+              </xsd:annotation>
+            </xsd:element>
+          </xsd:sequence>
+        </xsd:complexType>
+      </xsd:element>"#,
+            r#"<message name="GetCustomerInput">
+    <documentation>Request of a customer.</documentation>
+    <part name="body" element="tns:Customer"/>
+  </message>
+  <portType name="TestPortType">
+    <operation name="GetCustomer">
+      <documentation>
+        Returns a customer.
+      </documentation>
+      <input message="tns:GetCustomerInput"/>
+    </operation>
+    <operation name="Ping">
+      <input message="tns:GetCustomerInput"/>
+    </operation>
+  </portType>"#,
+        );
+        let wsdl = parse(source.as_bytes()).unwrap();
+        let text = |key: &str| wsdl.documentation.get(key).map(|d| d.as_str());
+        assert_eq!(text("operation:GetCustomer"), Some("Returns a customer."));
+        assert_eq!(
+            text("message:GetCustomerInput"),
+            Some("Request of a customer.")
+        );
+        assert_eq!(text("type:Customer"), Some("A customer"));
+        assert_eq!(
+            text("field:Customer.id"),
+            Some("Identifier of the customer.")
+        );
+        assert_eq!(text("operation:Ping"), None);
+        assert_eq!(wsdl.documentation.len(), 4);
+    }
+
+    #[test]
+    fn operation_extensions() {
+        let source = document(
+            r#"<xsd:element name="Quote" type="xsd:string"/>"#,
+            r#"<message name="QuoteMessage">
+    <part name="body" element="tns:Quote"/>
+  </message>
+  <portType name="TestPortType">
+    <operation name="GetQuote" xmlns:sap="urn:sap-com:document:sap:rfc:functions">
+      <sap:rfm name="Z_GET_QUOTE" message="tns:QuoteMessage"/>
+      <acme:routing xmlns:acme="http://example.com/acme">
+        <acme:queue>quotes</acme:queue>
+      </acme:routing>
+      <input message="tns:QuoteMessage"/>
+      <output message="tns:QuoteMessage"/>
+    </operation>
+    <operation name="Ping">
+      <input message="tns:QuoteMessage"/>
+    </operation>
+  </portType>"#,
+        );
+        let wsdl = parse(source.as_bytes()).unwrap();
+
+        let operation = &wsdl.operations["GetQuote"];
+        assert_eq!(operation.input.as_deref(), Some("QuoteMessage"));
+        assert_eq!(operation.extensions.len(), 2);
+        let rfm = &operation.extensions["{urn:sap-com:document:sap:rfc:functions}rfm"];
+        assert_eq!(rfm.attributes["name"], "Z_GET_QUOTE");
+        let routing = &operation.extensions["{http://example.com/acme}routing"];
+        assert_eq!(
+            routing
+                .get_child("queue")
+                .and_then(|q| q.get_text())
+                .as_deref(),
+            Some("quotes")
+        );
+        assert!(wsdl.operations["Ping"].extensions.is_empty());
+    }
+
+    #[test]
+    fn normalized_operation_documentation() {
+        let source = document(
+            r#"<xsd:element name="Quote" type="xsd:string"/>"#,
+            r#"<message name="QuoteMessage">
+    <part name="body" element="tns:Quote"/>
+  </message>
+  <portType name="TestPortType">
+    <operation name="GetQuote">
+      <documentation>Returns a quote.</documentation>
+      <input message="tns:QuoteMessage"/>
+    </operation>
+  </portType>"#,
+        );
+
+        let wsdl = parse(source.as_bytes()).unwrap();
+        assert_eq!(
+            wsdl.operation_documentation("GetQuote"),
+            Some("Returns a quote.")
+        );
+        assert_eq!(wsdl.operation_documentation("getQuote"), None);
+
+        let options = ParseOptions {
+            normalize_operation_case: true,
+            ..Default::default()
+        };
+        let wsdl = parse_with_options(source.as_bytes(), &options).unwrap();
+        assert!(wsdl.documentation.contains_key("operation:getquote"));
+        assert_eq!(
+            wsdl.operation_documentation("GetQuote"),
+            Some("Returns a quote.")
+        );
+        assert_eq!(
+            wsdl.operation_documentation("GETQUOTE"),
+            Some("Returns a quote.")
+        );
+    }
+
+    #[test]
+    fn normalize_operation_case() {
+        let source = document(
+            r#"<xsd:element name="Quote" type="xsd:string"/>"#,
+            r#"<message name="QuoteMessage">
+    <part name="body" element="tns:Quote"/>
+  </message>
+  <portType name="TestPortType">
+    <operation name="GetQuote">
+      <input message="tns:QuoteMessage"/>
+      <output message="tns:QuoteMessage"/>
+    </operation>
+  </portType>
+  <binding name="TestBinding" type="tns:TestPortType">
+    <soap:binding style="document" transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="getQuote">
+      <soap:operation soapAction="urn:getQuote"/>
+    </operation>
+  </binding>"#,
+        );
+
+        let wsdl = parse(source.as_bytes()).unwrap();
+        assert_eq!(wsdl.operations["GetQuote"].soap_action, None);
+
+        let options = ParseOptions {
+            normalize_operation_case: true,
+            ..Default::default()
+        };
+        let wsdl = parse_with_options(source.as_bytes(), &options).unwrap();
+        assert_eq!(options.operation_key("GetQuote"), "getquote");
+        let operation = &wsdl.operations[&options.operation_key("GetQuote")];
+        assert_eq!(operation.name, "GetQuote");
+        assert_eq!(operation.soap_action.as_deref(), Some("urn:getQuote"));
+        assert!(wsdl.has_operation("GetQuote"));
+        assert!(wsdl.has_operation("getQuote"));
+        assert_eq!(
+            wsdl.operation_signature("getQuote")
+                .unwrap()
+                .input_type
+                .as_deref(),
+            Some("QuoteMessage")
+        );
+        assert_eq!(
+            wsdl.binding_for_operation("GetQuote")
+                .map(|b| b.name.as_str()),
+            Some("TestBinding")
+        );
+        assert!(wsdl.input_qname("GETQUOTE").is_some());
+    }
+
+    #[test]
+    fn envelope_namespaces() {
+        let wsdl = parse(
+            r#"<?xml version="1.0"?>
+<definitions name="Orders"
+             targetNamespace="http://example.com/orders.wsdl"
+             xmlns:tns="http://example.com/orders.wsdl"
+             xmlns:ord="http://example.com/orders.xsd"
+             xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+             xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <types>
+    <xsd:schema targetNamespace="http://example.com/orders.xsd">
+      <xsd:element name="GetOrder">
+        <xsd:complexType>
+          <xsd:sequence>
+            <xsd:element name="id" type="xsd:int"/>
+          </xsd:sequence>
+        </xsd:complexType>
+      </xsd:element>
+      <xsd:element name="Order" type="ord:OrderType"/>
+      <xsd:complexType name="OrderType">
+        <xsd:sequence>
+          <xsd:element name="id" type="xsd:int"/>
+        </xsd:sequence>
+      </xsd:complexType>
+    </xsd:schema>
+    <xsd:schema targetNamespace="http://example.com/customers.xsd">
+      <xsd:element name="GetCustomer">
+        <xsd:complexType>
+          <xsd:sequence>
+            <xsd:element name="order" type="ord:OrderType"/>
+          </xsd:sequence>
+        </xsd:complexType>
+      </xsd:element>
+    </xsd:schema>
+  </types>
+  <message name="GetOrderInput">
+    <part name="body" element="ord:GetOrder"/>
+  </message>
+  <message name="GetOrderOutput">
+    <part name="body" element="ord:Order"/>
+  </message>
+  <message name="GetCustomerInput">
+    <part name="body" element="cus:GetCustomer"/>
+  </message>
+  <portType name="OrdersPortType">
+    <operation name="GetOrder">
+      <input message="tns:GetOrderInput"/>
+      <output message="tns:GetOrderOutput"/>
+    </operation>
+    <operation name="GetCustomer">
+      <input message="tns:GetCustomerInput"/>
+    </operation>
+  </portType>
+  <service name="OrdersService"/>
+</definitions>"#
+                .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            wsdl.envelope_namespaces("GetOrder"),
+            vec![(
+                "ord".to_string(),
+                "http://example.com/orders.xsd".to_string()
+            )]
+        );
+        assert_eq!(
+            wsdl.envelope_namespaces("GetCustomer"),
+            vec![
+                (
+                    "ns1".to_string(),
+                    "http://example.com/customers.xsd".to_string()
+                ),
+                (
+                    "ord".to_string(),
+                    "http://example.com/orders.xsd".to_string()
+                ),
+            ]
+        );
+        assert!(wsdl.envelope_namespaces("GetInvoice").is_empty());
+    }
+
+    #[test]
+    fn shareable_across_tasks() {
+        fn assert_shareable<T: Send + Sync + 'static>() {}
+        assert_shareable::<std::sync::Arc<Wsdl>>();
+    }
+
+    #[test]
+    fn parse_from_str() {
+        let wsdl = parse_str(std::str::from_utf8(EXAMPLE_WSDL).unwrap()).unwrap();
+        assert_eq!(wsdl.name, "StockQuoteService");
+        assert!(wsdl.operations.contains_key("GetLastTradePrice"));
+    }
+
+    #[test]
+    fn has_definitions() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert!(wsdl.has_operation("GetLastTradePrice"));
+        assert!(!wsdl.has_operation("GetLastTradeVolume"));
+        assert!(wsdl.has_type("TradePrice"));
+        assert!(!wsdl.has_type("GetLastTradePriceInput"));
+        assert!(wsdl.has_message("GetLastTradePriceInput"));
+        assert!(!wsdl.has_message("TradePrice"));
+    }
+
+    #[test]
+    fn counts() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert_eq!(wsdl.operation_count(), 1);
+        assert_eq!(wsdl.type_count(), 2);
+        assert_eq!(wsdl.message_count(), 2);
+    }
+
+    #[test]
+    fn stats() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert_eq!(
+            wsdl.stats(),
+            WsdlStats {
+                complex_types: 2,
+                messages: 2,
+                request_response_operations: 1,
+                services: 1,
+                bindings: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_anonymous_complex_types() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:element name="Customer">
+        <xsd:annotation>
+          <xsd:documentation>A customer</xsd:documentation>
+        </xsd:annotation>
+        <xsd:complexType>
+          <xsd:sequence>
+            <xsd:element name="id" type="xsd:int"/>
+            <xsd:element name="address" type="tns:Address"/>
+          </xsd:sequence>
+        </xsd:complexType>
+      </xsd:element>
+      <xsd:complexType name="Address">
+        <xsd:sequence>
+          <xsd:element name="city" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        match &wsdl.types["Customer"] {
+            Type::Complex(c) => {
+                assert!(matches!(c.fields["id"].1, SimpleType::Int));
+                assert!(matches!(&c.fields["address"].1, SimpleType::Complex(s) if s == "Address"));
+            }
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+        match &wsdl.types["Address"] {
+            Type::Complex(c) => assert!(matches!(c.fields["city"].1, SimpleType::String)),
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn resolve_aliases() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:element name="GetCustomer" type="tns:CustomerRequest"/>
+      <xsd:element name="CustomerRequest" type="tns:CustomerQuery"/>
+      <xsd:complexType name="CustomerQuery">
+        <xsd:sequence>
+          <xsd:element name="id" type="xsd:int"/>
+        </xsd:sequence>
+      </xsd:complexType>
+      <xsd:element name="Name" type="xsd:string"/>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert!(matches!(&wsdl.types["GetCustomer"], Type::Alias(t) if t == "CustomerRequest"));
+        match wsdl.resolve_alias("GetCustomer") {
+            Some(Type::Complex(c)) => assert!(c.fields.contains_key("id")),
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+        assert!(matches!(
+            wsdl.resolve_alias("Name"),
+            Some(Type::Simple(SimpleType::String))
+        ));
+        assert!(wsdl.resolve_alias("Unknown").is_none());
+    }
+
+    #[test]
+    fn substitution_groups() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:element name="Address" abstract="true"/>
+      <xsd:element name="StreetAddress" substitutionGroup="tns:Address">
+        <xsd:complexType>
+          <xsd:sequence>
+            <xsd:element name="street" type="xsd:string"/>
+          </xsd:sequence>
+        </xsd:complexType>
+      </xsd:element>
+      <xsd:element name="PoBox" substitutionGroup="tns:Address"/>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        match &wsdl.types["Address"] {
+            Type::Complex(c) => {
+                assert!(c.abstract_);
+                assert!(c.fields.is_empty());
+                assert_eq!(c.substitution_group, None);
+            }
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+        match &wsdl.types["StreetAddress"] {
+            Type::Complex(c) => {
+                assert!(!c.abstract_);
+                assert!(c.fields.contains_key("street"));
+                assert_eq!(c.substitution_group.as_deref(), Some("Address"));
+            }
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+        assert!(matches!(
+            &wsdl.types["PoBox"],
+            Type::Complex(c) if c.substitution_group.as_deref() == Some("Address")
+        ));
+    }
+
+    #[test]
+    fn builtin_types_by_namespace() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Name">
+        <xsd:sequence>
+          <xsd:element name="given" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>
+      <xsd:element name="Person">
+        <xsd:complexType>
+          <xsd:sequence>
+            <xsd:element name="name" type="tns:Name"/>
+            <xsd:element name="login" type="xsd:Name"/>
+            <xsd:element name="id" type="QName" xmlns="http://www.w3.org/2001/XMLSchema"/>
+          </xsd:sequence>
+        </xsd:complexType>
+      </xsd:element>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        match &wsdl.types["Person"] {
+            Type::Complex(c) => {
+                assert_eq!(c.fields["name"].1, SimpleType::Complex("Name".to_string()));
+                assert_eq!(c.fields["login"].1, SimpleType::Name);
+                assert_eq!(c.fields["id"].1, SimpleType::QName);
+            }
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn typed_substitution_members() {
+        let wsdl = parse(include_bytes!("../assets/substitutions.wsdl")).unwrap();
+
+        assert!(matches!(&wsdl.types["Locker"], Type::Alias(t) if t == "LockerType"));
+        match &wsdl.types["LockerType"] {
+            Type::Complex(c) => {
+                assert!(c.fields.contains_key("code"));
+                assert_eq!(c.substitution_group.as_deref(), Some("Address"));
+            }
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn effective_primitive() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:simpleType name="Code">
+        <xsd:restriction base="xsd:string"/>
+      </xsd:simpleType>
+      <xsd:simpleType name="CountryCode">
+        <xsd:restriction base="tns:Code"/>
+      </xsd:simpleType>
+      <xsd:element name="Country" type="tns:CountryCode"/>
+      <xsd:simpleType name="Loop">
+        <xsd:restriction base="tns:Loop"/>
+      </xsd:simpleType>
+      <xsd:simpleType name="Status">
+        <xsd:restriction base="xsd:string">
+          <xsd:enumeration value="open"/>
+        </xsd:restriction>
+      </xsd:simpleType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            wsdl.effective_primitive("CountryCode"),
+            Some(SimpleType::String)
+        );
+        assert_eq!(
+            wsdl.effective_primitive("Country"),
+            Some(SimpleType::String)
+        );
+        assert_eq!(wsdl.effective_primitive("Loop"), None);
+        assert_eq!(wsdl.effective_primitive("Status"), None);
+        assert_eq!(wsdl.effective_primitive("Unknown"), None);
+    }
+
+    #[test]
+    fn transports() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert_eq!(
+            wsdl.bindings["StockQuoteSoapBinding"].port_type,
+            "StockQuotePortType"
+        );
+        assert_eq!(
+            wsdl.transports(),
+            vec!["http://schemas.xmlsoap.org/soap/http".to_string()]
+        );
+    }
+
+    #[test]
+    fn binding_for_operation() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert_eq!(
+            wsdl.binding_for_operation("GetLastTradePrice")
+                .map(|b| b.name.as_str()),
+            Some("StockQuoteSoapBinding")
+        );
+        assert!(wsdl.binding_for_operation("GetHistory").is_none());
+    }
+
+    #[test]
+    fn add_operation() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        wsdl.add_type("History", Type::Simple(SimpleType::String))
+            .unwrap();
+        wsdl.add_message(
+            "GetHistoryInput",
+            Message {
+                part_name: "body".to_string(),
+                part_element: "History".to_string(),
+                part_prefix: None,
+                parts: vec![Part {
+                    name: "body".to_string(),
+                    element: "History".to_string(),
+                }],
+            },
+        )
+        .unwrap();
+        wsdl.add_operation(Operation {
+            name: "GetHistory".to_string(),
+            input: Some("GetHistoryInput".to_string()),
+            output: None,
+            faults: None,
+            input_name: None,
+            output_name: None,
+            soap_action: None,
+            documentation: None,
+            parameter_order: None,
+            extensions: HashMap::new(),
+        })
+        .unwrap();
+
+        assert_eq!(wsdl.operation_count(), 2);
+        assert_eq!(
+            wsdl.operation_signature("GetHistory").unwrap().input_type,
+            Some("GetHistoryInput".to_string())
+        );
+        assert_eq!(
+            wsdl.input_qname("GetHistory").unwrap().local_name,
+            "History"
+        );
+
+        let duplicate = Operation {
+            name: "GetLastTradePrice".to_string(),
+            input: None,
+            output: None,
+            faults: None,
+            input_name: None,
+            output_name: None,
+            soap_action: None,
+            documentation: None,
+            parameter_order: None,
+            extensions: HashMap::new(),
+        };
+        match wsdl.add_operation(duplicate) {
+            Err(WsdlError::DuplicateOperation(name)) => assert_eq!(name, "GetLastTradePrice"),
+            other => panic!("expected a duplicate operation, got {:?}", other),
+        }
+        let message = wsdl.messages["GetHistoryInput"].clone();
+        assert!(matches!(
+            wsdl.add_message("GetHistoryInput", message),
+            Err(WsdlError::DuplicateMessage(_))
+        ));
+        assert!(matches!(
+            wsdl.add_type("History", Type::Simple(SimpleType::Int)),
+            Err(WsdlError::DuplicateType(_))
+        ));
+    }
+
+    #[test]
+    fn output_parts() {
+        let wsdl = parse(
+            document(
+                "",
+                r#"
+  <message name="GetQuoteInput">
+    <part name="symbol" type="xsd:string"/>
+  </message>
+  <message name="GetQuoteOutput">
+    <part name="price" type="xsd:float"/>
+    <part name="currency" type="xsd:string"/>
+  </message>
+  <portType name="TestPortType">
+    <operation name="GetQuote">
+      <input message="tns:GetQuoteInput"/>
+      <output message="tns:GetQuoteOutput"/>
+    </operation>
+  </portType>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let parts = wsdl.output_parts("GetQuote");
+        let names = parts.iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["price", "currency"]);
+        assert!(wsdl.output_parts("Missing").is_empty());
+
+        let response = crate::rpser::Response::from_xml(
+            r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+  <soap:Body>
+    <GetQuoteResponse>
+      <price>12.5</price>
+      <currency>EUR</currency>
+    </GetQuoteResponse>
+  </soap:Body>
+</soap:Envelope>"#,
+        )
+        .unwrap();
+        let values = response
+            .parts(&parts)
+            .iter()
+            .map(|e| e.get_text().unwrap().into_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec!["12.5", "EUR"]);
+    }
+
+    #[test]
+    fn overloaded_operations() {
+        let wsdl = parse(
+            document(
+                "",
+                r#"<message name="ByIdInput"><part name="id" type="xsd:int"/></message>
+  <message name="ByNameInput"><part name="name" type="xsd:string"/></message>
+  <message name="FindOutput"><part name="result" type="xsd:string"/></message>
+  <portType name="TestPortType">
+    <operation name="Find">
+      <input name="FindById" message="tns:ByIdInput"/>
+      <output name="FindByIdResponse" message="tns:FindOutput"/>
+    </operation>
+    <operation name="Find">
+      <input name="FindByName" message="tns:ByNameInput"/>
+      <output name="FindByNameResponse" message="tns:FindOutput"/>
+    </operation>
+  </portType>
+  <binding name="TestBinding" type="tns:TestPortType">
+    <soap:binding style="rpc" transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="Find">
+      <soap:operation soapAction="urn:findByName"/>
+      <input name="FindByName"/>
+      <output name="FindByNameResponse"/>
+    </operation>
+    <operation name="Find">
+      <soap:operation soapAction="urn:findById"/>
+      <input name="FindById"/>
+      <output name="FindByIdResponse"/>
+    </operation>
+  </binding>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(wsdl.operations["Find"].input.as_deref(), Some("ByIdInput"));
+        let overloads = &wsdl.overloads["Find"];
+        assert_eq!(overloads.len(), 2);
+        assert_eq!(overloads[1].input_name.as_deref(), Some("FindByName"));
+        assert_eq!(
+            overloads[1].output_name.as_deref(),
+            Some("FindByNameResponse")
+        );
+
+        let by_name = wsdl.overload("Find", Some("FindByName")).unwrap();
+        assert_eq!(by_name.input.as_deref(), Some("ByNameInput"));
+        let by_id = wsdl.overload("Find", Some("FindById")).unwrap();
+        assert_eq!(by_id.input.as_deref(), Some("ByIdInput"));
+        assert!(wsdl.overload("Find", None).is_none());
+
+        // each overload takes the action of its own binding
+        assert_eq!(by_name.soap_action.as_deref(), Some("urn:findByName"));
+        assert_eq!(by_id.soap_action.as_deref(), Some("urn:findById"));
+        assert_eq!(
+            wsdl.operations["Find"].soap_action.as_deref(),
+            Some("urn:findById")
+        );
+        let binding = &wsdl.bindings["TestBinding"];
+        assert_eq!(binding.overloads["Find"].len(), 2);
+        let bound = binding
+            .overload("Find", Some("FindById"), Some("FindByIdResponse"))
+            .unwrap();
+        assert_eq!(bound.soap_action.as_deref(), Some("urn:findById"));
+        assert!(binding.overload("Find", Some("FindById"), None).is_none());
+    }
+
+    #[test]
+    fn soap_action_header() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let operation = &wsdl.operations["GetLastTradePrice"];
+        assert_eq!(
+            operation.soap_action.as_deref(),
+            Some("http://example.com/GetLastTradePrice")
+        );
+        assert_eq!(
+            operation.soap_action_header(true),
+            "\"http://example.com/GetLastTradePrice\""
+        );
+        assert_eq!(
+            operation.soap_action_header(false),
+            "http://example.com/GetLastTradePrice"
+        );
+
+        let operation = Operation {
+            soap_action: None,
+            ..operation.clone()
+        };
+        assert_eq!(operation.soap_action_header(true), "\"\"");
+        assert_eq!(operation.soap_action_header(false), "");
+    }
+
+    #[test]
+    fn used_primitives() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:simpleType name="Code">
+        <xsd:restriction base="xsd:string"/>
+      </xsd:simpleType>
+      <xsd:complexType name="Event">
+        <xsd:sequence>
+          <xsd:element name="id" type="xsd:int"/>
+          <xsd:element name="at" type="xsd:dateTime"/>
+          <xsd:element name="code" type="tns:Code"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let expected: HashSet<_> = vec!["string", "int", "dateTime"].into_iter().collect();
+        assert_eq!(wsdl.used_primitives(), expected);
+    }
+
+    #[test]
+    fn notation() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Picture">
+        <xsd:sequence>
+          <xsd:element name="format" type="xsd:NOTATION"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        match &wsdl.types["Picture"] {
+            Type::Complex(c) => assert_eq!(c.fields["format"].1, SimpleType::Notation),
+            t => panic!("expected a complex type, got {:?}", t),
+        }
+    }
+
+    #[test]
+    fn parse_enumerations() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:simpleType name="Status">
+        <xsd:restriction base="xsd:string">
+          <xsd:enumeration value="active"/>
+          <xsd:enumeration value="on hold"/>
+        </xsd:restriction>
+      </xsd:simpleType>
+      <xsd:simpleType name="Code">
+        <xsd:restriction base="xsd:string"/>
+      </xsd:simpleType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert!(
+            matches!(&wsdl.types["Status"], Type::Enumeration(v) if v == &["active", "on hold"])
+        );
+        assert!(matches!(
+            &wsdl.types["Code"],
+            Type::Simple(SimpleType::String)
+        ));
+    }
+
+    #[test]
+    fn choice_alternative() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Contact">
+        <xsd:choice>
+          <xsd:element name="email" type="xsd:string"/>
+          <xsd:element name="phone" type="xsd:string"/>
+        </xsd:choice>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+        let contact = match &wsdl.types["Contact"] {
+            Type::Complex(c) => c,
+            other => panic!("expected a complex type, got {:?}", other),
+        };
+        assert_eq!(contact.content_model, ContentModel::Choice);
+
+        let response = Element::parse(
+            r#"<Contact xmlns="http://example.com/test.wsdl"><phone>555-0100</phone></Contact>"#
+                .as_bytes(),
+        )
+        .unwrap();
+        let (field, value) = contact.choice(&response).unwrap();
+        assert_eq!(field, "phone");
+        assert_eq!(value.get_text().unwrap(), "555-0100");
+    }
+
+    #[test]
+    fn prefixed_wsdl_elements() {
+        let wsdl = parse(
+            br#"<?xml version="1.0"?>
+<wsdl:definitions name="Prefixed"
+                  targetNamespace="http://example.com/prefixed.wsdl"
+                  xmlns:tns="http://example.com/prefixed.wsdl"
+                  xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+                  xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+                  xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/">
+  <wsdl:types>
+    <xsd:schema targetNamespace="http://example.com/prefixed.wsdl">
+      <xsd:element name="Ping">
+        <xsd:complexType>
+          <xsd:sequence>
+            <xsd:element name="message" type="xsd:string"/>
+          </xsd:sequence>
+        </xsd:complexType>
+      </xsd:element>
+    </xsd:schema>
+  </wsdl:types>
+  <wsdl:message name="PingInput">
+    <wsdl:part name="body" element="tns:Ping"/>
+  </wsdl:message>
+  <wsdl:portType name="PingPortType">
+    <wsdl:operation name="Ping">
+      <wsdl:input message="tns:PingInput"/>
+    </wsdl:operation>
+  </wsdl:portType>
+  <wsdl:binding name="PingBinding" type="tns:PingPortType">
+    <soap:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+  </wsdl:binding>
+  <wsdl:service name="PingService">
+    <wsdl:port name="PingPort" binding="tns:PingBinding">
+      <soap:address location="http://example.com/ping"/>
+    </wsdl:port>
+  </wsdl:service>
+</wsdl:definitions>"#,
+        )
+        .unwrap();
+
+        assert_eq!(wsdl.name, "PingService");
+        assert!(matches!(wsdl.types["Ping"], Type::Complex(_)));
+        assert_eq!(wsdl.messages["PingInput"].part_element, "Ping");
+        assert_eq!(wsdl.operations["Ping"].input.as_deref(), Some("PingInput"));
+        assert_eq!(
+            wsdl.transports(),
+            vec!["http://schemas.xmlsoap.org/soap/http".to_string()]
+        );
+        assert_eq!(wsdl.primary_endpoint_url(), Some("http://example.com/ping"));
+    }
+
+    #[test]
+    fn reject_wsdl_2() {
+        match parse(WIKIPEDIA_WSDL) {
+            Err(WsdlError::UnsupportedWsdlVersion(namespace)) => {
+                assert_eq!(namespace, "http://www.w3.org/ns/wsdl")
+            }
+            other => panic!("expected an unsupported version, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reject_entity_expansion() {
+        let entities = (1..10)
+            .map(|i| {
+                format!(
+                    "<!ENTITY lol{} \"{}\">",
+                    i,
+                    format!("&lol{};", i - 1).repeat(10)
+                )
+            })
+            .collect::<String>();
+        let bomb = format!(
+            r#"<?xml version="1.0"?>
+<!DOCTYPE definitions [<!ENTITY lol0 "lol">{}]>
+<definitions name="Bomb" targetNamespace="http://example.com/bomb.wsdl"
+             xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <documentation>&lol9;</documentation>
+</definitions>"#,
+            entities
+        );
+        match parse(bomb.as_bytes()) {
+            Err(WsdlError::SecurityLimitExceeded(reason)) => {
+                assert_eq!(reason, "entity declarations in DOCTYPE")
+            }
+            other => panic!("expected the document to be rejected, got {:?}", other),
+        }
+
+        let options = ParseOptions {
+            max_input_size: Some(1024),
+            ..Default::default()
+        };
+        assert!(matches!(
+            parse_with_options(EXAMPLE_WSDL, &options),
+            Err(WsdlError::SecurityLimitExceeded(_))
+        ));
+        let options = ParseOptions {
+            max_input_size: Some(EXAMPLE_WSDL.len()),
+            ..Default::default()
+        };
+        assert!(parse_with_options(EXAMPLE_WSDL, &options).is_ok());
+    }
+
+    #[test]
+    fn parse_named_faults() {
+        let wsdl = parse(
+            document(
+                "",
+                r#"
+  <portType name="TestPortType">
+    <operation name="GetCustomer">
+      <input message="tns:GetCustomerInput"/>
+      <output message="tns:GetCustomerOutput"/>
+      <fault name="invalid_input" message="tns:InvalidInputFault"/>
+    </operation>
+  </portType>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            wsdl.operations["GetCustomer"].faults,
+            Some(vec![FaultRef {
+                name: "invalid_input".to_string(),
+                message: "InvalidInputFault".to_string(),
+            }])
+        );
+        let operation = &wsdl.operations["GetCustomer"];
+        assert!(operation.has_fault("InvalidInputFault"));
+        assert!(operation.has_fault("tns:InvalidInputFault"));
+        assert!(!operation.has_fault("invalid_input"));
+        assert!(!operation.has_fault("NotFoundFault"));
+        let without_faults = parse(EXAMPLE_WSDL).unwrap();
+        assert!(!without_faults.operations["GetLastTradePrice"].has_fault("InvalidInputFault"));
+    }
+
+    #[test]
+    fn fault_names() {
+        let wsdl = parse(
+            document(
+                "",
+                r#"
+  <portType name="TestPortType">
+    <operation name="GetCustomer">
+      <input message="tns:GetCustomerInput"/>
+      <output message="tns:GetCustomerOutput"/>
+      <fault name="invalid_input" message="tns:InvalidInputFault"/>
+      <fault name="not_found" message="tns:NotFoundFault"/>
+    </operation>
+  </portType>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            wsdl.operations["GetCustomer"].fault_names(),
+            vec!["invalid_input", "not_found"]
+        );
+        let without_faults = parse(EXAMPLE_WSDL).unwrap();
+        assert!(without_faults.operations["GetLastTradePrice"]
+            .fault_names()
+            .is_empty());
+    }
+
+    #[test]
+    fn operation_signature() {
+        let wsdl = parse(
+            document(
+                "",
+                r#"
+  <portType name="TestPortType">
+    <operation name="GetCustomer">
+      <input message="tns:GetCustomerInput"/>
+      <output message="tns:GetCustomerOutput"/>
+      <fault name="invalid_input" message="tns:InvalidInputFault"/>
+    </operation>
+  </portType>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            wsdl.operation_signature("GetCustomer"),
+            Some(OperationSignature {
+                name: "get_customer".to_string(),
+                input_type: Some("GetCustomerInput".to_string()),
+                output_type: Some("GetCustomerOutput".to_string()),
+                fault_types: vec!["InvalidInputFault".to_string()],
+            })
+        );
+        assert_eq!(wsdl.operation_signature("Unknown"), None);
+    }
+
+    #[test]
+    fn endpoint_url() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert_eq!(wsdl.endpoints[0].binding, "StockQuoteSoapBinding");
+        assert_eq!(
+            wsdl.primary_endpoint_url(),
+            Some("http://example.com/stockquote")
+        );
+        assert_eq!(
+            wsdl.require_endpoint_url().unwrap(),
+            "http://example.com/stockquote"
+        );
+
+        let wsdl = parse(document("", r#"<portType name="TestPortType"/>"#).as_bytes()).unwrap();
+        assert_eq!(wsdl.primary_endpoint_url(), None);
+        assert!(matches!(
+            wsdl.require_endpoint_url(),
+            Err(WsdlError::ElementNotFound("address"))
+        ));
+    }
+
+    #[test]
+    fn parse_directory() {
+        let dir = std::env::temp_dir().join(format!("savon-directory-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("b.wsdl"), EXAMPLE_WSDL).unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a wsdl").unwrap();
+
+        let (wsdls, failures) = super::parse_directory(&dir);
+        assert_eq!(wsdls.len(), 1);
+        assert_eq!(wsdls[0].1.name, "StockQuoteService");
+        assert!(failures.is_empty());
+
+        // failures do not hide the documents parsed along them
+        std::fs::write(dir.join("a.wsdl"), "<definitions").unwrap();
+        std::fs::write(dir.join("c.wsdl"), "<definitions").unwrap();
+        std::fs::write(dir.join("d.wsdl"), EXAMPLE_WSDL).unwrap();
+        let (wsdls, failures) = super::parse_directory(&dir);
+        assert_eq!(
+            wsdls
+                .iter()
+                .map(|(path, _)| path.clone())
+                .collect::<Vec<_>>(),
+            vec![dir.join("b.wsdl"), dir.join("d.wsdl")]
+        );
+        assert_eq!(
+            failures
+                .iter()
+                .map(|(path, _)| path.clone())
+                .collect::<Vec<_>>(),
+            vec![dir.join("a.wsdl"), dir.join("c.wsdl")]
+        );
+        assert!(matches!(failures[0].1, WsdlError::Parse(_)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        let (wsdls, failures) = super::parse_directory(&dir);
+        assert!(wsdls.is_empty());
+        assert!(matches!(failures[..], [(_, WsdlError::Io(_))]));
+    }
+
+    #[test]
+    fn default_endpoint() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert_eq!(wsdl.endpoints[0].soap_version, Some(SoapVersion::Soap11));
+        assert_eq!(
+            wsdl.default_endpoint(),
+            Some("http://example.com/stockquote")
+        );
+
+        let source = document("", r#"<portType name="TestPortType"/>"#).replace(
+            r#"<service name="TestService"/>"#,
+            r#"<service name="TestService">
+    <port name="HttpPort" binding="tns:TestHttpBinding">
+      <http:address xmlns:http="http://schemas.xmlsoap.org/wsdl/http/" location="http://example.com/http"/>
+    </port>
+    <port name="Soap11Port" binding="tns:TestSoapBinding">
+      <soap:address xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/" location="http://example.com/soap11"/>
+    </port>
+    <port name="Soap12Port" binding="tns:TestSoap12Binding">
+      <soap12:address xmlns:soap12="http://schemas.xmlsoap.org/wsdl/soap12/" location="http://example.com/soap12"/>
+    </port>
+  </service>
+  <service name="OtherService">
+    <port name="OtherPort" binding="tns:TestSoap12Binding">
+      <soap12:address xmlns:soap12="http://schemas.xmlsoap.org/wsdl/soap12/" location="http://example.com/other"/>
+    </port>
+  </service>"#,
+        );
+        let wsdl = parse(source.as_bytes()).unwrap();
+        assert_eq!(wsdl.primary_endpoint_url(), Some("http://example.com/http"));
+        assert_eq!(wsdl.default_endpoint(), Some("http://example.com/soap12"));
+    }
+
+    #[test]
+    fn repeated_fields() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:element name="Order">
+        <xsd:complexType>
+          <xsd:sequence>
+            <xsd:element name="id" type="xsd:int" minOccurs="1" maxOccurs="1"/>
+            <xsd:element name="note" type="xsd:string" minOccurs="0" maxOccurs="1"/>
+            <xsd:element name="items" type="tns:Item" maxOccurs="unbounded"/>
+          </xsd:sequence>
+        </xsd:complexType>
+      </xsd:element>
+      <xsd:complexType name="Item">
+        <xsd:sequence>
+          <xsd:element name="sku" type="xsd:string"/>
+          <xsd:element name="tags" type="xsd:string" minOccurs="0" maxOccurs="3"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<message name="OrderInput">
+    <part name="body" element="tns:Order"/>
+  </message>
+  <portType name="TestPortType">
+    <operation name="PlaceOrder">
+      <input message="tns:OrderInput"/>
+    </operation>
+  </portType>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        match &wsdl.types["Order"] {
+            Type::Complex(c) => {
+                let (id, _) = &c.fields["id"];
+                assert!(id.min_occurs.is_none() && id.max_occurs.is_none() && !id.nillable);
+                let (note, _) = &c.fields["note"];
+                assert!(note.max_occurs.is_none() && note.nillable);
+                let (items, _) = &c.fields["items"];
+                assert!(matches!(items.max_occurs, Some(Occurence::Unbounded)));
+            }
+            t => panic!("expected a complex type, got {:?}", t),
+        }
+
+        let schema = wsdl.input_schema("PlaceOrder").unwrap();
+        assert_eq!(schema.name, "Order");
+        let names = schema
+            .children
+            .iter()
+            .map(|n| (n.name.as_str(), n.repeated))
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![("id", false), ("items", true), ("note", false)]);
+        let items = &schema.children[1];
+        assert!(items.children[1].repeated);
+        assert_eq!(items.children[1].name, "tags");
+    }
+
+    #[test]
+    fn field_documentation() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Customer">
+        <xsd:sequence>
+          <xsd:element name="id" type="xsd:int">
+            <xsd:annotation>
+              <xsd:documentation>
+                Identifier assigned at registration.
+              </xsd:documentation>
+            </xsd:annotation>
+          </xsd:element>
+          <xsd:element name="name" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        match &wsdl.types["Customer"] {
+            Type::Complex(c) => {
+                assert_eq!(
+                    c.fields["id"].0.documentation.as_deref(),
+                    Some("Identifier assigned at registration.")
+                );
+                assert_eq!(c.fields["name"].0.documentation, None);
+            }
+            t => panic!("expected a complex type, got {:?}", t),
+        }
+    }
+
+    #[test]
+    fn recover_from_errors() {
+        let bytes = document(
+            r#"
+      <xsd:group name="Contact">
+        <xsd:sequence>
+          <xsd:element name="email" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:group>
+      <xsd:complexType name="Customer">
+        <xsd:sequence>
+          <xsd:element name="name" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+            r#"<message name="Broken"/>
+  <message name="CustomerInput">
+    <part name="body" element="tns:Customer"/>
+  </message>
+  <portType name="TestPortType">
+    <operation name="AddCustomer">
+      <input message="tns:CustomerInput"/>
+    </operation>
+  </portType>"#,
+        );
+
+        assert!(matches!(
+            parse(bytes.as_bytes()),
+            Err(WsdlError::UnsupportedType(name)) if name == "Contact"
+        ));
+
+        let (wsdl, errors) = parse_with_recovery(bytes.as_bytes());
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(&errors[0], WsdlError::UnsupportedType(name) if name == "Contact"));
+        assert!(matches!(errors[1], WsdlError::ElementNotFound("part")));
+        assert!(wsdl.types.contains_key("Customer"));
+        assert!(!wsdl.types.contains_key("Contact"));
+        assert!(wsdl.messages.contains_key("CustomerInput"));
+        assert!(wsdl.operations.contains_key("AddCustomer"));
+        assert_eq!(wsdl.name, "TestService");
+
+        let (wsdl, errors) = parse_with_recovery(b"<definitions");
+        assert!(matches!(errors[..], [WsdlError::Parse(_)]));
+        assert!(wsdl.operations.is_empty());
+    }
+
+    #[test]
+    fn recover_from_invalid_occurences() {
+        let bytes = document(
+            r#"
+      <xsd:complexType name="Order">
+        <xsd:sequence>
+          <xsd:element name="line" type="xsd:string" maxOccurs="-1"/>
+        </xsd:sequence>
+      </xsd:complexType>
+      <xsd:complexType name="Customer">
+        <xsd:sequence minOccurs="none">
+          <xsd:element name="name" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>
+      <xsd:complexType name="Address">
+        <xsd:sequence>
+          <xsd:element name="city" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+            r#"<portType name="TestPortType"/>"#,
+        );
+
+        let (wsdl, errors) = parse_with_recovery(bytes.as_bytes());
+        let mut invalid = errors
+            .iter()
+            .map(|e| match e {
+                WsdlError::InvalidOccurence(n) => n.as_str(),
+                other => panic!("expected an invalid occurence, got {:?}", other),
+            })
+            .collect::<Vec<_>>();
+        invalid.sort();
+        assert_eq!(invalid, vec!["-1", "none"]);
+        assert!(wsdl.types.contains_key("Address"));
+        assert!(!wsdl.types.contains_key("Order"));
+        assert!(!wsdl.types.contains_key("Customer"));
+    }
+
+    #[test]
+    fn input_qname() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let qname = wsdl.input_qname("GetLastTradePrice").unwrap();
+        assert_eq!(
+            qname,
+            QName {
+                prefix: Some("xsd1".to_string()),
+                namespace: Some("http://example.com/stockquote.xsd".to_string()),
+                local_name: "TradePriceRequest".to_string(),
+            }
+        );
+        assert_eq!(qname.to_string(), "xsd1:TradePriceRequest");
+        assert_eq!(wsdl.input_qname("Unknown"), None);
+    }
+
+    #[test]
+    fn operation_for_input_element() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        let (name, message) = MessageBuilder::new()
+            .name("GetHistoryInput")
+            .part("parameters", "GetHistory")
+            .build();
+        wsdl.add_message(&name, message).unwrap();
+        wsdl.add_operation(
+            OperationBuilder::new()
+                .name("GetHistory")
+                .input("GetHistoryInput")
+                .build(),
+        )
+        .unwrap();
+
+        let operation = |element: &str| {
+            wsdl.operation_for_input_element(element)
+                .map(|o| o.name.as_str())
+        };
+        assert_eq!(operation("TradePriceRequest"), Some("GetLastTradePrice"));
+        assert_eq!(
+            operation("xsd1:TradePriceRequest"),
+            Some("GetLastTradePrice")
+        );
+        assert_eq!(operation("GetHistory"), Some("GetHistory"));
+        // wrapper element named after the operation
+        assert_eq!(operation("GetLastTradePrice"), Some("GetLastTradePrice"));
+        assert_eq!(operation("TradePrice"), None);
+    }
+
+    #[test]
+    fn duplicate_types() {
+        let bytes = document(
+            r#"
+      <xsd:complexType name="Address">
+        <xsd:sequence>
+          <xsd:element name="city" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>
+      <xsd:element name="Customer" type="tns:Customer"/>
+      <xsd:complexType name="Customer">
+        <xsd:sequence>
+          <xsd:element name="name" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>
+    </xsd:schema>
+    <xsd:schema targetNamespace="http://example.com/other.xsd">
+      <xsd:complexType name="Address">
+        <xsd:sequence>
+          <xsd:element name="street" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+            r#"<portType name="TestPortType"/>"#,
+        );
+        let fields = |t: &Type| match t {
+            Type::Complex(c) => c.fields.keys().cloned().collect::<Vec<_>>(),
+            t => panic!("expected a complex type, got {:?}", t),
+        };
+        let parse = |duplicate_strategy| {
+            parse_with_options(
+                bytes.as_bytes(),
+                &ParseOptions {
+                    duplicate_strategy,
+                    ..Default::default()
+                },
+            )
+        };
+
+        assert!(matches!(
+            parse(DuplicateStrategy::Error),
+            Err(WsdlError::DuplicateType(name)) if name == "Address"
+        ));
+
+        let wsdl = parse(DuplicateStrategy::KeepFirst).unwrap();
+        assert_eq!(fields(&wsdl.types["Address"]), vec!["city"]);
+        assert_eq!(wsdl.overridden_types.len(), 1);
+        assert_eq!(wsdl.overridden_types[0].0, "Address");
+        assert_eq!(fields(&wsdl.overridden_types[0].1), vec!["street"]);
+        // an element declared with the type of the same name is not a duplicate
+        assert_eq!(fields(&wsdl.types["Customer"]), vec!["name"]);
+
+        let wsdl = parse(DuplicateStrategy::KeepLast).unwrap();
+        assert_eq!(fields(&wsdl.types["Address"]), vec!["street"]);
+        assert_eq!(wsdl.overridden_types.len(), 1);
+        assert_eq!(fields(&wsdl.overridden_types[0].1), vec!["city"]);
+    }
+
+    #[test]
+    fn diff() {
+        let previous = parse(EXAMPLE_WSDL).unwrap();
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert!(wsdl.diff(&previous).is_empty());
+
+        wsdl.types.remove("TradePriceRequest");
+        wsdl.types
+            .insert("Ticker".to_string(), Type::Simple(SimpleType::String));
+        if let Some(m) = wsdl.messages.get_mut("GetLastTradePriceOutput") {
+            m.part_element = "Ticker".to_string();
+        }
+
+        assert_eq!(
+            wsdl.diff(&previous),
+            WsdlDiff {
+                added_types: vec!["Ticker".to_string()],
+                removed_types: vec!["TradePriceRequest".to_string()],
+                changed_operations: vec!["GetLastTradePrice".to_string()],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn semantically_equal() {
+        let types = r#"
+      <xsd:complexType name="Account">
+        <xsd:sequence>
+          <xsd:element name="id" type="xsd:int"/>
+          <xsd:element name="label" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>
+      <xsd:element name="Lookup" type="tns:Account"/>"#;
+        let definitions = r#"
+  <message name="LookupInput">
+    <part name="body" element="tns:Lookup"/>
+  </message>
+  <message name="Missing">
+    <part name="body" element="tns:Account"/>
+  </message>
+  <portType name="TestPortType">
+    <operation name="Lookup">
+      <input message="tns:LookupInput"/>
+      <fault name="missing" message="tns:Missing"/>
+      <fault name="denied" message="tns:Missing"/>
+    </operation>
+  </portType>"#;
+        let wsdl = parse(document(types, definitions).as_bytes()).unwrap();
+
+        let reordered = document(
+            r#"
+      <s:element name="Lookup" type="t:Account"/>
+      <s:complexType name="Account">
+        <s:sequence>
+          <s:element name="label" type="s:string"/>
+          <s:element name="id" type="s:int"/>
+        </s:sequence>
+      </s:complexType>"#,
+            &definitions.replace("tns:", "t:").replace(
+                r#"<fault name="missing" message="t:Missing"/>
+      <fault name="denied" message="t:Missing"/>"#,
+                r#"<fault name="denied" message="t:Missing"/>
+      <fault name="missing" message="t:Missing"/>"#,
+            ),
+        )
+        .replace("xmlns:tns=", "xmlns:t=")
+        .replace("xmlns:xsd=", "xmlns:s=")
+        .replace("xsd:schema", "s:schema");
+        let other = parse(reordered.as_bytes()).unwrap();
+        assert_eq!(
+            other.messages["LookupInput"].part_prefix.as_deref(),
+            Some("t")
+        );
+        assert!(wsdl.semantically_equal(&other));
+
+        let changed = document(&types.replace("xsd:string", "xsd:int"), definitions);
+        let other = parse(changed.as_bytes()).unwrap();
+        assert!(!wsdl.semantically_equal(&other));
+    }
 
     #[test]
-    fn parse_example() {
-        let res = parse(EXAMPLE_WSDL);
-        println!("res: {:?}", res);
-        res.unwrap();
+    fn semantically_equal_extensions() {
+        let source = |prefix: &str| {
+            document(
+                r#"
+      <xsd:complexType name="Account">
+        <xsd:sequence>
+          <xsd:element name="id" type="xsd:int">
+            <xsd:annotation><xsd:appinfo><PREFIX:hint xmlns:PREFIX="http://example.com/acme"/></xsd:appinfo></xsd:annotation>
+          </xsd:element>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType">
+    <operation name="Lookup">
+      <PREFIX:routing xmlns:PREFIX="http://example.com/acme">
+        <PREFIX:queue>accounts</PREFIX:queue>
+      </PREFIX:routing>
+    </operation>
+  </portType>"#,
+            )
+            .replace("PREFIX", prefix)
+        };
+        let wsdl = parse(source("acme").as_bytes()).unwrap();
+        let other = parse(source("a").as_bytes()).unwrap();
+        assert_ne!(
+            wsdl.operations["Lookup"].extensions,
+            other.operations["Lookup"].extensions
+        );
+        assert!(wsdl.semantically_equal(&other));
+
+        let moved = source("a").replace(
+            r#"<a:routing xmlns:a="http://example.com/acme">"#,
+            r#"<a:routing xmlns:a="http://example.com/other">"#,
+        );
+        let other = parse(moved.as_bytes()).unwrap();
+        assert!(!wsdl.semantically_equal(&other));
+    }
+
+    #[test]
+    fn schema_without_types() {
+        let source = document(
+            r#"
+      <xsd:complexType name="Picture">
+        <xsd:sequence>
+          <xsd:element name="width" type="xsd:int"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+            r#"<portType name="TestPortType"/>"#,
+        )
+        .replace("<types>", "")
+        .replace("</types>", "");
+        let wsdl = parse(source.as_bytes()).unwrap();
+        assert!(wsdl.types.contains_key("Picture"));
+
+        let source = document("", r#"<portType name="TestPortType"/>"#)
+            .replace("<types>", "")
+            .replace("</types>", "")
+            .replace("<xsd:schema", "<xsd:annotation")
+            .replace("</xsd:schema>", "</xsd:annotation>");
+        match parse(source.as_bytes()) {
+            Err(WsdlError::ElementNotFound("types")) => {}
+            other => panic!("expected a missing types error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nested_compositors() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Payment">
+        <xsd:sequence>
+          <xsd:element name="amount" type="xsd:int"/>
+          <xsd:choice>
+            <xsd:element name="card" type="xsd:string"/>
+            <xsd:sequence>
+              <xsd:element name="iban" type="xsd:string"/>
+              <xsd:element name="bic" type="xsd:string"/>
+            </xsd:sequence>
+          </xsd:choice>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let fields = match &wsdl.types["Payment"] {
+            Type::Complex(c) => &c.fields,
+            other => panic!("expected a complex type, got {:?}", other),
+        };
+        let mut names = fields.keys().map(|k| k.as_str()).collect::<Vec<_>>();
+        names.sort_unstable();
+        assert_eq!(names, vec!["amount", "bic", "card", "iban"]);
+
+        assert_eq!(fields["amount"].0.compositor, None);
+        assert!(!fields["amount"].0.nillable);
+        assert_eq!(fields["card"].0.compositor, Some(ContentModel::Choice));
+        assert!(fields["card"].0.nillable);
+        assert_eq!(fields["iban"].0.compositor, Some(ContentModel::Sequence));
+        assert!(fields["iban"].0.nillable);
+    }
+
+    #[test]
+    fn attribute_fields() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Price">
+        <xsd:sequence>
+          <xsd:element name="amount" type="xsd:float"/>
+        </xsd:sequence>
+        <xsd:attribute name="currency" type="xsd:string" use="required"/>
+        <xsd:attribute name="rounded" type="xsd:boolean"/>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        match &wsdl.types["Price"] {
+            Type::Complex(c) => {
+                assert!(!c.is_attribute("amount"));
+                assert!(c.is_attribute("currency"));
+                assert!(c.is_attribute("rounded"));
+                assert!(!c.is_attribute("missing"));
+                assert!(!c.fields["currency"].0.nillable);
+                assert!(c.fields["rounded"].0.nillable);
+                assert_eq!(c.fields["rounded"].1, SimpleType::Boolean);
+            }
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn whitespace_and_comments() {
+        let wsdl = parse(
+            document(
+                r#"
+
+      <!-- contact details -->
+      <xsd:complexType name="Contact">
+
+        <!-- fields in display order -->
+        <xsd:sequence>
+
+          <xsd:element name="name"      type="xsd:string"/>
+          <!-- optional -->
+          <xsd:element   name="phone"   type="xsd:string"   minOccurs="0"  />
+
+          <?editor folded?>
+          <xsd:element name="tags" type="xsd:string" maxOccurs="unbounded">
+            <!-- free text -->
+            <xsd:annotation>
+              <xsd:documentation>
+                Labels of the contact.
+              </xsd:documentation>
+            </xsd:annotation>
+          </xsd:element>
+
+        </xsd:sequence>
+
+      </xsd:complexType>
+
+"#,
+                r#"
+  <message name="ContactInput">
+
+    <!-- the only part -->
+    <part name="body" element="tns:Contact"/>
+  </message>
+  <portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        match &wsdl.types["Contact"] {
+            Type::Complex(c) => {
+                let mut names = c.fields.keys().map(|n| n.as_str()).collect::<Vec<_>>();
+                names.sort();
+                assert_eq!(names, vec!["name", "phone", "tags"]);
+                assert_eq!(c.fields["name"].1, SimpleType::String);
+                assert!(c.fields["phone"].0.nillable);
+                assert!(c.fields["tags"].0.is_repeated());
+                assert_eq!(
+                    c.fields["tags"].0.documentation.as_deref(),
+                    Some("Labels of the contact.")
+                );
+            }
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+        assert_eq!(wsdl.messages["ContactInput"].part_element, "Contact");
+
+        let response = crate::rpser::Response::from_xml(
+            r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+
+  <soap:Body>
+    <!-- generated by the server -->
+
+    <ContactResponse>   </ContactResponse>
+  </soap:Body>
+</soap:Envelope>"#,
+        )
+        .unwrap();
+        assert_eq!(response.body.name, "ContactResponse");
+    }
+
+    #[test]
+    fn recommended_fields() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Signup">
+        <xsd:sequence>
+          <xsd:element name="email" type="xsd:string"/>
+          <xsd:element name="phone" type="xsd:string" minOccurs="0">
+            <xsd:annotation>
+              <xsd:documentation>Used for delivery notices.</xsd:documentation>
+              <xsd:appinfo><recommended/></xsd:appinfo>
+            </xsd:annotation>
+          </xsd:element>
+          <xsd:element name="company" type="xsd:string" minOccurs="0">
+            <xsd:annotation>
+              <xsd:appinfo><internal/></xsd:appinfo>
+            </xsd:annotation>
+          </xsd:element>
+        </xsd:sequence>
+        <xsd:attribute name="locale" type="xsd:string">
+          <xsd:annotation>
+            <xsd:appinfo><recommended/></xsd:appinfo>
+          </xsd:annotation>
+        </xsd:attribute>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        match &wsdl.types["Signup"] {
+            Type::Complex(c) => {
+                assert_eq!(c.recommended_fields(), vec!["locale", "phone"]);
+                assert_eq!(c.fields["company"].0.appinfo[0].name, "internal");
+                assert!(c.fields["email"].0.appinfo.is_empty());
+            }
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nillable_warnings() {
+        let source = document(
+            r#"
+      <xsd:complexType name="Contact">
+        <xsd:sequence>
+          <xsd:element name="name" type="xsd:string"/>
+          <xsd:element name="phone" type="xsd:string" minOccurs="0"/>
+          <xsd:element name="fax" type="xsd:string" minOccurs="0" nillable="true"/>
+          <xsd:element name="email" type="xsd:string" minOccurs="0" maxOccurs="unbounded"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+            r#"<portType name="TestPortType"/>"#,
+        );
+
+        let options = ParseOptions {
+            collect_warnings: true,
+            ..Default::default()
+        };
+        let wsdl = parse_with_options(source.as_bytes(), &options).unwrap();
+        assert_eq!(
+            wsdl.warnings,
+            vec![WsdlWarning::NillableNormalized {
+                type_name: "Contact".to_string(),
+                field: "phone".to_string(),
+            }]
+        );
+
+        assert!(parse(source.as_bytes()).unwrap().warnings.is_empty());
+    }
+
+    #[test]
+    fn numeric_nillable() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Contact">
+        <xsd:sequence>
+          <xsd:element name="fax" type="xsd:string" nillable="1"/>
+          <xsd:element name="pager" type="xsd:string" nillable="0"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        match &wsdl.types["Contact"] {
+            Type::Complex(c) => {
+                assert!(c.fields["fax"].0.nillable);
+                assert!(!c.fields["pager"].0.nillable);
+            }
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+
+        let source = document(
+            r#"
+      <xsd:complexType name="Contact">
+        <xsd:sequence>
+          <xsd:element name="fax" type="xsd:string" nillable="yes"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+            r#"<portType name="TestPortType"/>"#,
+        );
+        assert!(matches!(
+            parse(source.as_bytes()),
+            Err(WsdlError::InvalidNillable(n)) if n == "yes"
+        ));
+    }
+
+    #[test]
+    fn schema_version() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert_eq!(wsdl.schema_version, XsdVersion::V1_0);
+
+        let schema = r#"
+      <xsd:complexType name="Period">
+        <xsd:sequence>
+          <xsd:element name="start" type="xsd:int"/>
+          <xsd:element name="end" type="xsd:int"/>
+        </xsd:sequence>
+        <xsd:assert test="start &lt;= end"/>
+      </xsd:complexType>"#;
+        let source = document(schema, r#"<portType name="TestPortType"/>"#);
+        let options = ParseOptions {
+            collect_warnings: true,
+            ..Default::default()
+        };
+        let wsdl = parse_with_options(source.as_bytes(), &options).unwrap();
+        assert_eq!(wsdl.schema_version, XsdVersion::V1_1);
+        assert_eq!(
+            wsdl.warnings,
+            vec![WsdlWarning::UndeclaredXsd11Feature("assert".to_string())]
+        );
+        assert!(parse(source.as_bytes()).unwrap().warnings.is_empty());
+
+        let declared = source.replace(
+            r#"<xsd:schema targetNamespace="http://example.com/test.wsdl">"#,
+            r#"<xsd:schema targetNamespace="http://example.com/test.wsdl"
+                xmlns:vc="http://www.w3.org/2007/XMLSchema-versioning" vc:minVersion="1.1">"#,
+        );
+        let wsdl = parse_with_options(declared.as_bytes(), &options).unwrap();
+        assert_eq!(wsdl.schema_version, XsdVersion::V1_1);
+        assert!(wsdl.warnings.is_empty());
+    }
+
+    #[test]
+    fn imported_port_type() {
+        let interface = r#"<?xml version="1.0"?>
+<definitions targetNamespace="http://example.com/quotes/interface"
+             xmlns:intf="http://example.com/quotes/interface"
+             xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+             xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <types>
+    <xsd:schema targetNamespace="http://example.com/quotes/interface">
+      <xsd:element name="GetQuote">
+        <xsd:complexType>
+          <xsd:sequence>
+            <xsd:element name="symbol" type="xsd:string"/>
+          </xsd:sequence>
+        </xsd:complexType>
+      </xsd:element>
+      <xsd:element name="Quote" type="xsd:float"/>
+    </xsd:schema>
+  </types>
+  <message name="GetQuoteInput">
+    <part name="body" element="intf:GetQuote"/>
+  </message>
+  <message name="GetQuoteOutput">
+    <part name="body" element="intf:Quote"/>
+  </message>
+  <portType name="QuotePortType">
+    <operation name="GetQuote">
+      <input message="intf:GetQuoteInput"/>
+      <output message="intf:GetQuoteOutput"/>
+    </operation>
+  </portType>
+</definitions>"#;
+        // a port type and a message of the same names, in another namespace
+        let legacy = r#"<?xml version="1.0"?>
+<definitions targetNamespace="http://example.com/quotes/legacy"
+             xmlns:legacy="http://example.com/quotes/legacy"
+             xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <message name="GetQuoteInput">
+    <part name="body" element="legacy:LegacyQuote"/>
+  </message>
+  <portType name="QuotePortType">
+    <operation name="GetLegacyQuote"/>
+  </portType>
+</definitions>"#;
+        let source = r#"<?xml version="1.0"?>
+<definitions name="Quotes"
+             targetNamespace="http://example.com/quotes/service"
+             xmlns:tns="http://example.com/quotes/service"
+             xmlns:intf="http://example.com/quotes/interface"
+             xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+             xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <import namespace="http://example.com/quotes/interface" location="interface.wsdl"/>
+  <import namespace="http://example.com/quotes/legacy" location="legacy.wsdl"/>
+  <binding name="QuoteBinding" type="intf:QuotePortType">
+    <soap:binding style="document" transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="GetQuote">
+      <soap:operation soapAction="http://example.com/GetQuote"/>
+    </operation>
+  </binding>
+  <service name="QuoteService">
+    <port name="QuotePort" binding="tns:QuoteBinding">
+      <soap:address location="http://example.com/quotes"/>
+    </port>
+  </service>
+</definitions>"#;
+
+        let wsdl = parse_with_resolver(source.as_bytes(), |location| match location {
+            "interface.wsdl" => Some(interface.as_bytes().to_vec()),
+            "legacy.wsdl" => Some(legacy.as_bytes().to_vec()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(wsdl.name, "QuoteService");
+        assert!(wsdl.has_operation("GetQuote"));
+        assert!(wsdl.has_message("GetQuoteInput"));
+        assert_eq!(wsdl.messages["GetQuoteInput"].part_element, "GetQuote");
+        assert!(wsdl.has_message("GetQuoteOutput"));
+        assert!(wsdl.has_type("GetQuote"));
+        assert_eq!(
+            wsdl.operations["GetQuote"].soap_action.as_deref(),
+            Some("http://example.com/GetQuote")
+        );
+        assert_eq!(wsdl.bindings["QuoteBinding"].port_type, "QuotePortType");
+        assert!(!wsdl.has_operation("GetLegacyQuote"));
+
+        match parse_with_resolver(source.as_bytes(), |location| {
+            Some(legacy.as_bytes().to_vec()).filter(|_| location == "legacy.wsdl")
+        }) {
+            Err(WsdlError::ImportNotFound(location)) => assert_eq!(location, "interface.wsdl"),
+            other => panic!("expected an import error, got {:?}", other),
+        }
+
+        // a port type missing from the namespace it is referenced in
+        let missing = source.replace("intf:QuotePortType", "intf:MissingPortType");
+        match parse_with_resolver(missing.as_bytes(), |location| match location {
+            "interface.wsdl" => Some(interface.as_bytes().to_vec()),
+            "legacy.wsdl" => Some(legacy.as_bytes().to_vec()),
+            _ => None,
+        }) {
+            Err(WsdlError::ElementNotFound("portType")) => {}
+            other => panic!("expected a missing port type, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn multiple_compositors() {
+        let source = document(
+            r#"
+      <xsd:complexType name="Broken">
+        <xsd:sequence>
+          <xsd:element name="id" type="xsd:int"/>
+        </xsd:sequence>
+        <xsd:sequence>
+          <xsd:element name="label" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+            r#"<portType name="TestPortType"/>"#,
+        );
+        match parse(source.as_bytes()) {
+            Err(WsdlError::MultipleChildren("complexType")) => {}
+            other => panic!("expected a multiple children error, got {:?}", other),
+        }
+
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Documented">
+        <xsd:annotation><xsd:documentation>Valid type.</xsd:documentation></xsd:annotation>
+        <xsd:sequence>
+          <xsd:element name="id" type="xsd:int"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+        assert!(
+            matches!(&wsdl.types["Documented"], Type::Complex(c) if c.fields.contains_key("id"))
+        );
+    }
+
+    #[test]
+    fn inline_simple_types() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Account">
+        <xsd:sequence>
+          <xsd:element name="Status">
+            <xsd:simpleType>
+              <xsd:restriction base="xsd:string">
+                <xsd:enumeration value="Active"/>
+                <xsd:enumeration value="Inactive"/>
+              </xsd:restriction>
+            </xsd:simpleType>
+          </xsd:element>
+          <xsd:element name="label">
+            <xsd:simpleType>
+              <xsd:restriction base="xsd:string"/>
+            </xsd:simpleType>
+          </xsd:element>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        match &wsdl.types["Account"] {
+            Type::Complex(c) => {
+                assert_eq!(
+                    c.fields["Status"].1,
+                    SimpleType::Complex("AccountStatus".to_string())
+                );
+                assert_eq!(c.fields["label"].1, SimpleType::String);
+            }
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+        assert_eq!(
+            wsdl.types["AccountStatus"],
+            Type::Enumeration(vec!["Active".to_string(), "Inactive".to_string()])
+        );
+    }
+
+    #[test]
+    fn inline_simple_type_names() {
+        let status = r#"<xsd:element name="Status">
+            <xsd:simpleType>
+              <xsd:restriction base="xsd:string">
+                <xsd:enumeration value="Active"/>
+              </xsd:restriction>
+            </xsd:simpleType>
+          </xsd:element>"#;
+        let declared = r#"
+      <xsd:complexType name="AccountStatus">
+        <xsd:sequence>
+          <xsd:element name="code" type="xsd:int"/>
+        </xsd:sequence>
+      </xsd:complexType>"#;
+        let account = format!(
+            r#"
+      <xsd:complexType name="Account">
+        <xsd:sequence>
+          {}
+        </xsd:sequence>
+      </xsd:complexType>"#,
+            status
+        );
+
+        // whether the type of the same name is declared before or after
+        for schema in &[
+            format!("{}{}", declared, account),
+            format!("{}{}", account, declared),
+        ] {
+            let wsdl =
+                parse(document(schema, r#"<portType name="TestPortType"/>"#).as_bytes()).unwrap();
+            assert!(
+                matches!(&wsdl.types["AccountStatus"], Type::Complex(c) if c.fields.contains_key("code"))
+            );
+            assert_eq!(
+                wsdl.types["AccountStatus2"],
+                Type::Enumeration(vec!["Active".to_string()])
+            );
+            match &wsdl.types["Account"] {
+                Type::Complex(c) => assert_eq!(
+                    c.fields["Status"].1,
+                    SimpleType::Complex("AccountStatus2".to_string())
+                ),
+                other => panic!("expected a complex type, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn repeated_compositors() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Ledger">
+        <xsd:sequence>
+          <xsd:element name="owner" type="xsd:string"/>
+          <xsd:sequence maxOccurs="unbounded">
+            <xsd:element name="date" type="xsd:date"/>
+            <xsd:element name="amount" type="xsd:int"/>
+          </xsd:sequence>
+          <xsd:choice maxOccurs="2">
+            <xsd:element name="note" type="xsd:string" maxOccurs="3"/>
+          </xsd:choice>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let fields = match &wsdl.types["Ledger"] {
+            Type::Complex(c) => &c.fields,
+            other => panic!("expected a complex type, got {:?}", other),
+        };
+        assert!(!fields["owner"].0.is_repeated());
+        assert_eq!(fields["owner"].0.group_max_occurs, None);
+        for name in &["date", "amount"] {
+            let attributes = &fields[*name].0;
+            assert!(attributes.is_repeated());
+            assert_eq!(attributes.max_occurs, Some(Occurence::Unbounded));
+            assert_eq!(attributes.group_max_occurs, Some(Occurence::Unbounded));
+        }
+        assert_eq!(fields["note"].0.max_occurs, Some(Occurence::Num(6)));
+        assert_eq!(fields["note"].0.group_max_occurs, Some(Occurence::Num(2)));
+    }
+
+    #[test]
+    fn occurence_limits() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Grid">
+        <xsd:sequence maxOccurs="100000">
+          <xsd:element name="cell" type="xsd:int" maxOccurs="100000"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+        match &wsdl.types["Grid"] {
+            Type::Complex(c) => {
+                assert_eq!(c.fields["cell"].0.max_occurs, Some(Occurence::Unbounded))
+            }
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+
+        let source = document(
+            r#"
+      <xsd:complexType name="Grid">
+        <xsd:sequence>
+          <xsd:element name="cell" type="xsd:int" maxOccurs="many"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+            r#"<portType name="TestPortType"/>"#,
+        );
+        assert!(matches!(
+            parse(source.as_bytes()),
+            Err(WsdlError::InvalidOccurence(n)) if n == "many"
+        ));
+    }
+
+    #[test]
+    fn element_form() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Account">
+        <xsd:sequence>
+          <xsd:element name="id" type="xsd:int" form="qualified"/>
+          <xsd:element name="label" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        match &wsdl.types["Account"] {
+            Type::Complex(c) => {
+                assert_eq!(c.element_form_default, ElementForm::Unqualified);
+                assert_eq!(c.fields["id"].0.form, Some(ElementForm::Qualified));
+                assert_eq!(c.field_form("id"), ElementForm::Qualified);
+                assert_eq!(c.field_form("label"), ElementForm::Unqualified);
+            }
+            t => panic!("expected a complex type, got {:?}", t),
+        }
+    }
+
+    #[test]
+    fn decimal_digits() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:simpleType name="Amount">
+        <xsd:restriction base="xsd:decimal">
+          <xsd:totalDigits value="10"/>
+          <xsd:fractionDigits value="2"/>
+        </xsd:restriction>
+      </xsd:simpleType>
+      <xsd:simpleType name="Rate">
+        <xsd:restriction base="xsd:decimal"/>
+      </xsd:simpleType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            wsdl.types["Amount"],
+            Type::Decimal {
+                total_digits: Some(10),
+                fraction_digits: Some(2),
+            }
+        );
+        assert_eq!(wsdl.types["Rate"], Type::Simple(SimpleType::Decimal));
+    }
+
+    #[test]
+    fn interface_description() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        wsdl.types.insert(
+            "Exchange".to_string(),
+            Type::Enumeration(vec!["NYSE".to_string(), "LSE".to_string()]),
+        );
+
+        assert_eq!(
+            wsdl.to_interface_description(),
+            r#"service StockQuoteService {
+  GetLastTradePrice(input: TradePriceRequest): TradePrice;
+}
+
+type Exchange = "NYSE" | "LSE";
+
+type TradePrice {
+  price: float;
+}
+
+type TradePriceRequest {
+  tickerSymbol: string;
+}
+"#
+        );
+    }
+
+    #[test]
+    fn interface_description_types() {
+        let mut wsdl = parse(EXAMPLE_WSDL).unwrap();
+        wsdl.types.clear();
+        wsdl.types
+            .insert("Digest".to_string(), Type::Simple(SimpleType::HexBinary));
+        wsdl.types.insert(
+            "Amount".to_string(),
+            Type::Decimal {
+                total_digits: None,
+                fraction_digits: Some(2),
+            },
+        );
+        wsdl.types.insert(
+            "Quote".to_string(),
+            Type::Enumeration(vec![r#"say "hi""#.to_string()]),
+        );
+
+        let description = wsdl.to_interface_description();
+        assert!(description.ends_with(
+            r#"
+type Amount = decimal;
+
+type Digest = hexBinary;
+
+type Quote = "say \"hi\"";
+"#
+        ));
+    }
+
+    #[test]
+    fn render_as_markdown() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert_eq!(
+            wsdl.render_as_markdown(),
+            "# StockQuoteService\n\n\
+             - Namespace: `http://example.com/stockquote.wsdl`\n\
+             - Endpoint: http://example.com/stockquote\n\
+             \n## Operations\n\n\
+             | Operation | Input | Output | Faults |\n\
+             | --- | --- | --- | --- |\n\
+             | GetLastTradePrice | TradePriceRequest | TradePrice |  |\n\
+             \n## Types\n\
+             \n### TradePrice\n\n\
+             | Field | Type | Description |\n\
+             | --- | --- | --- |\n\
+             | price | `float` |  |\n\
+             \n### TradePriceRequest\n\n\
+             | Field | Type | Description |\n\
+             | --- | --- | --- |\n\
+             | tickerSymbol | `string` |  |\n"
+        );
+    }
+
+    #[test]
+    fn render_rust_struct() {
+        struct Prefixed;
+        impl NameMapper for Prefixed {
+            fn type_name(&self, name: &str) -> String {
+                format!("Api{}", name)
+            }
+            fn field_name(&self, name: &str) -> String {
+                name.to_snake()
+            }
+        }
+
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Order">
+        <xsd:sequence>
+          <xsd:element name="orderId" type="xsd:int">
+            <xsd:annotation><xsd:documentation>Order number.</xsd:documentation></xsd:annotation>
+          </xsd:element>
+          <xsd:element name="note" type="xsd:string" minOccurs="0"/>
+          <xsd:element name="lines" type="tns:Line" maxOccurs="unbounded"/>
+        </xsd:sequence>
+      </xsd:complexType>
+      <xsd:complexType name="Line">
+        <xsd:sequence>
+          <xsd:element name="sku" type="xsd:string"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let rendered = wsdl
+            .render_rust_struct("Order", &DefaultNameMapper)
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "#[derive(Clone, Debug, Default)]
+pub struct Order {
+    pub lines: Vec<Line>,
+    pub note: Option<String>,
+    /// Order number.
+    pub order_id: i64,
+}
+"
+        );
+        let rendered = wsdl.render_rust_struct("Order", &Prefixed).unwrap();
+        assert!(rendered.contains("pub struct ApiOrder {"));
+        assert!(rendered.contains("pub lines: Vec<ApiLine>,"));
+        assert_eq!(wsdl.render_rust_struct("Missing", &DefaultNameMapper), None);
+    }
+
+    #[test]
+    fn render_rust_struct_builtin_types() {
+        let wsdl = parse(
+            document(
+                r#"
+      <xsd:complexType name="Payment">
+        <xsd:sequence>
+          <xsd:element name="amount" type="xsd:decimal"/>
+          <xsd:element name="kind" type="xsd:QName"/>
+          <xsd:element name="at" type="xsd:dateTime"/>
+        </xsd:sequence>
+      </xsd:complexType>"#,
+                r#"<portType name="TestPortType"/>"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let rendered = wsdl
+            .render_rust_struct("Payment", &DefaultNameMapper)
+            .unwrap();
+        assert!(rendered.contains("pub amount: savon::gen::Decimal,"));
+        assert!(rendered.contains("pub kind: savon::gen::QName,"));
+        assert!(rendered.contains(
+            "pub at: savon::internal::chrono::DateTime<savon::internal::chrono::FixedOffset>,"
+        ));
+    }
+
+    #[test]
+    fn schema_namespace() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        assert_eq!(wsdl.target_namespace, "http://example.com/stockquote.wsdl");
+        assert_eq!(
+            wsdl.type_namespace("TradePrice"),
+            "http://example.com/stockquote.xsd"
+        );
+
+        let source = document(
+            r#"<xsd:element name="Ping" type="xsd:string"/>"#,
+            r#"<portType name="TestPortType"/>"#,
+        )
+        .replace(r#" targetNamespace="http://example.com/test.wsdl">"#, ">");
+        let wsdl = parse(source.as_bytes()).unwrap();
+        assert!(wsdl.type_namespaces.is_empty());
+        assert_eq!(wsdl.type_namespace("Ping"), "http://example.com/test.wsdl");
+    }
+
+    #[test]
+    fn parse_imports() {
+        let wsdl = parse_with_resolver(IMPORT_WSDL, |location| {
+            assert_eq!(location, "schemas/inventory.xsd");
+            Some(INVENTORY_XSD.to_vec())
+        })
+        .unwrap();
+        assert!(wsdl.types.contains_key("StockRequest"));
+        assert!(wsdl.types.contains_key("StockLevel"));
+
+        match parse_with_resolver(IMPORT_WSDL, |_| None) {
+            Err(WsdlError::ImportNotFound(location)) => {
+                assert_eq!(location, "schemas/inventory.xsd")
+            }
+            other => panic!("expected a missing import, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn schema_cache() {
+        fn assert_shareable<T: Send + Sync>() {}
+        assert_shareable::<SchemaCache>();
+
+        let cache = SchemaCache::new();
+        let resolved = std::cell::Cell::new(0);
+        let resolver = |location: &str| {
+            assert_eq!(location, "schemas/inventory.xsd");
+            resolved.set(resolved.get() + 1);
+            Some(INVENTORY_XSD.to_vec())
+        };
+        let other = std::str::from_utf8(IMPORT_WSDL)
+            .unwrap()
+            .replace("InventoryService", "WarehouseService");
+
+        let first = parse_with_cache(IMPORT_WSDL, resolver, &cache).unwrap();
+        let second = parse_with_cache(other.as_bytes(), resolver, &cache).unwrap();
+        assert_eq!(resolved.get(), 1);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(first.types, second.types);
+        assert_ne!(first.name, second.name);
+    }
+
+    #[test]
+    fn schema_cache_includes() {
+        let cache = SchemaCache::new();
+        let resolver = |_: &str| Some(COMMON_TYPES_XSD.to_vec());
+        let first = parse_with_cache(INCLUDE_WSDL, resolver, &cache).unwrap();
+        // the cached definitions still take the namespace of the including schema
+        let second = parse_with_cache(INCLUDE_WSDL, |_| None, &cache).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(first.types, second.types);
+        assert_eq!(
+            second.type_namespace("Address"),
+            "http://example.com/customers.wsdl"
+        );
+        assert_eq!(second.source_location("Address"), Some("common-types.xsd"));
+    }
+
+    #[test]
+    fn parse_includes() {
+        let wsdl = parse_with_resolver(INCLUDE_WSDL, |location| {
+            assert_eq!(location, "common-types.xsd");
+            Some(COMMON_TYPES_XSD.to_vec())
+        })
+        .unwrap();
+        match &wsdl.types["Address"] {
+            Type::Complex(c) => assert_eq!(c.fields.len(), 2),
+            other => panic!("expected a complex type, got {:?}", other),
+        }
+        assert_eq!(
+            wsdl.type_namespace("Address"),
+            "http://example.com/customers.wsdl"
+        );
+        assert_eq!(
+            wsdl.type_namespace("Customer"),
+            "http://example.com/customers.wsdl"
+        );
+
+        match parse_with_resolver(INCLUDE_WSDL, |_| None) {
+            Err(WsdlError::ImportNotFound(location)) => assert_eq!(location, "common-types.xsd"),
+            other => panic!("expected a missing include, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn type_source_locations() {
+        let wsdl = parse_with_resolver(IMPORT_WSDL, |_| Some(INVENTORY_XSD.to_vec())).unwrap();
+        assert_eq!(
+            wsdl.source_location("StockLevel"),
+            Some("schemas/inventory.xsd")
+        );
+        assert_eq!(
+            wsdl.type_locations.get("StockLevel").map(String::as_str),
+            Some("schemas/inventory.xsd")
+        );
+
+        let wsdl = parse_with_resolver(INCLUDE_WSDL, |_| Some(COMMON_TYPES_XSD.to_vec())).unwrap();
+        assert_eq!(wsdl.source_location("Address"), Some("common-types.xsd"));
+        assert_eq!(wsdl.source_location("Customer"), None);
+        assert!(!wsdl.type_locations.contains_key("Customer"));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn parse_async_imports() {
+        let documents: HashMap<&str, &[u8]> = vec![("schemas/inventory.xsd", INVENTORY_XSD)]
+            .into_iter()
+            .collect();
+        let resolver = |location: &str| {
+            let document = documents.get(location).map(|d| d.to_vec());
+            async move { document }
+        };
+        let wsdl = parse_with_async_resolver(IMPORT_WSDL, resolver, &ParseOptions::default())
+            .await
+            .unwrap();
+        assert!(wsdl.types.contains_key("StockRequest"));
+        assert!(wsdl.types.contains_key("StockLevel"));
+
+        let options = ParseOptions {
+            normalize_operation_case: true,
+            ..Default::default()
+        };
+        let wsdl = parse_with_async_resolver(IMPORT_WSDL, resolver, &options)
+            .await
+            .unwrap();
+        assert!(wsdl.normalize_operation_case);
+
+        let options = ParseOptions::default();
+        match parse_with_async_resolver(IMPORT_WSDL, |_| async { None }, &options).await {
+            Err(WsdlError::ImportNotFound(location)) => {
+                assert_eq!(location, "schemas/inventory.xsd")
+            }
+            other => panic!("expected a missing import, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn parse_http_imports() {
+        use crate::http::tests::mock_server;
+
+        let (url, server) = mock_server(include_str!("../assets/inventory.xsd"));
+        let wsdl = parse_with_http_imports(IMPORT_WSDL, &url, &ParseOptions::default())
+            .await
+            .unwrap();
+        assert!(wsdl.types.contains_key("StockLevel"));
+        assert!(server
+            .join()
+            .unwrap()
+            .starts_with("GET /schemas/inventory.xsd "));
+
+        // accepts the connection but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let options = ParseOptions {
+            import_timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        match parse_with_http_imports(IMPORT_WSDL, &url, &options).await {
+            Err(WsdlError::ImportTimeout(import)) => {
+                assert_eq!(import, format!("{}schemas/inventory.xsd", url))
+            }
+            other => panic!("expected an import timeout, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn parse_nested_http_imports() {
+        use std::io::{Read, Write};
+
+        let outer = r#"<xsd:schema xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+            targetNamespace="http://example.com/outer">
+  <xsd:import namespace="http://example.com/inner" schemaLocation="inner.xsd"/>
+  <xsd:complexType name="Outer">
+    <xsd:sequence><xsd:element name="id" type="xsd:int"/></xsd:sequence>
+  </xsd:complexType>
+</xsd:schema>"#;
+        let inner = r#"<xsd:schema xmlns:xsd="http://www.w3.org/2001/XMLSchema"
+            targetNamespace="http://example.com/inner">
+  <xsd:complexType name="Inner">
+    <xsd:sequence><xsd:element name="id" type="xsd:int"/></xsd:sequence>
+  </xsd:complexType>
+</xsd:schema>"#;
+        // serves the schemas under /wsdl/schemas/, closing each connection
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/wsdl/service.wsdl",
+            listener.local_addr().unwrap()
+        );
+        let server = std::thread::spawn(move || {
+            let mut paths = Vec::new();
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0; 4096];
+                let n = stream.read(&mut buf).unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let path = request.split_whitespace().nth(1).unwrap().to_string();
+                let (status, body) = match path.as_str() {
+                    "/wsdl/schemas/outer.xsd" => ("200 OK", outer),
+                    "/wsdl/schemas/inner.xsd" => ("200 OK", inner),
+                    _ => ("404 Not Found", ""),
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+                paths.push(path);
+            }
+            paths
+        });
+
+        let source = document(
+            r#"<xsd:import namespace="http://example.com/outer" schemaLocation="schemas/outer.xsd"/>"#,
+            r#"<portType name="TestPortType"/>"#,
+        );
+        let wsdl = parse_with_http_imports(source.as_bytes(), &url, &ParseOptions::default())
+            .await
+            .unwrap();
+        assert!(wsdl.types.contains_key("Outer"));
+        assert!(wsdl.types.contains_key("Inner"));
+        assert_eq!(
+            server.join().unwrap(),
+            vec!["/wsdl/schemas/outer.xsd", "/wsdl/schemas/inner.xsd"]
+        );
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn parse_zip() {
+        use std::io::{Cursor, Write};
+
+        let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::default();
+        archive
+            .start_file("inventory/schemas/inventory.xsd", options)
+            .unwrap();
+        archive.write_all(INVENTORY_XSD).unwrap();
+        archive
+            .start_file("inventory/inventory.wsdl", options)
+            .unwrap();
+        archive.write_all(IMPORT_WSDL).unwrap();
+        let bytes = archive.finish().unwrap().into_inner();
+
+        let wsdl = parse_from_zip(Cursor::new(bytes)).unwrap();
+        assert_eq!(wsdl.name, "InventoryService");
+        assert!(wsdl.types.contains_key("StockLevel"));
     }
 }
@@ -0,0 +1,212 @@
+//! Builders of operations and messages, for documents constructed in code
+//! rather than parsed.
+//!
+//! The name of a builder is tracked in its type: `build` is only available
+//! once `name` has been called.
+//!
+//! ```
+//! use savon::wsdl::{MessageBuilder, OperationBuilder, Wsdl};
+//!
+//! let mut wsdl = Wsdl::default();
+//! let (name, message) = MessageBuilder::new()
+//!     .name("GetHistoryInput")
+//!     .part("body", "History")
+//!     .build();
+//! wsdl.add_message(&name, message).unwrap();
+//! wsdl.add_operation(
+//!     OperationBuilder::new()
+//!         .name("GetHistory")
+//!         .input("GetHistoryInput")
+//!         .build(),
+//! )
+//! .unwrap();
+//! ```
+
+use super::{FaultRef, Message, Operation, Part};
+use std::collections::HashMap;
+
+/// State of a builder whose name is not set.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Unnamed;
+
+/// State of a builder whose name is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Named(String);
+
+/// Builds an `Operation` of a port type.
+#[derive(Debug, Clone, Default)]
+pub struct OperationBuilder<N = Unnamed> {
+    name: N,
+    input: Option<String>,
+    output: Option<String>,
+    faults: Vec<FaultRef>,
+    documentation: Option<String>,
+}
+
+impl OperationBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<N> OperationBuilder<N> {
+    pub fn name(self, name: &str) -> OperationBuilder<Named> {
+        OperationBuilder {
+            name: Named(name.to_string()),
+            input: self.input,
+            output: self.output,
+            faults: self.faults,
+            documentation: self.documentation,
+        }
+    }
+
+    /// Name of the input message.
+    pub fn input(mut self, message: &str) -> Self {
+        self.input = Some(message.to_string());
+        self
+    }
+
+    /// Name of the output message, left unset for one-way operations.
+    pub fn output(mut self, message: &str) -> Self {
+        self.output = Some(message.to_string());
+        self
+    }
+
+    /// Declares the fault `name` with the message `message`.
+    pub fn fault(mut self, name: &str, message: &str) -> Self {
+        self.faults.push(FaultRef {
+            name: name.to_string(),
+            message: message.to_string(),
+        });
+        self
+    }
+
+    pub fn documentation(mut self, documentation: &str) -> Self {
+        self.documentation = Some(documentation.to_string());
+        self
+    }
+}
+
+impl OperationBuilder<Named> {
+    pub fn build(self) -> Operation {
+        Operation {
+            name: self.name.0,
+            input: self.input,
+            output: self.output,
+            faults: Some(self.faults).filter(|f| !f.is_empty()),
+            input_name: None,
+            output_name: None,
+            soap_action: None,
+            documentation: self.documentation,
+            parameter_order: None,
+            extensions: HashMap::new(),
+        }
+    }
+}
+
+/// Builds a `Message` along with its name.
+#[derive(Debug, Clone, Default)]
+pub struct MessageBuilder<N = Unnamed> {
+    name: N,
+    parts: Vec<Part>,
+}
+
+impl MessageBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<N> MessageBuilder<N> {
+    pub fn name(self, name: &str) -> MessageBuilder<Named> {
+        MessageBuilder {
+            name: Named(name.to_string()),
+            parts: self.parts,
+        }
+    }
+
+    /// Adds a part holding `element`, or a type for RPC style messages.
+    pub fn part(mut self, name: &str, element: &str) -> Self {
+        self.parts.push(Part {
+            name: name.to_string(),
+            element: element.to_string(),
+        });
+        self
+    }
+}
+
+impl MessageBuilder<Named> {
+    /// Returns the name of the message, to add it with `Wsdl::add_message`,
+    /// and the message. The first part is the main part of the message, left
+    /// empty if there is no part.
+    pub fn build(self) -> (String, Message) {
+        let first = self.parts.first().cloned().unwrap_or(Part {
+            name: String::new(),
+            element: String::new(),
+        });
+
+        (
+            self.name.0,
+            Message {
+                part_name: first.name,
+                part_element: first.element,
+                part_prefix: None,
+                parts: self.parts,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wsdl::{Type, Wsdl};
+
+    #[test]
+    fn builders() {
+        let operation = OperationBuilder::new()
+            .input("PlaceOrderInput")
+            .output("PlaceOrderOutput")
+            .fault("out_of_stock", "OutOfStock")
+            .name("PlaceOrder")
+            .documentation("Places an order.")
+            .build();
+        assert_eq!(operation.name, "PlaceOrder");
+        assert_eq!(operation.input.as_deref(), Some("PlaceOrderInput"));
+        assert_eq!(operation.output.as_deref(), Some("PlaceOrderOutput"));
+        assert_eq!(
+            operation.faults,
+            Some(vec![FaultRef {
+                name: "out_of_stock".to_string(),
+                message: "OutOfStock".to_string(),
+            }])
+        );
+        assert!(operation.has_fault("tns:OutOfStock"));
+        assert_eq!(operation.fault_names(), vec!["out_of_stock"]);
+        assert!(OperationBuilder::new()
+            .name("Ping")
+            .build()
+            .faults
+            .is_none());
+
+        let (name, message) = MessageBuilder::new()
+            .name("PlaceOrderInput")
+            .part("order", "Order")
+            .part("priority", "int")
+            .build();
+        assert_eq!(name, "PlaceOrderInput");
+        assert_eq!(message.part_name, "order");
+        assert_eq!(message.part_element, "Order");
+        assert_eq!(message.parts.len(), 2);
+
+        let mut wsdl = Wsdl::default();
+        wsdl.add_type("Order", Type::Alias("string".to_string()))
+            .unwrap();
+        wsdl.add_message(&name, message).unwrap();
+        wsdl.add_operation(operation).unwrap();
+        assert_eq!(
+            wsdl.operation_signature("PlaceOrder").unwrap().input_type,
+            Some("PlaceOrderInput".to_string())
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! Parses the WSDL documents of `tests/fixtures`, modeled on public services,
+//! and checks what is found in each of them.
+
+use savon::wsdl::parse;
+use std::path::Path;
+
+/// File name, service name, and counts of operations, types and messages.
+const FIXTURES: &[(&str, &str, usize, usize, usize)] = &[
+    ("calculator.wsdl", "Calculator", 4, 8, 8),
+    ("country-info.wsdl", "CountryInfoService", 21, 54, 42),
+    ("currency-converter.wsdl", "CurrencyConvertor", 1, 4, 2),
+    ("holidays.wsdl", "HolidayService2", 3, 12, 6),
+    ("isbn.wsdl", "ISBNService", 2, 4, 4),
+    ("notifications.wsdl", "NotificationService", 3, 5, 4),
+    ("number-conversion.wsdl", "NumberConversion", 2, 4, 4),
+    ("stock-quote.wsdl", "StockQuoteService", 2, 7, 5),
+    ("temperature-conversion.wsdl", "TempConvert", 2, 5, 4),
+    ("weather.wsdl", "Weather", 3, 14, 6),
+    ("zip-code.wsdl", "ZipCodeService", 2, 6, 4),
+];
+
+fn fixtures_dir() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures"))
+}
+
+#[test]
+fn parse_fixtures() {
+    for (file, name, operations, types, messages) in FIXTURES {
+        let bytes = std::fs::read(fixtures_dir().join(file)).unwrap();
+        let wsdl = parse(&bytes).unwrap_or_else(|e| panic!("cannot parse {}: {:?}", file, e));
+
+        assert_eq!(wsdl.name, *name, "service of {}", file);
+        assert_eq!(
+            wsdl.operation_count(),
+            *operations,
+            "operations of {}",
+            file
+        );
+        assert_eq!(wsdl.type_count(), *types, "types of {}", file);
+        assert_eq!(wsdl.message_count(), *messages, "messages of {}", file);
+        assert!(
+            savon::gen::gen(&wsdl).is_ok(),
+            "cannot generate the code of {}",
+            file
+        );
+    }
+}
+
+#[test]
+fn every_fixture_is_checked() {
+    let mut files = std::fs::read_dir(fixtures_dir())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    files.sort();
+    let checked = FIXTURES.iter().map(|f| f.0).collect::<Vec<_>>();
+    assert_eq!(files, checked);
+}